@@ -3,7 +3,7 @@
 extern crate test;
 
 use sysinfo::get_current_pid;
-use sysinfo::{DiskExt, SystemExt};
+use sysinfo::{CpuRefreshKind, DiskExt, RefreshKind, SystemExt};
 
 #[bench]
 fn bench_new(b: &mut test::Bencher) {
@@ -56,6 +56,30 @@ fn bench_first_refresh_processes(b: &mut test::Bencher) {
     });
 }
 
+// Refreshes a fake `/proc` with a few thousand processes. Run it with and without the
+// "multithread" feature to compare the parallel and the sequential `/proc` reads.
+#[cfg(target_os = "linux")]
+#[bench]
+fn bench_refresh_many_processes(b: &mut test::Bencher) {
+    let dir = tempfile::tempdir().expect("failed to create temporary directory");
+    let proc_root = dir.path().join("proc");
+    for pid in 1..=3_000 {
+        let process_dir = proc_root.join(pid.to_string());
+        std::fs::create_dir_all(&process_dir).expect("failed to create process directory");
+        let mut stat = format!("{} (fake) S 1 {} {} 0 -1 0", pid, pid, pid);
+        for _ in 0..42 {
+            stat.push_str(" 0");
+        }
+        std::fs::write(process_dir.join("stat"), &stat).expect("failed to write stat");
+    }
+    let mut s = sysinfo::System::new().with_proc_root(proc_root);
+
+    s.refresh_processes(); // to load the whole processes list a first time.
+    b.iter(move || {
+        s.refresh_processes();
+    });
+}
+
 #[bench]
 fn bench_refresh_process(b: &mut test::Bencher) {
     let mut s = sysinfo::System::new();
@@ -133,6 +157,20 @@ fn bench_refresh_cpu(b: &mut test::Bencher) {
     });
 }
 
+// Compare it with `bench_refresh_memory` and `bench_refresh_cpu`: refreshing both at once shouldn't
+// cost more than refreshing them separately.
+#[bench]
+fn bench_refresh_memory_and_cpu(b: &mut test::Bencher) {
+    let mut s = sysinfo::System::new();
+    let refreshes = RefreshKind::new()
+        .with_memory()
+        .with_cpu(CpuRefreshKind::everything());
+
+    b.iter(move || {
+        s.refresh_specifics(refreshes);
+    });
+}
+
 #[bench]
 fn bench_refresh_components(b: &mut test::Bencher) {
     let mut s = sysinfo::System::new_all();
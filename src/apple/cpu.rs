@@ -1,13 +1,13 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
 use crate::sys::system::get_sys_value;
+use crate::utils::CpuUsageHistory;
 
 use crate::{CpuExt, CpuRefreshKind};
 
 use libc::{c_char, host_processor_info, mach_task_self};
 use std::mem;
 use std::ops::Deref;
-use std::sync::Arc;
 
 pub(crate) struct UnsafePtr<T>(*mut T);
 
@@ -22,6 +22,8 @@ impl<T> Deref for UnsafePtr<T> {
     }
 }
 
+/// Load info returned by `host_processor_info` for all CPUs. The buffer is kept until the next
+/// refresh so it can be used as the "old value" and is released with `vm_deallocate` on drop.
 pub(crate) struct CpuData {
     pub cpu_info: UnsafePtr<i32>,
     pub num_cpu_info: u32,
@@ -34,6 +36,10 @@ impl CpuData {
             num_cpu_info,
         }
     }
+
+    pub fn empty() -> CpuData {
+        CpuData::new(std::ptr::null_mut(), 0)
+    }
 }
 
 impl Drop for CpuData {
@@ -52,45 +58,86 @@ impl Drop for CpuData {
     }
 }
 
+/// Usage (in %) of a CPU, split by state.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct CpuStatesUsage {
+    user: f32,
+    system: f32,
+    nice: f32,
+    idle: f32,
+}
+
+impl CpuStatesUsage {
+    pub(crate) fn add(&mut self, other: &CpuStatesUsage) {
+        self.user += other.user;
+        self.system += other.system;
+        self.nice += other.nice;
+        self.idle += other.idle;
+    }
+
+    pub(crate) fn average(&self, count: usize) -> CpuStatesUsage {
+        let count = count as f32;
+        CpuStatesUsage {
+            user: self.user / count,
+            system: self.system / count,
+            nice: self.nice / count,
+            idle: self.idle / count,
+        }
+    }
+}
+
 #[doc = include_str!("../../md_doc/cpu.md")]
 pub struct Cpu {
     name: String,
     cpu_usage: f32,
-    cpu_data: Arc<CpuData>,
+    states_usage: CpuStatesUsage,
     frequency: u64,
     vendor_id: String,
     brand: String,
+    pub(crate) usage_history: CpuUsageHistory,
 }
 
 impl Cpu {
-    pub(crate) fn new(
-        name: String,
-        cpu_data: Arc<CpuData>,
-        frequency: u64,
-        vendor_id: String,
-        brand: String,
-    ) -> Cpu {
+    pub(crate) fn new(name: String, frequency: u64, vendor_id: String, brand: String) -> Cpu {
         Cpu {
             name,
             cpu_usage: 0f32,
-            cpu_data,
+            states_usage: CpuStatesUsage::default(),
             frequency,
             vendor_id,
             brand,
+            usage_history: CpuUsageHistory::default(),
         }
     }
 
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::CpuSnapshot) -> Cpu {
+        let mut cpu = Cpu::new(
+            snapshot.name.clone(),
+            snapshot.frequency,
+            snapshot.vendor_id.clone(),
+            snapshot.brand.clone(),
+        );
+        cpu.cpu_usage = snapshot.cpu_usage;
+        cpu
+    }
+
     pub(crate) fn set_cpu_usage(&mut self, cpu_usage: f32) {
         self.cpu_usage = cpu_usage;
+        self.usage_history.push(cpu_usage);
     }
 
-    pub(crate) fn update(&mut self, cpu_usage: f32, cpu_data: Arc<CpuData>) {
-        self.cpu_usage = cpu_usage;
-        self.cpu_data = cpu_data;
+    pub(crate) fn set_states_usage(&mut self, states_usage: CpuStatesUsage) {
+        self.states_usage = states_usage;
     }
 
-    pub(crate) fn data(&self) -> Arc<CpuData> {
-        Arc::clone(&self.cpu_data)
+    pub(crate) fn states_usage(&self) -> &CpuStatesUsage {
+        &self.states_usage
+    }
+
+    pub(crate) fn update(&mut self, cpu_usage: f32, states_usage: CpuStatesUsage) {
+        self.set_cpu_usage(cpu_usage);
+        self.set_states_usage(states_usage);
     }
 
     pub(crate) fn set_frequency(&mut self, frequency: u64) {
@@ -103,6 +150,26 @@ impl CpuExt for Cpu {
         self.cpu_usage
     }
 
+    fn user_usage(&self) -> f32 {
+        self.states_usage.user
+    }
+
+    fn system_usage(&self) -> f32 {
+        self.states_usage.system
+    }
+
+    fn nice_usage(&self) -> f32 {
+        self.states_usage.nice
+    }
+
+    fn idle_usage(&self) -> f32 {
+        self.states_usage.idle
+    }
+
+    fn usage_history(&self) -> &[f32] {
+        self.usage_history.values()
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -137,38 +204,45 @@ pub(crate) fn get_cpu_frequency() -> u64 {
 }
 
 #[inline]
-fn get_in_use(cpu_info: *mut i32, offset: isize) -> i32 {
-    unsafe {
-        *cpu_info.offset(offset + libc::CPU_STATE_USER as isize)
-            + *cpu_info.offset(offset + libc::CPU_STATE_SYSTEM as isize)
-            + *cpu_info.offset(offset + libc::CPU_STATE_NICE as isize)
-    }
-}
-
-#[inline]
-fn get_idle(cpu_info: *mut i32, offset: isize) -> i32 {
-    unsafe { *cpu_info.offset(offset + libc::CPU_STATE_IDLE as isize) }
+fn get_state(cpu_info: *mut i32, offset: isize, state: libc::c_int) -> i32 {
+    unsafe { *cpu_info.offset(offset + state as isize) }
 }
 
-pub(crate) fn compute_usage_of_cpu(proc_: &Cpu, cpu_info: *mut i32, offset: isize) -> f32 {
-    let old_cpu_info = proc_.data().cpu_info.0;
-    let in_use;
-    let total;
-
-    // In case we are initializing cpus, there is no "old value" yet.
-    if old_cpu_info == cpu_info {
-        in_use = get_in_use(cpu_info, offset);
-        total = in_use + get_idle(cpu_info, offset);
-    } else {
-        in_use = get_in_use(cpu_info, offset) - get_in_use(old_cpu_info, offset);
-        total = in_use + (get_idle(cpu_info, offset) - get_idle(old_cpu_info, offset));
-    }
-    in_use as f32 / total as f32 * 100.
+pub(crate) fn compute_usage_of_cpu(
+    old_cpu_info: *mut i32,
+    cpu_info: *mut i32,
+    offset: isize,
+) -> (f32, CpuStatesUsage) {
+    let get_time = |state| {
+        // In case we are initializing cpus, there is no "old value" yet.
+        if old_cpu_info.is_null() || old_cpu_info == cpu_info {
+            get_state(cpu_info, offset, state)
+        } else {
+            get_state(cpu_info, offset, state) - get_state(old_cpu_info, offset, state)
+        }
+    };
+    let user = get_time(libc::CPU_STATE_USER);
+    let system = get_time(libc::CPU_STATE_SYSTEM);
+    let nice = get_time(libc::CPU_STATE_NICE);
+    let idle = get_time(libc::CPU_STATE_IDLE);
+    let in_use = user + system + nice;
+    let total = (in_use + idle) as f32;
+
+    (
+        in_use as f32 / total * 100.,
+        CpuStatesUsage {
+            user: user as f32 / total * 100.,
+            system: system as f32 / total * 100.,
+            nice: nice as f32 / total * 100.,
+            idle: idle as f32 / total * 100.,
+        },
+    )
 }
 
-pub(crate) fn update_cpu_usage<F: FnOnce(Arc<CpuData>, *mut i32) -> (f32, usize)>(
+pub(crate) fn update_cpu_usage<F: FnOnce(*mut i32, *mut i32) -> (f32, CpuStatesUsage, usize)>(
     port: libc::mach_port_t,
     global_cpu: &mut Cpu,
+    cpu_data: &mut CpuData,
     f: F,
 ) {
     let mut num_cpu_u = 0u32;
@@ -176,6 +250,7 @@ pub(crate) fn update_cpu_usage<F: FnOnce(Arc<CpuData>, *mut i32) -> (f32, usize)
     let mut num_cpu_info = 0u32;
 
     let mut total_cpu_usage = 0f32;
+    let mut total_states_usage = CpuStatesUsage::default();
 
     unsafe {
         if host_processor_info(
@@ -186,11 +261,14 @@ pub(crate) fn update_cpu_usage<F: FnOnce(Arc<CpuData>, *mut i32) -> (f32, usize)
             &mut num_cpu_info as *mut u32,
         ) == libc::KERN_SUCCESS
         {
-            let (total_percentage, len) =
-                f(Arc::new(CpuData::new(cpu_info, num_cpu_info)), cpu_info);
+            let (total_percentage, states_usage, len) = f(cpu_data.cpu_info.0, cpu_info);
+            // Replacing the previous load info releases its buffer.
+            *cpu_data = CpuData::new(cpu_info, num_cpu_info);
             total_cpu_usage = total_percentage / len as f32;
+            total_states_usage = states_usage.average(len);
         }
         global_cpu.set_cpu_usage(total_cpu_usage);
+        global_cpu.set_states_usage(total_states_usage);
     }
 }
 
@@ -198,6 +276,7 @@ pub(crate) fn init_cpus(
     port: libc::mach_port_t,
     cpus: &mut Vec<Cpu>,
     global_cpu: &mut Cpu,
+    cpu_data: &mut CpuData,
     refresh_kind: CpuRefreshKind,
 ) {
     let mut num_cpu = 0;
@@ -221,27 +300,30 @@ pub(crate) fn init_cpus(
             num_cpu = 1;
         }
     }
-    update_cpu_usage(port, global_cpu, |proc_data, cpu_info| {
+    update_cpu_usage(port, global_cpu, cpu_data, |old_cpu_info, cpu_info| {
         let mut percentage = 0f32;
+        let mut states_usage = CpuStatesUsage::default();
         let mut offset = 0;
         for i in 0..num_cpu {
             let mut p = Cpu::new(
                 format!("{}", i + 1),
-                Arc::clone(&proc_data),
                 frequency,
                 vendor_id.clone(),
                 brand.clone(),
             );
             if refresh_kind.cpu_usage() {
-                let cpu_usage = compute_usage_of_cpu(&p, cpu_info, offset);
+                let (cpu_usage, cpu_states_usage) =
+                    compute_usage_of_cpu(old_cpu_info, cpu_info, offset);
                 p.set_cpu_usage(cpu_usage);
+                p.set_states_usage(cpu_states_usage);
                 percentage += p.cpu_usage();
+                states_usage.add(p.states_usage());
             }
             cpus.push(p);
 
             offset += libc::CPU_STATE_MAX as isize;
         }
-        (percentage, cpus.len())
+        (percentage, states_usage, cpus.len())
     });
 
     // We didn't set them above to avoid cloning them unnecessarily.
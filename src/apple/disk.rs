@@ -4,6 +4,7 @@ use crate::sys::{
     ffi,
     utils::{self, CFReleaser},
 };
+use crate::utils::DiskListFilter;
 use crate::{DiskExt, DiskType};
 
 use core_foundation_sys::array::CFArrayCreate;
@@ -28,7 +29,14 @@ pub struct Disk {
     volume_url: RetainedCFURL,
     pub(crate) total_space: u64,
     pub(crate) available_space: u64,
+    pub(crate) free_space: u64,
+    pub(crate) total_inodes: u64,
+    pub(crate) available_inodes: u64,
     pub(crate) is_removable: bool,
+    pub(crate) mount_options: Vec<String>,
+    pub(crate) model: String,
+    pub(crate) serial: String,
+    pub(crate) last_refresh_failed: bool,
 }
 
 impl DiskExt for Disk {
@@ -56,12 +64,40 @@ impl DiskExt for Disk {
         self.available_space
     }
 
+    fn free_space(&self) -> u64 {
+        self.free_space
+    }
+
+    fn total_inodes(&self) -> u64 {
+        self.total_inodes
+    }
+
+    fn available_inodes(&self) -> u64 {
+        self.available_inodes
+    }
+
     fn is_removable(&self) -> bool {
         self.is_removable
     }
 
+    fn mount_options(&self) -> &[String] {
+        &self.mount_options
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    fn last_refresh_failed(&self) -> bool {
+        self.last_refresh_failed
+    }
+
     fn refresh(&mut self) -> bool {
-        unsafe {
+        let refreshed = unsafe {
             if let Some(requested_properties) = build_requested_properties(&[
                 ffi::kCFURLVolumeAvailableCapacityKey,
                 ffi::kCFURLVolumeAvailableCapacityForImportantUsageKey,
@@ -69,6 +105,12 @@ impl DiskExt for Disk {
                 match get_disk_properties(&self.volume_url, &requested_properties) {
                     Some(disk_props) => {
                         self.available_space = get_available_volume_space(&disk_props);
+                        if let Some(stat) = get_statfs(&self.mount_point) {
+                            self.free_space =
+                                (stat.f_bfree as u64).saturating_mul(stat.f_bsize as _);
+                            self.total_inodes = stat.f_files as _;
+                            self.available_inodes = stat.f_ffree as _;
+                        }
                         true
                     }
                     None => false,
@@ -77,11 +119,13 @@ impl DiskExt for Disk {
                 sysinfo_debug!("failed to create volume key list, skipping refresh");
                 false
             }
-        }
+        };
+        self.last_refresh_failed = !refreshed;
+        refreshed
     }
 }
 
-pub(super) unsafe fn get_disks() -> Vec<Disk> {
+pub(super) unsafe fn get_disks(filter: DiskListFilter) -> Vec<Disk> {
     let raw_disks = {
         let count = libc::getfsstat(ptr::null_mut(), 0, libc::MNT_NOWAIT);
         if count < 1 {
@@ -121,6 +165,14 @@ pub(super) unsafe fn get_disks() -> Vec<Disk> {
 
     let mut disks = Vec::with_capacity(raw_disks.len());
     for c_disk in raw_disks {
+        if filter == DiskListFilter::LocalOnly {
+            let fs_type = CStr::from_ptr(c_disk.f_fstypename.as_ptr()).to_bytes();
+            if matches!(fs_type, b"autofs" | b"devfs") {
+                sysinfo_debug!("Pseudo filesystem `{:?}`, ignoring it.", fs_type);
+                continue;
+            }
+        }
+
         let volume_url = match CFReleaser::new(
             core_foundation_sys::url::CFURLCreateFromFileSystemRepresentation(
                 kCFAllocatorDefault,
@@ -157,7 +209,7 @@ pub(super) unsafe fn get_disks() -> Vec<Disk> {
         //
         // To avoid seemingly duplicating many disks and creating an inaccurate view of the system's resources,
         // these are skipped entirely.
-        if !browsable {
+        if !browsable && filter != DiskListFilter::All {
             continue;
         }
 
@@ -170,7 +222,7 @@ pub(super) unsafe fn get_disks() -> Vec<Disk> {
         // Skip any drive that is not locally attached to the system.
         //
         // This includes items like SMB mounts, and matches the other platform's behavior.
-        if !local_only {
+        if !local_only && filter != DiskListFilter::All {
             continue;
         }
 
@@ -231,6 +283,20 @@ fn get_available_volume_space(disk_props: &RetainedCFDictionary) -> u64 {
     .unwrap_or_default() as u64
 }
 
+// Returns the up-to-date `statfs` information of the volume.
+fn get_statfs(mount_point: &Path) -> Option<libc::statfs> {
+    let mut c_path = mount_point.as_os_str().as_bytes().to_vec();
+    c_path.push(0);
+    unsafe {
+        let mut stat: libc::statfs = std::mem::zeroed();
+        if libc::statfs(c_path.as_ptr() as *const _, &mut stat) == 0 {
+            Some(stat)
+        } else {
+            None
+        }
+    }
+}
+
 pub(super) enum DictKey {
     Extern(CFStringRef),
     #[cfg(target_os = "macos")]
@@ -298,11 +364,19 @@ pub(super) unsafe fn get_str_value(dict: CFDictionaryRef, key: DictKey) -> Optio
     })
 }
 
-unsafe fn get_bool_value(dict: CFDictionaryRef, key: DictKey) -> Option<bool> {
+#[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
+pub(super) unsafe fn get_array_value(
+    dict: CFDictionaryRef,
+    key: DictKey,
+) -> Option<core_foundation_sys::array::CFArrayRef> {
+    get_dict_value(dict, key, |v| Some(v.cast()))
+}
+
+pub(super) unsafe fn get_bool_value(dict: CFDictionaryRef, key: DictKey) -> Option<bool> {
     get_dict_value(dict, key, |v| Some(v as CFBooleanRef == kCFBooleanTrue))
 }
 
-unsafe fn get_int_value(dict: CFDictionaryRef, key: DictKey) -> Option<i64> {
+pub(super) unsafe fn get_int_value(dict: CFDictionaryRef, key: DictKey) -> Option<i64> {
     get_dict_value(dict, key, |v| {
         let mut val: i64 = 0;
         if CFNumberGetValue(
@@ -332,6 +406,12 @@ unsafe fn new_disk(
     #[cfg(not(target_os = "macos"))]
     let type_ = DiskType::SSD;
 
+    #[cfg(target_os = "macos")]
+    let (model, serial) =
+        crate::sys::inner::disk::get_disk_model_and_serial(&c_disk).unwrap_or_default();
+    #[cfg(not(target_os = "macos"))]
+    let (model, serial) = (String::new(), String::new());
+
     // Note: Since we requested these properties from the system, we don't expect
     // these property retrievals to fail.
 
@@ -377,6 +457,7 @@ unsafe fn new_disk(
     )? as u64;
 
     let available_space = get_available_volume_space(disk_props);
+    let free_space = (c_disk.f_bfree as u64).saturating_mul(c_disk.f_bsize as _);
 
     let file_system = IntoIterator::into_iter(c_disk.f_fstypename)
         .filter_map(|b| if b != 0 { Some(b as u8) } else { None })
@@ -390,6 +471,39 @@ unsafe fn new_disk(
         volume_url,
         total_space,
         available_space,
+        free_space,
+        total_inodes: c_disk.f_files as _,
+        available_inodes: c_disk.f_ffree as _,
         is_removable,
+        mount_options: get_mount_options(c_disk.f_flags),
+        model,
+        serial,
+        last_refresh_failed: false,
     })
 }
+
+fn get_mount_options(flags: u32) -> Vec<String> {
+    let mut options = vec![if flags & libc::MNT_RDONLY as u32 != 0 {
+        "ro".to_owned()
+    } else {
+        "rw".to_owned()
+    }];
+    options.extend(
+        [
+            (libc::MNT_SYNCHRONOUS, "sync"),
+            (libc::MNT_ASYNC, "async"),
+            (libc::MNT_NOEXEC, "noexec"),
+            (libc::MNT_NOSUID, "nosuid"),
+            (libc::MNT_NODEV, "nodev"),
+            (libc::MNT_NOATIME, "noatime"),
+            (libc::MNT_UNION, "union"),
+            (libc::MNT_QUARANTINE, "quarantine"),
+            (libc::MNT_JOURNALED, "journaled"),
+            (libc::MNT_DONTBROWSE, "nobrowse"),
+        ]
+        .iter()
+        .filter(|(flag, _)| flags & *flag as u32 != 0)
+        .map(|(_, name)| (*name).to_owned()),
+    );
+    options
+}
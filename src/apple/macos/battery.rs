@@ -0,0 +1,84 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::sys::ffi;
+use crate::sys::{
+    disk::{get_bool_value, get_int_value, get_str_value, DictKey},
+    utils::CFReleaser,
+};
+use crate::Battery;
+
+use core_foundation_sys::array::{CFArrayGetCount, CFArrayGetValueAtIndex};
+
+use std::time::Duration;
+
+// Note: Obtaining information about power sources is allowed inside the default macOS App Sandbox.
+pub(crate) fn get_battery() -> Option<Battery> {
+    unsafe {
+        let info = CFReleaser::new(ffi::IOPSCopyPowerSourcesInfo())?;
+        let sources = CFReleaser::new(ffi::IOPSCopyPowerSourcesList(info.inner()))?;
+
+        for index in 0..CFArrayGetCount(sources.inner()) {
+            let source = CFArrayGetValueAtIndex(sources.inner(), index);
+            // The description belongs to `info`, so it must not be released.
+            let description = ffi::IOPSGetPowerSourceDescription(info.inner(), source);
+            if description.is_null()
+                || get_str_value(description, DictKey::Defined(ffi::kIOPSTypeKey)).as_deref()
+                    != Some(ffi::kIOPSInternalBatteryType)
+            {
+                continue;
+            }
+            let current =
+                get_int_value(description, DictKey::Defined(ffi::kIOPSCurrentCapacityKey));
+            let max = get_int_value(description, DictKey::Defined(ffi::kIOPSMaxCapacityKey));
+            let (current, max) = match (current, max) {
+                (Some(current), Some(max)) if max > 0 => (current, max),
+                _ => continue,
+            };
+            let is_charging =
+                get_bool_value(description, DictKey::Defined(ffi::kIOPSIsChargingKey))
+                    .unwrap_or(false);
+            // The remaining time is expressed in minutes and is `-1` while it's being computed.
+            let time_remaining = get_int_value(
+                description,
+                DictKey::Defined(if is_charging {
+                    ffi::kIOPSTimeToFullChargeKey
+                } else {
+                    ffi::kIOPSTimeToEmptyKey
+                }),
+            )
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| Duration::from_secs(minutes as u64 * 60));
+
+            return Some(Battery {
+                percentage: (current as f32 * 100. / max as f32).min(100.),
+                is_charging,
+                time_remaining,
+            });
+        }
+        None
+    }
+}
+
+// Desktops don't have any power source (unless an UPS is connected), so `None` is returned.
+pub(crate) fn get_on_ac_power() -> Option<bool> {
+    unsafe {
+        let info = CFReleaser::new(ffi::IOPSCopyPowerSourcesInfo())?;
+        let sources = CFReleaser::new(ffi::IOPSCopyPowerSourcesList(info.inner()))?;
+
+        (0..CFArrayGetCount(sources.inner())).find_map(|index| {
+            let source = CFArrayGetValueAtIndex(sources.inner(), index);
+            // The description belongs to `info`, so it must not be released.
+            let description = ffi::IOPSGetPowerSourceDescription(info.inner(), source);
+            if description.is_null() {
+                return None;
+            }
+            match get_str_value(description, DictKey::Defined(ffi::kIOPSPowerSourceStateKey))?
+                .as_str()
+            {
+                ffi::kIOPSACPowerValue => Some(true),
+                ffi::kIOPSBatteryPowerValue => Some(false),
+                _ => None,
+            }
+        })
+    }
+}
@@ -9,11 +9,11 @@ use core_foundation_sys::string::{
 };
 
 use crate::apple::inner::ffi::{
-    kHIDPage_AppleVendor, kHIDUsage_AppleVendor_TemperatureSensor, kIOHIDEventTypeTemperature,
-    matching, IOHIDEventFieldBase, IOHIDEventGetFloatValue, IOHIDEventSystemClientCopyServices,
+    __IOHIDEventSystemClient, __IOHIDServiceClient, kHIDPage_AppleVendor,
+    kHIDUsage_AppleVendor_TemperatureSensor, kIOHIDEventTypeTemperature, matching,
+    IOHIDEventFieldBase, IOHIDEventGetFloatValue, IOHIDEventSystemClientCopyServices,
     IOHIDEventSystemClientCreate, IOHIDEventSystemClientSetMatching, IOHIDServiceClientCopyEvent,
-    IOHIDServiceClientCopyProperty, __IOHIDEventSystemClient, __IOHIDServiceClient,
-    HID_DEVICE_PROPERTY_PRODUCT,
+    IOHIDServiceClientCopyProperty, HID_DEVICE_PROPERTY_PRODUCT,
 };
 use crate::sys::utils::CFReleaser;
 use crate::ComponentExt;
@@ -31,6 +31,26 @@ impl Components {
         }
     }
 
+    /// Returns `false` if the HID event system client couldn't be created (or if `refresh` was
+    /// never called).
+    pub(crate) fn is_available(&self) -> bool {
+        self.client.is_some()
+    }
+
+    /// Returns the average temperature of the CPU die sensors (named "PMU tdie*").
+    pub(crate) fn cpu_temperature(&self) -> Option<f32> {
+        let (sum, count) = self
+            .inner
+            .iter()
+            .filter(|c| c.label.contains("tdie"))
+            .fold((0., 0), |(sum, count), c| (sum + c.temperature, count + 1));
+        if count == 0 {
+            None
+        } else {
+            Some(sum / count as f32)
+        }
+    }
+
     pub(crate) fn refresh(&mut self) {
         self.inner.clear();
 
@@ -1,7 +1,7 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
 use crate::sys::{ffi, macos::utils::IOReleaser};
-use crate::ComponentExt;
+use crate::{ComponentExt, Sensor, SensorKind};
 
 use libc::{c_char, c_int, c_void};
 
@@ -10,12 +10,22 @@ use std::mem;
 const COMPONENTS_TEMPERATURE_IDS: &[(&str, &[i8])] = &[
     ("PECI CPU", &['T' as i8, 'C' as i8, 'X' as i8, 'C' as i8]), // PECI CPU "TCXC"
     ("PECI CPU", &['T' as i8, 'C' as i8, 'X' as i8, 'c' as i8]), // PECI CPU "TCXc"
+    ("CPU Die", &['T' as i8, 'C' as i8, '0' as i8, 'D' as i8]),  // CPU Die "TC0D"
     (
         "CPU Proximity",
         &['T' as i8, 'C' as i8, '0' as i8, 'P' as i8],
     ), // CPU Proximity (heat spreader) "TC0P"
     ("GPU", &['T' as i8, 'G' as i8, '0' as i8, 'P' as i8]),      // GPU "TG0P"
+    ("GPU Die", &['T' as i8, 'G' as i8, '0' as i8, 'D' as i8]),  // GPU Die "TG0D"
+    (
+        "GPU Heatsink",
+        &['T' as i8, 'G' as i8, '0' as i8, 'H' as i8],
+    ), // GPU Heatsink "TG0H"
     ("Battery", &['T' as i8, 'B' as i8, '0' as i8, 'T' as i8]),  // Battery "TB0T"
+    ("Battery 1", &['T' as i8, 'B' as i8, '1' as i8, 'T' as i8]), // Battery 1 "TB1T"
+    ("Battery 2", &['T' as i8, 'B' as i8, '2' as i8, 'T' as i8]), // Battery 2 "TB2T"
+    ("Ambient", &['T' as i8, 'A' as i8, '0' as i8, 'P' as i8]),  // Ambient "TA0P"
+    ("Ambient 2", &['T' as i8, 'A' as i8, '1' as i8, 'P' as i8]), // Ambient 2 "TA1P"
 ];
 
 pub(crate) struct ComponentFFI {
@@ -58,6 +68,53 @@ impl Components {
         }
     }
 
+    /// Returns `false` if the SMC connection couldn't be opened.
+    pub(crate) fn is_available(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Returns the temperature of the most precise CPU sensor available.
+    pub(crate) fn cpu_temperature(&self) -> Option<f32> {
+        ["CPU Die", "PECI CPU", "CPU Proximity"]
+            .iter()
+            .find_map(|label| self.inner.iter().find(|c| c.label == *label))
+            .map(|c| c.temperature)
+    }
+
+    /// Returns the temperature sensors along with the voltage (`V*` keys), current (`I*` keys)
+    /// and fan speed (`F*Ac` keys) sensors of the SMC.
+    pub(crate) fn sensors(&self) -> Vec<Sensor> {
+        let mut sensors = Sensor::temperatures(&self.inner);
+        let connection = match self.connection {
+            Some(ref connection) => connection.inner(),
+            None => return sensors,
+        };
+        let count = match get_key_count(connection) {
+            Some(count) => count,
+            None => return sensors,
+        };
+        for index in 0..count {
+            let key = match get_key_at_index(connection, index) {
+                Some(key) => key,
+                None => continue,
+            };
+            let kind = match [key[0] as u8, key[2] as u8, key[3] as u8] {
+                [b'V', _, _] => SensorKind::Voltage,
+                [b'I', _, _] => SensorKind::Current,
+                [b'F', b'A', b'c'] => SensorKind::Fan,
+                _ => continue,
+            };
+            if let Some(value) = get_value(connection, &key) {
+                sensors.push(Sensor {
+                    label: key[..4].iter().map(|c| *c as u8 as char).collect(),
+                    kind,
+                    value,
+                });
+            }
+        }
+        sensors
+    }
+
     pub(crate) fn refresh(&mut self) {
         if let Some(ref connection) = self.connection {
             let connection = connection.inner();
@@ -96,13 +153,18 @@ impl Component {
         connection: ffi::io_connect_t,
     ) -> Option<Component> {
         let ffi_part = ComponentFFI::new(key, connection)?;
-        ffi_part.temperature().map(|temperature| Component {
-            temperature,
-            label,
-            max: max.unwrap_or(temperature),
-            critical,
-            ffi_part,
-        })
+        // Sensors which aren't available on this machine (like discrete GPU ones) can still
+        // exist but return `0`, so we skip them.
+        ffi_part
+            .temperature()
+            .filter(|temperature| *temperature > 0.)
+            .map(|temperature| Component {
+                temperature,
+                label,
+                max: max.unwrap_or(temperature),
+                critical,
+                ffi_part,
+            })
     }
 }
 
@@ -253,6 +315,87 @@ fn get_temperature(con: ffi::io_connect_t, key: &[i8]) -> Option<f32> {
     }
 }
 
+// Returns the number of keys of the SMC.
+fn get_key_count(con: ffi::io_connect_t) -> Option<u32> {
+    unsafe {
+        let (input_structure, val) =
+            get_key_size(con, &['#' as i8, 'K' as i8, 'E' as i8, 'Y' as i8, 0]).ok()?;
+        let val = read_key(con, &input_structure, val).ok()?;
+        let bytes = val.bytes;
+        Some(u32::from_be_bytes([
+            bytes[0] as u8,
+            bytes[1] as u8,
+            bytes[2] as u8,
+            bytes[3] as u8,
+        ]))
+    }
+}
+
+// Returns the name of the `index`-th key of the SMC.
+fn get_key_at_index(con: ffi::io_connect_t, index: u32) -> Option<[i8; 5]> {
+    unsafe {
+        let mut input_structure: ffi::KeyData_t = mem::zeroed::<ffi::KeyData_t>();
+        let mut output_structure: ffi::KeyData_t = mem::zeroed::<ffi::KeyData_t>();
+
+        input_structure.data8 = ffi::SMC_CMD_READ_INDEX;
+        input_structure.data32 = index;
+        if perform_call(
+            con,
+            ffi::KERNEL_INDEX_SMC,
+            &input_structure,
+            &mut output_structure,
+        ) != ffi::KIO_RETURN_SUCCESS
+        {
+            return None;
+        }
+        let mut key = [0; 5];
+        ultostr(key.as_mut_ptr(), output_structure.key);
+        Some(key)
+    }
+}
+
+// Returns the value of `key`, decoded according to its data type.
+fn get_value(con: ffi::io_connect_t, key: &[i8]) -> Option<f32> {
+    unsafe {
+        let (input_structure, val) = get_key_size(con, key).ok()?;
+        let val = read_key(con, &input_structure, val).ok()?;
+        let data_type = [
+            val.data_type[0] as u8,
+            val.data_type[1] as u8,
+            val.data_type[2] as u8,
+            val.data_type[3] as u8,
+        ];
+        let size = (val.data_size as usize).min(val.bytes.len());
+        let bytes = val.bytes[..size]
+            .iter()
+            .map(|b| *b as u8)
+            .collect::<Vec<_>>();
+        decode_value(data_type, &bytes)
+    }
+}
+
+// Decodes a SMC value. Fixed-point types are named `fpXY` (unsigned) or `spXY` (signed), `Y`
+// being the number of fractional bits (in hexadecimal), like `sp78` for temperatures.
+fn decode_value(data_type: [u8; 4], bytes: &[u8]) -> Option<f32> {
+    match (&data_type, bytes) {
+        (b"flt ", [a, b, c, d]) => Some(f32::from_ne_bytes([*a, *b, *c, *d])),
+        (b"ui8 ", [a]) => Some(*a as f32),
+        (b"ui16", [a, b]) => Some(u16::from_be_bytes([*a, *b]) as f32),
+        (b"ui32", [a, b, c, d]) => Some(u32::from_be_bytes([*a, *b, *c, *d]) as f32),
+        ([sign @ (b'f' | b's'), b'p', _, fraction], [a, b]) => {
+            let fraction_bits = (*fraction as char).to_digit(16)?;
+            let raw = u16::from_be_bytes([*a, *b]);
+            let raw = if *sign == b's' {
+                raw as i16 as f32
+            } else {
+                raw as f32
+            };
+            Some(raw / (1u32 << fraction_bits) as f32)
+        }
+        _ => None,
+    }
+}
+
 pub(crate) struct IoService(ffi::io_connect_t);
 
 impl IoService {
@@ -9,11 +9,16 @@ use crate::sys::{
 use crate::DiskType;
 
 use core_foundation_sys::base::{kCFAllocatorDefault, kCFAllocatorNull};
+use core_foundation_sys::dictionary::CFDictionaryRef;
 use core_foundation_sys::string as cfs;
 
 use std::ffi::CStr;
 
-pub(crate) fn get_disk_type(disk: &libc::statfs) -> Option<DiskType> {
+// Calls `callback` with the "Device Characteristics" properties of the device backing `disk`.
+fn with_device_characteristics<T, F: FnOnce(CFDictionaryRef) -> T>(
+    disk: &libc::statfs,
+    callback: F,
+) -> Option<T> {
     let characteristics_string = unsafe {
         CFReleaser::new(cfs::CFStringCreateWithBytesNoCopy(
             kCFAllocatorDefault,
@@ -98,29 +103,45 @@ pub(crate) fn get_disk_type(disk: &libc::statfs) -> Option<DiskType> {
             };
 
             if let Some(device_properties) = properties_result {
-                let disk_type = unsafe {
-                    super::disk::get_str_value(
-                        device_properties.inner(),
-                        DictKey::Defined(ffi::kIOPropertyMediumTypeKey),
-                    )
-                };
-
-                if let Some(disk_type) = disk_type.and_then(|medium| match medium.as_str() {
-                    _ if medium == ffi::kIOPropertyMediumTypeSolidStateKey => Some(DiskType::SSD),
-                    _ if medium == ffi::kIOPropertyMediumTypeRotationalKey => Some(DiskType::HDD),
-                    _ => None,
-                }) {
-                    return Some(disk_type);
-                } else {
-                    // Many external drive vendors do not advertise their device's storage medium.
-                    //
-                    // In these cases, assuming that there were _any_ properties about them registered, we fallback
-                    // to `HDD` when no storage medium is provided by the device instead of `Unknown`.
-                    return Some(DiskType::HDD);
-                }
+                return Some(callback(device_properties.inner()));
             }
         }
     }
 
     None
 }
+
+pub(crate) fn get_disk_type(disk: &libc::statfs) -> Option<DiskType> {
+    with_device_characteristics(disk, |device_properties| {
+        let disk_type = unsafe {
+            get_str_value(
+                device_properties,
+                DictKey::Defined(ffi::kIOPropertyMediumTypeKey),
+            )
+        };
+
+        match disk_type.as_deref() {
+            Some(medium) if medium == ffi::kIOPropertyMediumTypeSolidStateKey => DiskType::SSD,
+            Some(medium) if medium == ffi::kIOPropertyMediumTypeRotationalKey => DiskType::HDD,
+            // Many external drive vendors do not advertise their device's storage medium.
+            //
+            // In these cases, assuming that there were _any_ properties about them registered, we fallback
+            // to `HDD` when no storage medium is provided by the device instead of `Unknown`.
+            _ => DiskType::HDD,
+        }
+    })
+}
+
+pub(crate) fn get_disk_model_and_serial(disk: &libc::statfs) -> Option<(String, String)> {
+    with_device_characteristics(disk, |device_properties| unsafe {
+        let get = |key| {
+            get_str_value(device_properties, DictKey::Defined(key))
+                .map(|s| s.trim().to_owned())
+                .unwrap_or_default()
+        };
+        (
+            get(ffi::kIOPropertyProductNameKey),
+            get(ffi::kIOPropertyProductSerialNumberKey),
+        )
+    })
+}
@@ -1,6 +1,7 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use core_foundation_sys::base::{mach_port_t, CFAllocatorRef};
+use core_foundation_sys::array::CFArrayRef;
+use core_foundation_sys::base::{mach_port_t, CFAllocatorRef, CFTypeRef};
 use core_foundation_sys::dictionary::{CFDictionaryRef, CFMutableDictionaryRef};
 use core_foundation_sys::string::CFStringRef;
 
@@ -30,6 +31,143 @@ pub const kIOPropertyMediumTypeKey: &str = "Medium Type";
 pub const kIOPropertyMediumTypeSolidStateKey: &str = "Solid State";
 #[allow(non_upper_case_globals)]
 pub const kIOPropertyMediumTypeRotationalKey: &str = "Rotational";
+#[allow(non_upper_case_globals)]
+pub const kIOPropertyProductNameKey: &str = "Product Name";
+#[allow(non_upper_case_globals)]
+pub const kIOPropertyProductSerialNumberKey: &str = "Serial Number";
+#[allow(non_upper_case_globals)]
+pub const kIOUserClientCreatorKey: &str = "IOUserClientCreator";
+
+// From `IOKit/ps/IOPSKeys.h`.
+#[allow(non_upper_case_globals)]
+pub const kIOPSTypeKey: &str = "Type";
+#[allow(non_upper_case_globals)]
+pub const kIOPSInternalBatteryType: &str = "InternalBattery";
+#[allow(non_upper_case_globals)]
+pub const kIOPSCurrentCapacityKey: &str = "Current Capacity";
+#[allow(non_upper_case_globals)]
+pub const kIOPSMaxCapacityKey: &str = "Max Capacity";
+#[allow(non_upper_case_globals)]
+pub const kIOPSIsChargingKey: &str = "Is Charging";
+#[allow(non_upper_case_globals)]
+pub const kIOPSTimeToEmptyKey: &str = "Time to Empty";
+#[allow(non_upper_case_globals)]
+pub const kIOPSTimeToFullChargeKey: &str = "Time to Full Charge";
+#[allow(non_upper_case_globals)]
+pub const kIOPSPowerSourceStateKey: &str = "Power Source State";
+#[allow(non_upper_case_globals)]
+pub const kIOPSACPowerValue: &str = "AC Power";
+#[allow(non_upper_case_globals)]
+pub const kIOPSBatteryPowerValue: &str = "Battery Power";
+
+// From `sys/proc_info.h`, used to check the `pbi_flags` field of `proc_bsdinfo`.
+pub const PROC_FLAG_TRACED: u32 = 0x2;
+pub const PROC_FLAG_LP64: u32 = 0x10;
+
+// From `sys/proc_info.h`, used to retrieve information about a socket with `proc_pidfdinfo`.
+pub const PROC_PIDFDSOCKETINFO: libc::c_int = 3;
+pub const SOCKINFO_IN: libc::c_int = 1;
+pub const SOCKINFO_TCP: libc::c_int = 2;
+pub const TSI_S_LISTEN: libc::c_int = 1;
+
+// From `sys/proc_info.h`, used to list the threads of a process with `proc_pidinfo` and to
+// retrieve their information from their identifier.
+pub const PROC_PIDLISTTHREADIDS: libc::c_int = 28;
+pub const PROC_PIDTHREADID64INFO: libc::c_int = 15;
+// From `mach/thread_info.h`, `pth_cpu_usage` is expressed in thousandths.
+pub const TH_USAGE_SCALE: libc::c_int = 1000;
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct proc_fileinfo {
+    pub fi_openflags: u32,
+    pub fi_status: u32,
+    pub fi_offset: libc::off_t,
+    pub fi_type: i32,
+    pub fi_guardflags: u32,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct in_sockinfo {
+    pub insi_fport: libc::c_int,
+    // In network byte order.
+    pub insi_lport: libc::c_int,
+    pub insi_gencnt: u64,
+    pub insi_flags: u32,
+    pub insi_flow: u32,
+    pub insi_vflag: u8,
+    pub insi_ip_ttl: u8,
+    pub rfu_1: u32,
+    // `union { struct in4in6_addr; struct in6_addr; }`.
+    pub insi_faddr: [u32; 4],
+    pub insi_laddr: [u32; 4],
+    // `struct { u_char in4_tos; }`.
+    pub insi_v4: u8,
+    // `struct { uint8_t in6_hlim; int in6_cksum; u_short in6_ifindex; short in6_hops; }`.
+    pub insi_v6: [u32; 3],
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct tcp_sockinfo {
+    pub tcpsi_ini: in_sockinfo,
+    pub tcpsi_state: libc::c_int,
+    pub tcpsi_timer: [libc::c_int; 4],
+    pub tcpsi_mss: libc::c_int,
+    pub tcpsi_flags: u32,
+    pub rfu_1: u32,
+    pub tcpsi_tp: u64,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union soi_proto {
+    pub pri_in: in_sockinfo,
+    pub pri_tcp: tcp_sockinfo,
+    // The other members aren't used. The biggest one is `struct un_sockinfo`.
+    pub pri_un: [u64; 66],
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct socket_info {
+    // `struct vinfo_stat`.
+    pub soi_stat: [u64; 17],
+    pub soi_so: u64,
+    pub soi_pcb: u64,
+    pub soi_type: libc::c_int,
+    pub soi_protocol: libc::c_int,
+    pub soi_family: libc::c_int,
+    pub soi_options: libc::c_short,
+    pub soi_linger: libc::c_short,
+    pub soi_state: libc::c_short,
+    pub soi_qlen: libc::c_short,
+    pub soi_incqlen: libc::c_short,
+    pub soi_qlimit: libc::c_short,
+    pub soi_timeo: libc::c_short,
+    pub soi_error: libc::c_ushort,
+    pub soi_oobmark: u32,
+    // `struct sockbuf_info`.
+    pub soi_rcv: [u32; 6],
+    pub soi_snd: [u32; 6],
+    pub soi_kind: libc::c_int,
+    pub rfu_1: u32,
+    pub soi_proto: soi_proto,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct socket_fdinfo {
+    pub pfi: proc_fileinfo,
+    pub psi: socket_info,
+}
 
 // Note: Obtaining information about disks using IOKIt is allowed inside the default macOS App Sandbox.
 #[link(name = "IOKit", kind = "framework")]
@@ -56,6 +194,21 @@ extern "C" {
         parent: *mut io_registry_entry_t,
     ) -> kern_return_t;
 
+    pub fn IORegistryEntryCreateCFProperties(
+        entry: io_registry_entry_t,
+        properties: *mut CFMutableDictionaryRef,
+        allocator: CFAllocatorRef,
+        options: IOOptionBits,
+    ) -> kern_return_t;
+
+    pub fn IORegistryEntryGetChildIterator(
+        entry: io_registry_entry_t,
+        plane: io_name_t,
+        iterator: *mut io_iterator_t,
+    ) -> kern_return_t;
+
+    pub fn IOServiceMatching(name: *const c_char) -> CFMutableDictionaryRef;
+
     pub fn IOBSDNameMatching(
         mainPort: mach_port_t,
         options: u32,
@@ -64,6 +217,10 @@ extern "C" {
 
     // This is deprecated as of macOS 12.0, but Rust doesn't have a good way to only use the replacement on 12+.
     pub static kIOMasterPortDefault: mach_port_t;
+
+    pub fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+    pub fn IOPSCopyPowerSourcesList(blob: CFTypeRef) -> CFArrayRef;
+    pub fn IOPSGetPowerSourceDescription(blob: CFTypeRef, ps: CFTypeRef) -> CFDictionaryRef;
 }
 
 #[cfg(all(
@@ -72,8 +229,7 @@ extern "C" {
 ))]
 mod io_service {
     use super::{io_object_t, mach_port_t};
-    use core_foundation_sys::dictionary::CFMutableDictionaryRef;
-    use libc::{c_char, kern_return_t, size_t, task_t};
+    use libc::{kern_return_t, size_t, task_t};
 
     #[allow(non_camel_case_types)]
     pub type io_connect_t = io_object_t;
@@ -85,8 +241,6 @@ mod io_service {
     pub type task_port_t = task_t;
 
     extern "C" {
-        pub fn IOServiceMatching(a: *const c_char) -> CFMutableDictionaryRef;
-
         pub fn IOServiceOpen(
             device: io_service_t,
             owning_task: task_port_t,
@@ -158,6 +312,9 @@ mod io_service {
     #[allow(dead_code)]
     pub const SMC_CMD_READ_BYTES: u8 = 5;
 
+    #[allow(dead_code)]
+    pub const SMC_CMD_READ_INDEX: u8 = 8;
+
     pub const KIO_RETURN_SUCCESS: i32 = 0;
 }
 
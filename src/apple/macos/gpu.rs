@@ -0,0 +1,121 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::sys::ffi;
+use crate::sys::{
+    disk::{get_array_value, get_int_value, get_str_value, DictKey},
+    macos::utils::IOReleaser,
+    utils::CFReleaser,
+};
+use crate::Pid;
+
+use core_foundation_sys::array::{CFArrayGetCount, CFArrayGetValueAtIndex};
+use core_foundation_sys::base::kCFAllocatorDefault;
+use core_foundation_sys::dictionary::CFMutableDictionaryRef;
+
+use std::collections::HashMap;
+
+// Returns the GPU time (in nanoseconds) used by each process, or `None` if the GPU drivers don't
+// provide per-process statistics.
+//
+// Each process using the GPU has a user client under the `IOAccelerator` service. It contains
+// its creator ("pid 1234, name") and the GPU time used by each of its command queues.
+pub(crate) fn get_gpu_times() -> Option<HashMap<Pid, u64>> {
+    let mut times = HashMap::new();
+    let mut found = false;
+
+    unsafe {
+        // We don't need to wrap this in an auto-releaser because the following call to
+        // `IOServiceGetMatchingServices` will take ownership of one retain reference.
+        let matching = ffi::IOServiceMatching(b"IOAccelerator\0".as_ptr().cast());
+        if matching.is_null() {
+            return None;
+        }
+        let mut iterator: ffi::io_iterator_t = 0;
+        if ffi::IOServiceGetMatchingServices(ffi::kIOMasterPortDefault, matching, &mut iterator)
+            != libc::KERN_SUCCESS
+        {
+            return None;
+        }
+        // Safety: We checked for success, so there is always a valid iterator, even if it's empty.
+        let iterator = IOReleaser::new_unchecked(iterator);
+
+        while let Some(accelerator) = IOReleaser::new(ffi::IOIteratorNext(iterator.inner())) {
+            let mut children: ffi::io_iterator_t = 0;
+            if ffi::IORegistryEntryGetChildIterator(
+                accelerator.inner(),
+                ffi::kIOServicePlane.as_ptr().cast(),
+                &mut children,
+            ) != libc::KERN_SUCCESS
+            {
+                continue;
+            }
+            let children = IOReleaser::new_unchecked(children);
+
+            while let Some(child) = IOReleaser::new(ffi::IOIteratorNext(children.inner())) {
+                let mut properties: CFMutableDictionaryRef = std::ptr::null_mut();
+                if ffi::IORegistryEntryCreateCFProperties(
+                    child.inner(),
+                    &mut properties,
+                    kCFAllocatorDefault,
+                    0,
+                ) != libc::KERN_SUCCESS
+                {
+                    continue;
+                }
+                let properties = match CFReleaser::new(properties) {
+                    Some(properties) => properties,
+                    None => continue,
+                };
+                let pid = get_str_value(
+                    properties.inner(),
+                    DictKey::Defined(ffi::kIOUserClientCreatorKey),
+                )
+                .and_then(|creator| parse_creator_pid(&creator));
+                // The array belongs to `properties`, so it must not be released.
+                let usage = get_array_value(properties.inner(), DictKey::Defined("AppUsage"));
+                let (pid, usage) = match (pid, usage) {
+                    (Some(pid), Some(usage)) => (pid, usage),
+                    _ => continue,
+                };
+                found = true;
+
+                let time = (0..CFArrayGetCount(usage))
+                    .filter_map(|index| {
+                        get_int_value(
+                            CFArrayGetValueAtIndex(usage, index).cast(),
+                            DictKey::Defined("accumulatedGPUTime"),
+                        )
+                    })
+                    .map(|time| time.max(0) as u64)
+                    .fold(0, u64::saturating_add);
+                let total = times.entry(pid).or_insert(0);
+                *total = total.saturating_add(time);
+            }
+        }
+    }
+    if found {
+        Some(times)
+    } else {
+        None
+    }
+}
+
+// Parses the creator of an `IOAccelerator` user client ("pid 1234, WindowServer").
+fn parse_creator_pid(creator: &str) -> Option<Pid> {
+    let (pid, _) = creator.strip_prefix("pid ")?.split_once(',')?;
+    pid.trim().parse().ok().map(Pid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_creator_pid;
+    use crate::Pid;
+
+    #[test]
+    fn check_parse_creator_pid() {
+        assert_eq!(parse_creator_pid("pid 1234, WindowServer"), Some(Pid(1234)));
+        assert_eq!(parse_creator_pid("pid 42, my, app"), Some(Pid(42)));
+        assert_eq!(parse_creator_pid("pid 42"), None);
+        assert_eq!(parse_creator_pid("WindowServer"), None);
+    }
+}
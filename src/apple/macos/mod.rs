@@ -1,7 +1,9 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+pub(crate) mod battery;
 pub mod disk;
 pub mod ffi;
+pub(crate) mod swap;
 pub(crate) mod utils;
 
 #[cfg(not(feature = "apple-sandbox"))]
@@ -10,6 +12,9 @@ pub mod system;
 #[cfg(not(feature = "apple-sandbox"))]
 pub mod component;
 
+#[cfg(not(feature = "apple-sandbox"))]
+pub(crate) mod gpu;
+
 #[cfg(not(feature = "apple-sandbox"))]
 pub mod process;
 
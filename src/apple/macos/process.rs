@@ -9,8 +9,16 @@ use std::borrow::Borrow;
 
 use libc::{c_int, c_void, kill, size_t};
 
-use crate::{DiskUsage, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Signal, Uid};
-
+use crate::{
+    DiskUsage, Error, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Protocol, Signal,
+    ThreadInfo, Uid,
+};
+
+use crate::sys::ffi::{
+    socket_fdinfo, PROC_FLAG_LP64, PROC_FLAG_TRACED, PROC_PIDFDSOCKETINFO, PROC_PIDLISTTHREADIDS,
+    PROC_PIDTHREADID64INFO, SOCKINFO_IN, SOCKINFO_TCP, TH_USAGE_SCALE, TSI_S_LISTEN,
+};
+use crate::sys::macos::system::get_timebase_to_ns;
 use crate::sys::process::ThreadStatus;
 use crate::sys::system::Wrap;
 
@@ -18,6 +26,7 @@ use crate::sys::system::Wrap;
 pub struct Process {
     pub(crate) name: String,
     pub(crate) cmd: Vec<String>,
+    cmd_truncated: bool,
     pub(crate) exe: PathBuf,
     pid: Pid,
     parent: Option<Pid>,
@@ -32,8 +41,25 @@ pub struct Process {
     run_time: u64,
     pub(crate) updated: bool,
     cpu_usage: f32,
+    cpu_time: u64,
+    gpu_time: Option<u64>,
+    gpu_usage: Option<f32>,
     user_id: Option<Uid>,
+    real_user_id: Option<Uid>,
     group_id: Option<Gid>,
+    real_group_id: Option<Gid>,
+    being_traced: Option<bool>,
+    is_64bit: Option<bool>,
+    minor_faults: u64,
+    major_faults: u64,
+    open_files: Option<usize>,
+    thread_count: usize,
+    threads: Vec<ThreadInfo>,
+    nice: i32,
+    priority: i32,
+    tty: Option<String>,
+    process_group_id: Option<Pid>,
+    session_id: Option<Pid>,
     pub(crate) process_status: ProcessStatus,
     /// Status of process (running, stopped, waiting, etc). `None` means `sysinfo` doesn't have
     /// enough rights to get this information.
@@ -53,6 +79,7 @@ impl Process {
             pid,
             parent: None,
             cmd: Vec::new(),
+            cmd_truncated: false,
             environ: Vec::new(),
             exe,
             cwd,
@@ -60,13 +87,30 @@ impl Process {
             memory: 0,
             virtual_memory: 0,
             cpu_usage: 0.,
+            cpu_time: 0,
+            gpu_time: None,
+            gpu_usage: None,
             old_utime: 0,
             old_stime: 0,
             updated: true,
             start_time: 0,
             run_time: 0,
             user_id: None,
+            real_user_id: None,
             group_id: None,
+            real_group_id: None,
+            being_traced: None,
+            is_64bit: None,
+            minor_faults: 0,
+            major_faults: 0,
+            open_files: None,
+            thread_count: 0,
+            threads: Vec::new(),
+            nice: 0,
+            priority: 0,
+            tty: None,
+            process_group_id: None,
+            session_id: None,
             process_status: ProcessStatus::Unknown(0),
             status: None,
             old_read_bytes: 0,
@@ -82,6 +126,7 @@ impl Process {
             pid,
             parent,
             cmd: Vec::new(),
+            cmd_truncated: false,
             environ: Vec::new(),
             exe: PathBuf::new(),
             cwd: PathBuf::new(),
@@ -89,13 +134,30 @@ impl Process {
             memory: 0,
             virtual_memory: 0,
             cpu_usage: 0.,
+            cpu_time: 0,
+            gpu_time: None,
+            gpu_usage: None,
             old_utime: 0,
             old_stime: 0,
             updated: true,
             start_time,
             run_time,
             user_id: None,
+            real_user_id: None,
             group_id: None,
+            real_group_id: None,
+            being_traced: None,
+            is_64bit: None,
+            minor_faults: 0,
+            major_faults: 0,
+            open_files: None,
+            thread_count: 0,
+            threads: Vec::new(),
+            nice: 0,
+            priority: 0,
+            tty: None,
+            process_group_id: None,
+            session_id: None,
             process_status: ProcessStatus::Unknown(0),
             status: None,
             old_read_bytes: 0,
@@ -104,6 +166,43 @@ impl Process {
             written_bytes: 0,
         }
     }
+
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::ProcessSnapshot) -> Process {
+        let mut p = Process::new(
+            snapshot.pid,
+            snapshot.parent,
+            snapshot.start_time,
+            snapshot.run_time,
+        );
+        p.name = snapshot.name.clone();
+        p.cmd = snapshot.cmd.clone();
+        p.exe = snapshot.exe.clone();
+        p.memory = snapshot.memory;
+        p.virtual_memory = snapshot.virtual_memory;
+        p.cpu_usage = snapshot.cpu_usage;
+        p.process_status = snapshot.status;
+        p.read_bytes = snapshot.disk_usage.total_read_bytes;
+        p.old_read_bytes = p.read_bytes.saturating_sub(snapshot.disk_usage.read_bytes);
+        p.written_bytes = snapshot.disk_usage.total_written_bytes;
+        p.old_written_bytes = p
+            .written_bytes
+            .saturating_sub(snapshot.disk_usage.written_bytes);
+        p.user_id = snapshot.user_id.clone();
+        p
+    }
+
+    // `gpu_time` is the GPU time used by the process (or `None` if it isn't available) and
+    // `elapsed` the time since the previous update, both in nanoseconds.
+    pub(crate) fn update_gpu_usage(&mut self, gpu_time: Option<u64>, elapsed: u64) {
+        self.gpu_usage = match (self.gpu_time, gpu_time) {
+            (Some(old), Some(new)) if elapsed > 0 => {
+                Some((new.saturating_sub(old) as f64 * 100. / elapsed as f64) as f32)
+            }
+            _ => None,
+        };
+        self.gpu_time = gpu_time;
+    }
 }
 
 impl ProcessExt for Process {
@@ -120,6 +219,10 @@ impl ProcessExt for Process {
         &self.cmd
     }
 
+    fn is_cmd_truncated(&self) -> bool {
+        self.cmd_truncated
+    }
+
     fn exe(&self) -> &Path {
         self.exe.as_path()
     }
@@ -156,6 +259,10 @@ impl ProcessExt for Process {
         self.process_status
     }
 
+    fn wait_channel(&self) -> Option<&str> {
+        None
+    }
+
     fn start_time(&self) -> u64 {
         self.start_time
     }
@@ -168,6 +275,18 @@ impl ProcessExt for Process {
         self.cpu_usage
     }
 
+    fn cpu_time(&self) -> u64 {
+        self.cpu_time
+    }
+
+    fn iowait_time(&self) -> Option<u64> {
+        None
+    }
+
+    fn gpu_usage(&self) -> Option<f32> {
+        self.gpu_usage
+    }
+
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage {
             read_bytes: self.read_bytes - self.old_read_bytes,
@@ -181,25 +300,141 @@ impl ProcessExt for Process {
         self.user_id.as_ref()
     }
 
+    fn real_user_id(&self) -> Option<&Uid> {
+        self.real_user_id.as_ref()
+    }
+
     fn group_id(&self) -> Option<Gid> {
         self.group_id
     }
 
-    fn wait(&self) {
+    fn real_group_id(&self) -> Option<Gid> {
+        self.real_group_id
+    }
+
+    fn is_being_traced(&self) -> Option<bool> {
+        self.being_traced
+    }
+
+    fn tracer_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    fn open_files(&self) -> Option<usize> {
+        self.open_files
+    }
+
+    fn network_usage(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    fn is_64bit(&self) -> Option<bool> {
+        self.is_64bit
+    }
+
+    fn minor_faults(&self) -> u64 {
+        self.minor_faults
+    }
+
+    fn major_faults(&self) -> u64 {
+        self.major_faults
+    }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn threads(&self) -> Vec<ThreadInfo> {
+        self.threads.clone()
+    }
+
+    fn nice(&self) -> i32 {
+        self.nice
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn set_nice(&self, value: i32) -> bool {
+        let value = value.clamp(-20, 19);
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, self.pid.0 as _, value) == 0 }
+    }
+
+    fn tty(&self) -> Option<&str> {
+        self.tty.as_deref()
+    }
+
+    fn process_group_id(&self) -> Option<Pid> {
+        self.process_group_id
+    }
+
+    fn session_id(&self) -> Option<Pid> {
+        self.session_id
+    }
+
+    fn wait_status(&self) -> Option<i32> {
         let mut status = 0;
         // attempt waiting
         unsafe {
             if libc::waitpid(self.pid.0, &mut status, 0) < 0 {
-                // attempt failed (non-child process) so loop until process ends
-                let duration = std::time::Duration::from_millis(10);
-                while kill(self.pid.0, 0) == 0 {
-                    std::thread::sleep(duration);
+                // attempt failed (non-child process) so wait until process ends
+                if !wait_with_kqueue(self.pid) {
+                    let duration = std::time::Duration::from_millis(10);
+                    while kill(self.pid.0, 0) == 0 {
+                        std::thread::sleep(duration);
+                    }
                 }
+                // The exit code of a non-child process cannot be retrieved.
+                None
+            } else if libc::WIFEXITED(status) {
+                Some(libc::WEXITSTATUS(status))
+            } else {
+                None
             }
         }
     }
 }
 
+// Blocks until the process exits using a kqueue. Returns `false` if it failed.
+unsafe fn wait_with_kqueue(pid: Pid) -> bool {
+    let kq = libc::kqueue();
+    if kq < 0 {
+        return false;
+    }
+    let mut event: libc::kevent = std::mem::zeroed();
+    event.ident = pid.0 as _;
+    event.filter = libc::EVFILT_PROC;
+    event.flags = libc::EV_ADD | libc::EV_ONESHOT;
+    event.fflags = libc::NOTE_EXIT;
+    let mut triggered: libc::kevent = std::mem::zeroed();
+    let ret = loop {
+        let ret = libc::kevent(kq, &event, 1, &mut triggered, 1, std::ptr::null());
+        if ret >= 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+            break ret;
+        }
+    };
+    libc::close(kq);
+    ret > 0
+}
+
+/// Returns the CPU usage (in percent) corresponding to `time_diff` spent on the CPU over
+/// `time_interval`.
+///
+/// If the interval is too small (which can happen with back-to-back refreshes), it returns `0`.
+/// The result is clamped between `0` and `max_cpu_usage` and is never NaN.
+fn cpu_usage_percent(time_diff: f64, time_interval: f64, max_cpu_usage: f32) -> f32 {
+    if time_interval.is_nan() || time_interval <= 0.000001 {
+        return 0.;
+    }
+    let cpu_usage = (time_diff / time_interval * 100.) as f32;
+    if cpu_usage.is_nan() {
+        0.
+    } else {
+        cpu_usage.max(0.).min(max_cpu_usage)
+    }
+}
+
 #[allow(deprecated)] // Because of libc::mach_absolute_time.
 pub(crate) fn compute_cpu_usage(
     p: &mut Process,
@@ -207,21 +442,20 @@ pub(crate) fn compute_cpu_usage(
     system_time: u64,
     user_time: u64,
     time_interval: Option<f64>,
+    max_cpu_usage: f32,
 ) {
     if let Some(time_interval) = time_interval {
         let total_existing_time = p.old_stime.saturating_add(p.old_utime);
-        if time_interval > 0.000001 && total_existing_time > 0 {
+        p.cpu_usage = if total_existing_time > 0 {
             let total_current_time = task_info
                 .pti_total_system
                 .saturating_add(task_info.pti_total_user);
 
             let total_time_diff = total_current_time.saturating_sub(total_existing_time);
-            if total_time_diff > 0 {
-                p.cpu_usage = (total_time_diff as f64 / time_interval * 100.) as f32;
-            }
+            cpu_usage_percent(total_time_diff as f64, time_interval, max_cpu_usage)
         } else {
-            p.cpu_usage = 0.;
-        }
+            0.
+        };
         p.old_stime = task_info.pti_total_system;
         p.old_utime = task_info.pti_total_user;
     } else {
@@ -245,11 +479,8 @@ pub(crate) fn compute_cpu_usage(
             };
             p.old_utime = task_time;
             p.old_stime = time;
-            p.cpu_usage = if time_delta == 0 {
-                0f32
-            } else {
-                (system_time_delta as f64 * 100f64 / time_delta as f64) as f32
-            };
+            p.cpu_usage =
+                cpu_usage_percent(system_time_delta as f64, time_delta as f64, max_cpu_usage);
         }
     }
 }
@@ -262,6 +493,153 @@ pub(crate) fn compute_cpu_usage(
     p.updated = true;
 }*/
 
+unsafe fn get_fds(pid: Pid) -> Option<Vec<libc::proc_fdinfo>> {
+    let fd_size = mem::size_of::<libc::proc_fdinfo>();
+    // Without a buffer, we only get an estimation (a bit bigger than the real value) of the
+    // needed buffer size.
+    let size = libc::proc_pidinfo(pid.0, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0);
+    if size <= 0 {
+        return None;
+    }
+    let mut fds: Vec<libc::proc_fdinfo> = Vec::with_capacity(size as usize / fd_size);
+    let size = libc::proc_pidinfo(
+        pid.0,
+        libc::PROC_PIDLISTFDS,
+        0,
+        fds.as_mut_ptr() as *mut c_void,
+        (fds.capacity() * fd_size) as _,
+    );
+    if size <= 0 {
+        None
+    } else {
+        fds.set_len(size as usize / fd_size);
+        Some(fds)
+    }
+}
+
+/// Returns the information of the threads of the process. `thread_count` is used to size the
+/// buffer receiving the thread identifiers.
+unsafe fn get_threads(
+    pid: Pid,
+    thread_count: usize,
+    refresh_kind: ProcessRefreshKind,
+) -> Vec<ThreadInfo> {
+    let id_size = mem::size_of::<u64>();
+    // Some room is kept in case new threads were created in the meantime.
+    let mut ids: Vec<u64> = Vec::with_capacity(thread_count + 16);
+    let size = libc::proc_pidinfo(
+        pid.0,
+        PROC_PIDLISTTHREADIDS,
+        0,
+        ids.as_mut_ptr() as *mut c_void,
+        (ids.capacity() * id_size) as _,
+    );
+    if size <= 0 {
+        return Vec::new();
+    }
+    ids.set_len(size as usize / id_size);
+    ids.into_iter()
+        .filter_map(|tid| {
+            let mut info = mem::zeroed::<libc::proc_threadinfo>();
+            if libc::proc_pidinfo(
+                pid.0,
+                PROC_PIDTHREADID64INFO,
+                tid,
+                &mut info as *mut libc::proc_threadinfo as *mut c_void,
+                mem::size_of::<libc::proc_threadinfo>() as _,
+            ) <= 0
+            {
+                // The thread very likely ended in the meantime.
+                return None;
+            }
+            Some(ThreadInfo {
+                tid,
+                name: CStr::from_ptr(info.pth_name.as_ptr())
+                    .to_string_lossy()
+                    .into_owned(),
+                cpu_usage: if refresh_kind.cpu() {
+                    info.pth_cpu_usage as f32 * 100. / TH_USAGE_SCALE as f32
+                } else {
+                    0.
+                },
+                status: thread_process_status(ThreadStatus::from(info.pth_run_state)),
+            })
+        })
+        .collect()
+}
+
+fn thread_process_status(status: ThreadStatus) -> ProcessStatus {
+    match status {
+        ThreadStatus::Running => ProcessStatus::Run,
+        ThreadStatus::Stopped | ThreadStatus::Halted => ProcessStatus::Stop,
+        ThreadStatus::Waiting => ProcessStatus::Sleep,
+        // Matches the uninterruptible disk sleep of Linux.
+        ThreadStatus::Uninterruptible => ProcessStatus::Idle,
+        ThreadStatus::Unknown(x) => ProcessStatus::Unknown(x as _),
+    }
+}
+
+unsafe fn get_open_files(pid: Pid) -> Option<usize> {
+    get_fds(pid).map(|fds| fds.len())
+}
+
+/// Returns `true` if the process has a socket bound to the local `port`. For TCP, only
+/// listening sockets are considered.
+pub(crate) fn has_socket_on_port(pid: Pid, port: u16, protocol: Protocol) -> bool {
+    unsafe {
+        let fds = match get_fds(pid) {
+            Some(fds) => fds,
+            None => return false,
+        };
+        fds.iter()
+            .filter(|fd| fd.proc_fdtype == libc::PROX_FDTYPE_SOCKET as u32)
+            .any(|fd| {
+                let mut info: socket_fdinfo = mem::zeroed();
+                let size = mem::size_of::<socket_fdinfo>() as c_int;
+                if libc::proc_pidfdinfo(
+                    pid.0,
+                    fd.proc_fd,
+                    PROC_PIDFDSOCKETINFO,
+                    &mut info as *mut socket_fdinfo as *mut c_void,
+                    size,
+                ) != size
+                {
+                    return false;
+                }
+                let info = &info.psi;
+                if info.soi_family != libc::AF_INET && info.soi_family != libc::AF_INET6 {
+                    return false;
+                }
+                let local_port = match protocol {
+                    Protocol::Tcp
+                        if info.soi_kind == SOCKINFO_TCP
+                            && info.soi_proto.pri_tcp.tcpsi_state == TSI_S_LISTEN =>
+                    {
+                        info.soi_proto.pri_tcp.tcpsi_ini.insi_lport
+                    }
+                    Protocol::Udp
+                        if info.soi_kind == SOCKINFO_IN
+                            && info.soi_protocol == libc::IPPROTO_UDP =>
+                    {
+                        info.soi_proto.pri_in.insi_lport
+                    }
+                    _ => return false,
+                };
+                // The port is stored in network byte order.
+                u16::from_be(local_port as u16) == port
+            })
+    }
+}
+
+/// Returns the time spent on the CPU by the process, in milliseconds.
+fn get_cpu_time(task_info: &libc::proc_taskinfo) -> u64 {
+    // The times are expressed in mach absolute time units.
+    let total_time = task_info
+        .pti_total_user
+        .saturating_add(task_info.pti_total_system);
+    (total_time as f64 * get_timebase_to_ns() / 1_000_000.) as u64
+}
+
 unsafe fn get_task_info(pid: Pid) -> libc::proc_taskinfo {
     let mut task_info = mem::zeroed::<libc::proc_taskinfo>();
     // If it doesn't work, we just don't have memory information for this process
@@ -323,13 +701,62 @@ unsafe fn get_bsd_info(pid: Pid) -> Option<libc::proc_bsdinfo> {
     }
 }
 
+unsafe fn get_nice(pid: Pid) -> i32 {
+    // `getpriority` can legitimately return `-1`, so `errno` needs to be reset to know whether
+    // the call failed.
+    let errno = libc::__error();
+    if !errno.is_null() {
+        *errno = 0;
+    }
+    let nice = libc::getpriority(libc::PRIO_PROCESS, pid.0 as _);
+    if nice == -1 && !errno.is_null() && *errno != 0 {
+        0
+    } else {
+        nice
+    }
+}
+
+unsafe fn get_session_id(pid: Pid) -> Option<Pid> {
+    match libc::getsid(pid.0) {
+        -1 => None,
+        sid => Some(Pid(sid)),
+    }
+}
+
+unsafe fn get_tty(tdev: u32) -> Option<String> {
+    // `NODEV` means that the process has no controlling terminal.
+    if tdev == !0 {
+        return None;
+    }
+    // `devname` returns a pointer to a static buffer, so calls must not overlap.
+    static DEVNAME_LOCK: once_cell::sync::Lazy<std::sync::Mutex<()>> =
+        once_cell::sync::Lazy::new(|| std::sync::Mutex::new(()));
+
+    let _guard = DEVNAME_LOCK.lock();
+    let name = libc::devname(tdev as _, libc::S_IFCHR);
+    if name.is_null() {
+        None
+    } else {
+        CStr::from_ptr(name).to_str().ok().map(str::to_owned)
+    }
+}
+
+// Converts the error returned by a failed call on a process.
+fn process_error(err: std::io::Error) -> Error {
+    if err.raw_os_error() == Some(libc::ESRCH) {
+        Error::NotFound
+    } else {
+        err.into()
+    }
+}
+
 unsafe fn create_new_process(
     pid: Pid,
     mut size: size_t,
     now: u64,
     refresh_kind: ProcessRefreshKind,
     info: Option<libc::proc_bsdinfo>,
-) -> Result<Option<Process>, ()> {
+) -> Result<Option<Process>, Error> {
     let mut vnodepathinfo = mem::zeroed::<libc::proc_vnodepathinfo>();
     let result = libc::proc_pidinfo(
         pid.0,
@@ -352,26 +779,13 @@ unsafe fn create_new_process(
     let info = match info {
         Some(info) => info,
         None => {
-            let mut buffer: Vec<u8> = Vec::with_capacity(libc::PROC_PIDPATHINFO_MAXSIZE as _);
-            match libc::proc_pidpath(
-                pid.0,
-                buffer.as_mut_ptr() as *mut _,
-                libc::PROC_PIDPATHINFO_MAXSIZE as _,
-            ) {
-                x if x > 0 => {
-                    buffer.set_len(x as _);
-                    let tmp = String::from_utf8_unchecked(buffer);
-                    let exe = PathBuf::from(tmp);
-                    let name = exe
-                        .file_name()
-                        .and_then(|x| x.to_str())
-                        .unwrap_or("")
-                        .to_owned();
-                    return Ok(Some(Process::new_empty(pid, exe, name, cwd)));
+            return match get_exe(pid) {
+                Some(exe) => {
+                    let name = get_exe_name(&exe);
+                    Ok(Some(Process::new_empty(pid, exe, name, cwd)))
                 }
-                _ => {}
-            }
-            return Err(());
+                None => Err(process_error(std::io::Error::last_os_error())),
+            };
         }
     };
     let parent = match info.pbi_ppid as i32 {
@@ -379,9 +793,48 @@ unsafe fn create_new_process(
         p => Some(Pid(p)),
     };
 
-    let mut proc_args = Vec::with_capacity(size as _);
-    let ptr: *mut u8 = proc_args.as_mut_slice().as_mut_ptr();
+    let start_time = info.pbi_start_tvsec;
+    let run_time = now.saturating_sub(start_time);
+
+    if !refresh_kind.cmd() {
+        // We don't need to retrieve (and parse) the arguments and the environment, so we only
+        // get the executable path.
+        let mut p = Process::new(pid, parent, start_time, run_time);
+        if let Some(exe) = get_exe(pid) {
+            p.name = get_exe_name(&exe);
+            if exe.is_absolute() {
+                if let Some(parent_path) = exe.parent() {
+                    p.root = parent_path.to_path_buf();
+                }
+            }
+            p.exe = exe;
+        } else {
+            p.name = get_comm_name(&info);
+        }
+        p.cwd = cwd;
+        fill_new_process_info(&mut p, pid, &info, refresh_kind);
+        return Ok(Some(p));
+    }
+
     let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid.0 as _];
+    // Without a buffer, `sysctl` returns the size of the arguments of this process. It is usually
+    // much smaller than `kern.argmax` (`size`), which stays the upper limit, so we only allocate
+    // what is needed.
+    let mut needed: size_t = 0;
+    if libc::sysctl(
+        mib.as_mut_ptr(),
+        mib.len() as _,
+        std::ptr::null_mut(),
+        &mut needed,
+        std::ptr::null_mut(),
+        0,
+    ) == 0
+        && needed > 0
+    {
+        size = size.min(needed);
+    }
+    let mut proc_args: Vec<u8> = Vec::with_capacity(size as _);
+    let ptr: *mut u8 = proc_args.as_mut_ptr();
     /*
      * /---------------\ 0x00000000
      * | ::::::::::::: |
@@ -421,123 +874,145 @@ unsafe fn create_new_process(
         0,
     ) == -1
     {
-        return Err(()); // not enough rights I assume?
+        let err = std::io::Error::last_os_error();
+        // `KERN_PROCARGS2` fails with `EINVAL` when we don't have enough rights.
+        return Err(if err.raw_os_error() == Some(libc::EINVAL) {
+            Error::PermissionDenied
+        } else {
+            process_error(err)
+        });
     }
-    let mut n_args: c_int = 0;
-    libc::memcpy(
-        (&mut n_args) as *mut c_int as *mut c_void,
-        ptr as *const c_void,
-        mem::size_of::<c_int>(),
-    );
-
-    let mut cp = ptr.add(mem::size_of::<c_int>());
-    let mut start = cp;
-
-    let start_time = info.pbi_start_tvsec;
-    let run_time = now.saturating_sub(start_time);
-
-    let mut p = if cp < ptr.add(size) {
-        while cp < ptr.add(size) && *cp != 0 {
-            cp = cp.offset(1);
-        }
-        let exe = Path::new(get_unchecked_str(cp, start).as_str()).to_path_buf();
-        let name = exe
-            .file_name()
-            .and_then(|x| x.to_str())
-            .unwrap_or("")
-            .to_owned();
-        while cp < ptr.add(size) && *cp == 0 {
-            cp = cp.offset(1);
+    proc_args.set_len(size.min(proc_args.capacity()));
+
+    let mut p = match parse_proc_args(&proc_args) {
+        Some(args) => {
+            let name = get_exe_name(&args.exe);
+            let (environ, root) = if args.exe.is_absolute() {
+                if let Some(parent_path) = args.exe.parent() {
+                    get_environ(args.environ, parent_path.to_path_buf(), do_not_get_env_path)
+                } else {
+                    get_environ(args.environ, PathBuf::new(), do_get_env_path)
+                }
+            } else {
+                get_environ(args.environ, PathBuf::new(), do_get_env_path)
+            };
+            let mut p = Process::new(pid, parent, start_time, run_time);
+
+            p.exe = args.exe;
+            p.name = name;
+            p.cwd = cwd;
+            p.cmd = parse_command_line(&args.cmd);
+            p.cmd_truncated = args.cmd_truncated;
+            p.environ = environ;
+            p.root = root;
+            p
         }
-        start = cp;
-        let mut c = 0;
-        let mut cmd = Vec::with_capacity(n_args as usize);
-        while c < n_args && cp < ptr.add(size) {
-            if *cp == 0 {
-                c += 1;
-                cmd.push(get_unchecked_str(cp, start));
-                start = cp.offset(1);
+        None => {
+            let mut p = Process::new(pid, parent, start_time, run_time);
+            match get_exe(pid) {
+                Some(exe) => {
+                    p.name = get_exe_name(&exe);
+                    p.exe = exe;
+                }
+                None => p.name = get_comm_name(&info),
             }
-            cp = cp.offset(1);
+            p.cwd = cwd;
+            p
         }
+    };
 
-        #[inline]
-        unsafe fn get_environ<F: Fn(&str, &mut PathBuf, &mut bool)>(
-            ptr: *mut u8,
-            mut cp: *mut u8,
-            size: size_t,
-            mut root: PathBuf,
-            callback: F,
-        ) -> (Vec<String>, PathBuf) {
-            let mut environ = Vec::with_capacity(10);
-            let mut start = cp;
-            let mut check = true;
-            while cp < ptr.add(size) {
-                if *cp == 0 {
-                    if cp == start {
-                        break;
-                    }
-                    let e = get_unchecked_str(cp, start);
-                    callback(&e, &mut root, &mut check);
-                    environ.push(e);
-                    start = cp.offset(1);
-                }
-                cp = cp.offset(1);
-            }
-            (environ, root)
+    fill_new_process_info(&mut p, pid, &info, refresh_kind);
+    Ok(Some(p))
+}
+
+// `pti_faults` contains all the faults, including the page-ins (which are the major faults).
+fn get_minor_faults(task_info: &libc::proc_taskinfo) -> u64 {
+    (task_info.pti_faults as u64).saturating_sub(task_info.pti_pageins as _)
+}
+
+unsafe fn get_exe(pid: Pid) -> Option<PathBuf> {
+    let mut buffer: Vec<u8> = Vec::with_capacity(libc::PROC_PIDPATHINFO_MAXSIZE as _);
+    match libc::proc_pidpath(
+        pid.0,
+        buffer.as_mut_ptr() as *mut _,
+        libc::PROC_PIDPATHINFO_MAXSIZE as _,
+    ) {
+        x if x > 0 => {
+            buffer.set_len(x as _);
+            Some(PathBuf::from(String::from_utf8_unchecked(buffer)))
         }
+        _ => None,
+    }
+}
 
-        let (environ, root) = if exe.is_absolute() {
-            if let Some(parent_path) = exe.parent() {
-                get_environ(
-                    ptr,
-                    cp,
-                    size,
-                    parent_path.to_path_buf(),
-                    do_not_get_env_path,
-                )
-            } else {
-                get_environ(ptr, cp, size, PathBuf::new(), do_get_env_path)
-            }
-        } else {
-            get_environ(ptr, cp, size, PathBuf::new(), do_get_env_path)
-        };
-        let mut p = Process::new(pid, parent, start_time, run_time);
+fn get_exe_name(exe: &Path) -> String {
+    exe.file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or("")
+        .to_owned()
+}
 
-        p.exe = exe;
-        p.name = name;
-        p.cwd = cwd;
-        p.cmd = parse_command_line(&cmd);
-        p.environ = environ;
-        p.root = root;
-        p
+// Only used when the executable path isn't available since the kernel truncates the names:
+// `pbi_name` to 32 bytes and `pbi_comm` to 16 bytes.
+unsafe fn get_comm_name(info: &libc::proc_bsdinfo) -> String {
+    let name = if info.pbi_name[0] != 0 {
+        CStr::from_ptr(info.pbi_name.as_ptr())
     } else {
-        Process::new(pid, parent, start_time, run_time)
+        CStr::from_ptr(info.pbi_comm.as_ptr())
     };
+    name.to_string_lossy().into_owned()
+}
 
+// Retrieves the information of a new process which don't depend on its arguments.
+unsafe fn fill_new_process_info(
+    p: &mut Process,
+    pid: Pid,
+    info: &libc::proc_bsdinfo,
+    refresh_kind: ProcessRefreshKind,
+) {
     let task_info = get_task_info(pid);
 
     p.memory = task_info.pti_resident_size;
     p.virtual_memory = task_info.pti_virtual_size;
+    p.minor_faults = get_minor_faults(&task_info);
+    p.major_faults = task_info.pti_pageins as _;
+    p.thread_count = task_info.pti_threadnum as _;
+    p.priority = task_info.pti_priority;
+    p.nice = get_nice(pid);
+    p.cpu_time = get_cpu_time(&task_info);
 
     p.user_id = Some(Uid(info.pbi_uid));
+    p.real_user_id = Some(Uid(info.pbi_ruid));
     p.group_id = Some(Gid(info.pbi_gid));
+    p.real_group_id = Some(Gid(info.pbi_rgid));
     p.process_status = ProcessStatus::from(info.pbi_status);
+    p.tty = get_tty(info.e_tdev);
+    p.process_group_id = Some(Pid(info.pbi_pgid as _));
+    p.session_id = get_session_id(pid);
+    p.being_traced = Some(info.pbi_flags & PROC_FLAG_TRACED != 0);
+    p.is_64bit = Some(info.pbi_flags & PROC_FLAG_LP64 != 0);
+    if refresh_kind.open_files() {
+        p.open_files = get_open_files(pid);
+    }
+    if refresh_kind.threads() {
+        p.threads = get_threads(pid, p.thread_count, refresh_kind);
+    }
     if refresh_kind.disk_usage() {
-        update_proc_disk_activity(&mut p);
+        update_proc_disk_activity(p);
     }
-    Ok(Some(p))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn update_process(
     wrap: &Wrap,
     pid: Pid,
     size: size_t,
     time_interval: Option<f64>,
+    max_cpu_usage: f32,
     now: u64,
     refresh_kind: ProcessRefreshKind,
     check_if_alive: bool,
-) -> Result<Option<Process>, ()> {
+) -> Result<Option<Process>, Error> {
     unsafe {
         if let Some(ref mut p) = (*wrap.0.get()).get_mut(&pid) {
             if p.memory == 0 {
@@ -546,7 +1021,7 @@ pub(crate) fn update_process(
                     p.updated = true;
                     Ok(None)
                 } else {
-                    Err(())
+                    Err(Error::NotFound)
                 };
             }
             if let Some(info) = get_bsd_info(pid) {
@@ -556,6 +1031,9 @@ pub(crate) fn update_process(
                     // The owner of this PID changed.
                     return create_new_process(pid, size, now, refresh_kind, Some(info));
                 }
+                p.being_traced = Some(info.pbi_flags & PROC_FLAG_TRACED != 0);
+                p.is_64bit = Some(info.pbi_flags & PROC_FLAG_LP64 != 0);
+                p.process_group_id = Some(Pid(info.pbi_pgid as _));
             }
             let task_info = get_task_info(pid);
             let mut thread_info = mem::zeroed::<libc::proc_threadinfo>();
@@ -577,17 +1055,38 @@ pub(crate) fn update_process(
                 if check_if_pid_is_alive(pid, check_if_alive) {
                     (0, 0, Some(ThreadStatus::Running))
                 } else {
-                    return Err(());
+                    return Err(Error::NotFound);
                 }
             };
             p.status = thread_status;
 
             if refresh_kind.cpu() {
-                compute_cpu_usage(p, task_info, system_time, user_time, time_interval);
+                compute_cpu_usage(
+                    p,
+                    task_info,
+                    system_time,
+                    user_time,
+                    time_interval,
+                    max_cpu_usage,
+                );
             }
 
             p.memory = task_info.pti_resident_size;
             p.virtual_memory = task_info.pti_virtual_size;
+            p.minor_faults = get_minor_faults(&task_info);
+            p.major_faults = task_info.pti_pageins as _;
+            p.thread_count = task_info.pti_threadnum as _;
+            p.priority = task_info.pti_priority;
+            p.nice = get_nice(pid);
+            p.cpu_time = get_cpu_time(&task_info);
+            if refresh_kind.open_files() {
+                p.open_files = get_open_files(pid);
+            }
+            p.threads = if refresh_kind.threads() {
+                get_threads(pid, p.thread_count, refresh_kind)
+            } else {
+                Vec::new()
+            };
             if refresh_kind.disk_usage() {
                 update_proc_disk_activity(p);
             }
@@ -643,12 +1142,72 @@ pub(crate) fn get_proc_list() -> Option<Vec<Pid>> {
     }
 }
 
-unsafe fn get_unchecked_str(cp: *mut u8, start: *mut u8) -> String {
-    let len = cp as usize - start as usize;
-    let part = Vec::from_raw_parts(start, len, len);
-    let tmp = String::from_utf8_unchecked(part.clone());
-    mem::forget(part);
-    tmp
+// Arguments of a process, parsed from the buffer filled by `KERN_PROCARGS2`.
+struct ProcArgs<'a> {
+    exe: PathBuf,
+    cmd: Vec<String>,
+    // The rest of the buffer, which contains the environment.
+    environ: &'a [u8],
+    // `true` if the buffer ended before all the arguments could be read.
+    cmd_truncated: bool,
+}
+
+// The buffer starts with `argc`, followed by the executable path, some padding and then the
+// NUL-terminated arguments. Nothing in it is trusted: the parsing stops at the end of the buffer
+// whatever `argc` says.
+fn parse_proc_args(data: &[u8]) -> Option<ProcArgs<'_>> {
+    let mut n_args = [0; mem::size_of::<c_int>()];
+    n_args.copy_from_slice(data.get(..mem::size_of::<c_int>())?);
+    let n_args = c_int::from_ne_bytes(n_args).max(0) as usize;
+    let data = &data[mem::size_of::<c_int>()..];
+    if data.is_empty() {
+        return None;
+    }
+
+    let exe_end = data.iter().position(|&c| c == 0).unwrap_or(data.len());
+    let exe = PathBuf::from(String::from_utf8_lossy(&data[..exe_end]).into_owned());
+    let mut data = &data[exe_end..];
+    while let Some((&0, rest)) = data.split_first() {
+        data = rest;
+    }
+
+    // Each argument takes at least one byte, so we don't preallocate more than that.
+    let mut cmd = Vec::with_capacity(n_args.min(data.len()));
+    while cmd.len() < n_args {
+        match data.iter().position(|&c| c == 0) {
+            Some(end) => {
+                cmd.push(String::from_utf8_lossy(&data[..end]).into_owned());
+                data = &data[end + 1..];
+            }
+            None => break,
+        }
+    }
+    Some(ProcArgs {
+        exe,
+        cmd_truncated: cmd.len() < n_args,
+        cmd,
+        environ: data,
+    })
+}
+
+fn get_environ<F: Fn(&str, &mut PathBuf, &mut bool)>(
+    mut data: &[u8],
+    mut root: PathBuf,
+    callback: F,
+) -> (Vec<String>, PathBuf) {
+    let mut environ = Vec::with_capacity(10);
+    let mut check = true;
+    // The environment ends with an empty string.
+    while let Some(end) = data.iter().position(|&c| c == 0) {
+        if end == 0 {
+            break;
+        }
+        let e = String::from_utf8_lossy(&data[..end]).into_owned();
+        callback(&e, &mut root, &mut check);
+        environ.push(e);
+        data = &data[end + 1..];
+    }
+    (environ, root)
 }
 
 fn parse_command_line<T: Deref<Target = str> + Borrow<str>>(cmd: &[T]) -> Vec<String> {
@@ -685,4 +1244,44 @@ mod test {
         assert!(!check);
         assert_eq!(path, PathBuf::from("tadam"));
     }
+
+    #[test]
+    fn test_parse_proc_args() {
+        let mut data = 2i32.to_ne_bytes().to_vec();
+        data.extend_from_slice(b"/bin/ls\0\0\0ls\0-l\0HOME=/\0\0");
+        let args = parse_proc_args(&data).expect("failed to parse arguments");
+        assert_eq!(args.exe, PathBuf::from("/bin/ls"));
+        assert_eq!(args.cmd, ["ls", "-l"]);
+        assert!(!args.cmd_truncated);
+        assert_eq!(
+            get_environ(args.environ, PathBuf::new(), do_get_env_path).0,
+            ["HOME=/"]
+        );
+
+        // The buffer ends before all the arguments are read.
+        let mut data = i32::MAX.to_ne_bytes().to_vec();
+        data.extend_from_slice(b"/bin/ls\0ls\0-l\0-a");
+        let args = parse_proc_args(&data).expect("failed to parse arguments");
+        assert_eq!(args.cmd, ["ls", "-l"]);
+        assert!(args.cmd_truncated);
+        assert!(get_environ(args.environ, PathBuf::new(), do_get_env_path)
+            .0
+            .is_empty());
+
+        assert!(parse_proc_args(&[0, 0]).is_none());
+        assert!(parse_proc_args(&1i32.to_ne_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_cpu_usage_percent() {
+        // Two refreshes with the same ticks snapshot: no time elapsed.
+        assert_eq!(cpu_usage_percent(0., 0., 800.), 0.);
+        assert_eq!(cpu_usage_percent(10., 0., 800.), 0.);
+        assert_eq!(cpu_usage_percent(10., f64::NAN, 800.), 0.);
+
+        assert_eq!(cpu_usage_percent(50., 100., 800.), 50.);
+        // The CPU usage can't go over the number of CPUs * 100.
+        assert_eq!(cpu_usage_percent(1_000., 1., 800.), 800.);
+        assert_eq!(cpu_usage_percent(f64::INFINITY, 1., 800.), 800.);
+    }
 }
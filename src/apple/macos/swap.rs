@@ -0,0 +1,36 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::SwapDevice;
+
+use std::fs;
+
+// The swap files are created (and removed) on demand by the dynamic pager in this folder.
+const SWAP_FOLDER: &str = "/private/var/vm";
+
+pub(crate) fn get_swap_devices() -> Vec<SwapDevice> {
+    let entries = match fs::read_dir(SWAP_FOLDER) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut devices = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            // The `sleepimage` file is in the same folder, so we filter it out.
+            if !entry.file_name().to_str()?.starts_with("swapfile") {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            Some(SwapDevice {
+                path: entry.path(),
+                total: metadata.len(),
+                used: None,
+                priority: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    devices.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    devices
+}
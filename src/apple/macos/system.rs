@@ -5,7 +5,7 @@ use libc::{mach_timebase_info, mach_timebase_info_data_t};
 
 use libc::{
     host_processor_info, mach_port_t, munmap, natural_t, processor_cpu_load_info,
-    processor_cpu_load_info_t, sysconf, vm_page_size, PROCESSOR_CPU_LOAD_INFO, _SC_CLK_TCK,
+    processor_cpu_load_info_t, sysconf, vm_page_size, _SC_CLK_TCK, PROCESSOR_CPU_LOAD_INFO,
 };
 use std::ptr::null_mut;
 
@@ -16,6 +16,21 @@ unsafe fn free_cpu_load_info(cpu_load: &mut processor_cpu_load_info_t) {
     }
 }
 
+/// Returns the ratio to convert mach absolute time units into nanoseconds.
+#[allow(deprecated)] // Everything related to mach_timebase_info_data_t
+pub(crate) fn get_timebase_to_ns() -> f64 {
+    static TIMEBASE_TO_NS: once_cell::sync::Lazy<f64> = once_cell::sync::Lazy::new(|| unsafe {
+        let mut info = mach_timebase_info_data_t { numer: 0, denom: 0 };
+        if mach_timebase_info(&mut info) != libc::KERN_SUCCESS || info.denom == 0 {
+            sysinfo_debug!("mach_timebase_info failed, using default value of 1");
+            1.
+        } else {
+            info.numer as f64 / info.denom as f64
+        }
+    });
+    *TIMEBASE_TO_NS
+}
+
 pub(crate) struct SystemTimeInfo {
     timebase_to_ns: f64,
     clock_per_sec: f64,
@@ -27,7 +42,6 @@ unsafe impl Send for SystemTimeInfo {}
 unsafe impl Sync for SystemTimeInfo {}
 
 impl SystemTimeInfo {
-    #[allow(deprecated)] // Everything related to mach_timebase_info_data_t
     pub fn new(port: mach_port_t) -> Option<Self> {
         unsafe {
             let clock_ticks_per_sec = sysconf(_SC_CLK_TCK);
@@ -42,13 +56,6 @@ impl SystemTimeInfo {
             //     }
             // }
 
-            let mut info = mach_timebase_info_data_t { numer: 0, denom: 0 };
-            if mach_timebase_info(&mut info) != libc::KERN_SUCCESS {
-                sysinfo_debug!("mach_timebase_info failed, using default value of 1");
-                info.numer = 1;
-                info.denom = 1;
-            }
-
             let mut old_cpu_load = null_mut();
             let old_cpu_count = match Self::update_ticks(port, &mut old_cpu_load) {
                 Some(c) => c,
@@ -61,7 +68,7 @@ impl SystemTimeInfo {
             let nano_per_seconds = 1_000_000_000.;
             sysinfo_debug!("");
             Some(Self {
-                timebase_to_ns: info.numer as f64 / info.denom as f64,
+                timebase_to_ns: get_timebase_to_ns(),
                 clock_per_sec: nano_per_seconds / clock_ticks_per_sec as f64,
                 old_cpu_load,
                 old_cpu_count,
@@ -4,7 +4,9 @@ use libc::{self, c_char, if_msghdr2, CTL_NET, NET_RT_IFLIST2, PF_ROUTE, RTM_IFIN
 
 use std::collections::{hash_map, HashMap};
 use std::ptr::null_mut;
+use std::time::Instant;
 
+use crate::utils::{keep_network_after_list_refresh, ElapsedTime};
 use crate::{NetworkExt, NetworksExt, NetworksIter};
 
 macro_rules! old_and_new {
@@ -62,6 +64,7 @@ impl Networks {
             let buf = buf.as_ptr() as *const c_char;
             let lim = buf.add(len);
             let mut next = buf;
+            let now = Instant::now();
             while next < lim {
                 let ifm = next as *const libc::if_msghdr;
                 next = next.offset((*ifm).ifm_msglen as isize);
@@ -119,6 +122,7 @@ impl Networks {
                                 old_errors_out,
                                 (*if2m).ifm_data.ifi_oerrors
                             );
+                            interface.elapsed.update(now);
                             interface.updated = true;
                         }
                         hash_map::Entry::Vacant(e) => {
@@ -142,7 +146,9 @@ impl Networks {
                                 old_errors_in: errors_in,
                                 errors_out,
                                 old_errors_out: errors_out,
+                                elapsed: ElapsedTime::new(now),
                                 updated: true,
+                                inactive_refreshes: 0,
                             });
                         }
                     }
@@ -163,7 +169,8 @@ impl NetworksExt for Networks {
             data.updated = false;
         }
         self.update_networks();
-        self.interfaces.retain(|_, data| data.updated);
+        self.interfaces
+            .retain(|_, data| data.keep_after_list_refresh());
     }
 
     fn refresh(&mut self) {
@@ -186,10 +193,36 @@ pub struct NetworkData {
     old_errors_in: u64,
     errors_out: u64,
     old_errors_out: u64,
+    elapsed: ElapsedTime,
     updated: bool,
+    // Number of calls to `refresh_networks_list` since the interface disappeared.
+    inactive_refreshes: u8,
+}
+
+impl NetworkData {
+    // Called at the end of `refresh_networks_list`. Returns `false` once the interface should be
+    // removed.
+    fn keep_after_list_refresh(&mut self) -> bool {
+        keep_network_after_list_refresh(
+            self.updated,
+            &mut self.inactive_refreshes,
+            &mut [
+                (&mut self.old_in, self.current_in),
+                (&mut self.old_out, self.current_out),
+                (&mut self.old_packets_in, self.packets_in),
+                (&mut self.old_packets_out, self.packets_out),
+                (&mut self.old_errors_in, self.errors_in),
+                (&mut self.old_errors_out, self.errors_out),
+            ],
+        )
+    }
 }
 
 impl NetworkExt for NetworkData {
+    fn is_active(&self) -> bool {
+        self.inactive_refreshes == 0
+    }
+
     fn received(&self) -> u64 {
         self.current_in.saturating_sub(self.old_in)
     }
@@ -206,6 +239,14 @@ impl NetworkExt for NetworkData {
         self.current_out
     }
 
+    fn received_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.received())
+    }
+
+    fn transmitted_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.transmitted())
+    }
+
     fn packets_received(&self) -> u64 {
         self.packets_in.saturating_sub(self.old_packets_in)
     }
@@ -7,16 +7,20 @@ use crate::sys::network::Networks;
 use crate::sys::process::*;
 
 use crate::{
-    CpuExt, CpuRefreshKind, LoadAvg, Pid, ProcessRefreshKind, RefreshKind, SystemExt, User,
+    Battery, CgroupLimits, CgroupStats, CpuExt, CpuRefreshKind, Error, LoadAvg, Pid, PressureStats,
+    ProcessRefreshKind, Protocol, RefreshKind, RefreshTimes, SwapDevice, SystemExt, Uid, User,
+    UserExt, Virtualization,
 };
 
+use crate::utils::{DiskListFilter, RefreshInterval, SwapActivity, UserNameCache};
 #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
 use crate::ProcessExt;
 
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
 use std::mem;
-use std::sync::Arc;
+use std::path::Path;
+use std::time::Duration;
 #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
 use std::time::SystemTime;
 
@@ -78,23 +82,32 @@ declare_signals! {
 #[doc = include_str!("../../md_doc/system.md")]
 pub struct System {
     process_list: HashMap<Pid, Process>,
+    process_errors: HashMap<Pid, Error>,
+    process_refresh_interval: RefreshInterval,
+    refresh_times: RefreshTimes,
     mem_total: u64,
     mem_free: u64,
     mem_available: u64,
     swap_total: u64,
     swap_free: u64,
+    swap_activity: SwapActivity,
     global_cpu: Cpu,
+    cpu_data: CpuData,
+    cpu_usage_history_capacity: usize,
     cpus: Vec<Cpu>,
-    page_size_kb: u64,
+    page_size: u64,
     #[cfg(not(any(target_os = "ios", feature = "apple-sandbox")))]
     components: Components,
     disks: Vec<Disk>,
     networks: Networks,
     port: mach_port_t,
     users: Vec<User>,
+    user_names: UserNameCache,
     boot_time: u64,
     #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
     clock_info: Option<crate::sys::macos::system::SystemTimeInfo>,
+    #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
+    last_gpu_refresh: Option<std::time::Instant>,
     got_cpu_frequency: bool,
 }
 
@@ -136,6 +149,42 @@ fn get_now() -> u64 {
         .unwrap_or(0)
 }
 
+#[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
+impl System {
+    /// It is sometime possible that a CPU usage computation is bigger than
+    /// `"number of CPUs" * 100`.
+    ///
+    /// To prevent that, we compute ahead of time this maximum value and ensure that processes'
+    /// CPU usage don't go over it.
+    fn get_max_process_cpu_usage(&self) -> f32 {
+        let nb_cpus = if self.cpus.is_empty() {
+            // The CPUs weren't retrieved yet.
+            unsafe { sysconf(libc::_SC_NPROCESSORS_ONLN) }.max(1) as usize
+        } else {
+            self.cpus.len()
+        };
+        nb_cpus as f32 * 100.
+    }
+
+    #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
+    fn refresh_gpu_usage(&mut self) {
+        let now = std::time::Instant::now();
+        let elapsed = self
+            .last_gpu_refresh
+            .replace(now)
+            .map(|last| now.duration_since(last).as_nanos() as u64)
+            .unwrap_or(0);
+        let gpu_times = crate::sys::macos::gpu::get_gpu_times();
+        for (pid, proc_) in self.process_list.iter_mut() {
+            // Processes which don't have GPU statistics didn't use it.
+            let gpu_time = gpu_times
+                .as_ref()
+                .map(|times| times.get(pid).copied().unwrap_or(0));
+            proc_.update_gpu_usage(gpu_time, elapsed);
+        }
+    }
+}
+
 impl SystemExt for System {
     const IS_SUPPORTED: bool = true;
     const SUPPORTED_SIGNALS: &'static [Signal] = supported_signals();
@@ -146,29 +195,32 @@ impl SystemExt for System {
 
             let mut s = System {
                 process_list: HashMap::with_capacity(200),
+                process_errors: HashMap::new(),
+                process_refresh_interval: RefreshInterval::default(),
+                refresh_times: RefreshTimes::default(),
                 mem_total: 0,
                 mem_free: 0,
                 mem_available: 0,
                 swap_total: 0,
                 swap_free: 0,
-                global_cpu: Cpu::new(
-                    "0".to_owned(),
-                    Arc::new(CpuData::new(std::ptr::null_mut(), 0)),
-                    0,
-                    String::new(),
-                    String::new(),
-                ),
+                swap_activity: SwapActivity::default(),
+                global_cpu: Cpu::new("0".to_owned(), 0, String::new(), String::new()),
+                cpu_data: CpuData::empty(),
+                cpu_usage_history_capacity: 0,
                 cpus: Vec::new(),
-                page_size_kb: sysconf(_SC_PAGESIZE) as _,
+                page_size: sysconf(_SC_PAGESIZE) as _,
                 #[cfg(not(any(target_os = "ios", feature = "apple-sandbox")))]
                 components: Components::new(),
                 disks: Vec::with_capacity(1),
                 networks: Networks::new(),
                 port,
                 users: Vec::new(),
+                user_names: UserNameCache::default(),
                 boot_time: boot_time(),
                 #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
                 clock_info: crate::sys::macos::system::SystemTimeInfo::new(port),
+                #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
+                last_gpu_refresh: None,
                 got_cpu_frequency: false,
             };
             s.refresh_specifics(refreshes);
@@ -177,6 +229,7 @@ impl SystemExt for System {
     }
 
     fn refresh_memory(&mut self) {
+        self.refresh_times.memory = Some(std::time::Instant::now());
         let mut mib = [0, 0];
 
         unsafe {
@@ -226,9 +279,11 @@ impl SystemExt for System {
                         .saturating_add(u64::from(stat.wire_count))
                         .saturating_add(u64::from(stat.speculative_count))
                         .saturating_sub(u64::from(stat.purgeable_count))
-                        .saturating_mul(self.page_size_kb),
+                        .saturating_mul(self.page_size),
                 );
-                self.mem_free = u64::from(stat.free_count).saturating_mul(self.page_size_kb);
+                self.mem_free = u64::from(stat.free_count).saturating_mul(self.page_size);
+                self.swap_activity
+                    .update(stat.swapins, stat.swapouts, std::time::Instant::now());
             }
         }
     }
@@ -242,9 +297,20 @@ impl SystemExt for System {
     }
 
     fn refresh_cpu_specifics(&mut self, refresh_kind: CpuRefreshKind) {
+        self.refresh_times.cpu = Some(std::time::Instant::now());
         let cpus = &mut self.cpus;
         if cpus.is_empty() {
-            init_cpus(self.port, cpus, &mut self.global_cpu, refresh_kind);
+            init_cpus(
+                self.port,
+                cpus,
+                &mut self.global_cpu,
+                &mut self.cpu_data,
+                refresh_kind,
+            );
+            for cpu in cpus.iter_mut() {
+                cpu.usage_history
+                    .set_capacity(self.cpu_usage_history_capacity);
+            }
             self.got_cpu_frequency = refresh_kind.frequency();
             return;
         }
@@ -256,28 +322,55 @@ impl SystemExt for System {
             self.got_cpu_frequency = true;
         }
         if refresh_kind.cpu_usage() {
-            update_cpu_usage(self.port, &mut self.global_cpu, |proc_data, cpu_info| {
-                let mut percentage = 0f32;
-                let mut offset = 0;
-                for proc_ in cpus.iter_mut() {
-                    let cpu_usage = compute_usage_of_cpu(proc_, cpu_info, offset);
-                    proc_.update(cpu_usage, Arc::clone(&proc_data));
-                    percentage += proc_.cpu_usage();
-
-                    offset += libc::CPU_STATE_MAX as isize;
-                }
-                (percentage, cpus.len())
-            });
+            update_cpu_usage(
+                self.port,
+                &mut self.global_cpu,
+                &mut self.cpu_data,
+                |old_cpu_info, cpu_info| {
+                    let mut percentage = 0f32;
+                    let mut states_usage = CpuStatesUsage::default();
+                    let mut offset = 0;
+                    for proc_ in cpus.iter_mut() {
+                        let (cpu_usage, cpu_states_usage) =
+                            compute_usage_of_cpu(old_cpu_info, cpu_info, offset);
+                        proc_.update(cpu_usage, cpu_states_usage);
+                        percentage += proc_.cpu_usage();
+                        states_usage.add(proc_.states_usage());
+
+                        offset += libc::CPU_STATE_MAX as isize;
+                    }
+                    (percentage, states_usage, cpus.len())
+                },
+            );
+        }
+    }
+
+    fn set_cpu_usage_history_capacity(&mut self, capacity: usize) {
+        self.cpu_usage_history_capacity = capacity;
+        self.global_cpu.usage_history.set_capacity(capacity);
+        for cpu in self.cpus.iter_mut() {
+            cpu.usage_history.set_capacity(capacity);
         }
     }
 
     #[cfg(any(target_os = "ios", feature = "apple-sandbox"))]
-    fn refresh_processes_specifics(&mut self, _refresh_kind: ProcessRefreshKind) {}
+    fn refresh_processes_specifics(&mut self, _refresh_kind: ProcessRefreshKind) {
+        self.process_refresh_interval
+            .should_refresh(std::time::Instant::now());
+    }
 
     #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
     fn refresh_processes_specifics(&mut self, refresh_kind: ProcessRefreshKind) {
         use crate::utils::into_iter;
 
+        if !self
+            .process_refresh_interval
+            .should_refresh(std::time::Instant::now())
+        {
+            return;
+        }
+        self.refresh_times.processes = Some(std::time::Instant::now());
+        self.process_errors.clear();
         unsafe {
             let count = libc::proc_listallpids(::std::ptr::null_mut(), 0);
             if count < 1 {
@@ -289,37 +382,64 @@ impl SystemExt for System {
             let arg_max = get_arg_max();
             let port = self.port;
             let time_interval = self.clock_info.as_mut().map(|c| c.get_time_interval(port));
-            let entries: Vec<Process> = {
+            let max_cpu_usage = self.get_max_process_cpu_usage();
+            let entries: Vec<Result<Process, (Pid, Error)>> = {
                 let wrap = &Wrap(UnsafeCell::new(&mut self.process_list));
 
                 #[cfg(feature = "multithread")]
                 use rayon::iter::ParallelIterator;
 
                 into_iter(pids)
-                    .flat_map(|pid| {
+                    .filter_map(|pid| {
                         match update_process(
                             wrap,
                             pid,
                             arg_max as size_t,
                             time_interval,
+                            max_cpu_usage,
                             now,
                             refresh_kind,
                             false,
                         ) {
-                            Ok(x) => x,
-                            _ => None,
+                            Ok(x) => x.map(Ok),
+                            Err(err) => Some(Err((pid, err))),
                         }
                     })
                     .collect()
             };
-            entries.into_iter().for_each(|entry| {
-                self.process_list.insert(entry.pid(), entry);
-            });
+            for entry in entries {
+                match entry {
+                    Ok(entry) => {
+                        self.process_list.insert(entry.pid(), entry);
+                    }
+                    Err((pid, err)) => {
+                        self.process_errors.insert(pid, err);
+                    }
+                }
+            }
             self.process_list
                 .retain(|_, proc_| std::mem::replace(&mut proc_.updated, false));
+            if refresh_kind.gpu() {
+                self.refresh_gpu_usage();
+            } else {
+                // The GPU times will be compared again from the next refresh asking for them.
+                self.last_gpu_refresh = None;
+            }
         }
     }
 
+    fn set_process_refresh_interval(&mut self, interval: Duration) {
+        self.process_refresh_interval.set_interval(interval);
+    }
+
+    fn processes_refresh_skipped(&self) -> bool {
+        self.process_refresh_interval.skipped()
+    }
+
+    fn refresh_times(&self) -> RefreshTimes {
+        self.refresh_times
+    }
+
     #[cfg(any(target_os = "ios", feature = "apple-sandbox"))]
     fn refresh_process_specifics(&mut self, _pid: Pid, _refresh_kind: ProcessRefreshKind) -> bool {
         false
@@ -327,10 +447,12 @@ impl SystemExt for System {
 
     #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
     fn refresh_process_specifics(&mut self, pid: Pid, refresh_kind: ProcessRefreshKind) -> bool {
+        self.process_errors.clear();
         let now = get_now();
         let arg_max = get_arg_max();
         let port = self.port;
         let time_interval = self.clock_info.as_mut().map(|c| c.get_time_interval(port));
+        let max_cpu_usage = self.get_max_process_cpu_usage();
         match {
             let wrap = Wrap(UnsafeCell::new(&mut self.process_list));
             update_process(
@@ -338,6 +460,7 @@ impl SystemExt for System {
                 pid,
                 arg_max as size_t,
                 time_interval,
+                max_cpu_usage,
                 now,
                 refresh_kind,
                 true,
@@ -348,16 +471,67 @@ impl SystemExt for System {
                 true
             }
             Ok(_) => true,
-            Err(_) => false,
+            Err(err) => {
+                self.process_errors.insert(pid, err);
+                false
+            }
+        }
+    }
+
+    #[cfg(any(target_os = "ios", feature = "apple-sandbox"))]
+    fn refresh_pids_specifics(&mut self, _pids: &[Pid], _refresh_kind: ProcessRefreshKind) {}
+
+    #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
+    fn refresh_pids_specifics(&mut self, pids: &[Pid], refresh_kind: ProcessRefreshKind) {
+        self.process_errors.clear();
+        let now = get_now();
+        let arg_max = get_arg_max();
+        let port = self.port;
+        // The time interval is shared by all processes so their CPU usage is computed over the
+        // same period.
+        let time_interval = self.clock_info.as_mut().map(|c| c.get_time_interval(port));
+        let max_cpu_usage = self.get_max_process_cpu_usage();
+        for pid in pids {
+            let entry = {
+                let wrap = Wrap(UnsafeCell::new(&mut self.process_list));
+                update_process(
+                    &wrap,
+                    *pid,
+                    arg_max as size_t,
+                    time_interval,
+                    max_cpu_usage,
+                    now,
+                    refresh_kind,
+                    true,
+                )
+            };
+            match entry {
+                Ok(Some(p)) => {
+                    self.process_list.insert(p.pid(), p);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    self.process_errors.insert(*pid, err);
+                }
+            }
         }
     }
 
     fn refresh_disks_list(&mut self) {
-        self.disks = unsafe { get_disks() };
+        self.disks = unsafe { get_disks(DiskListFilter::Default) };
+    }
+
+    fn refresh_disks_list_local_only(&mut self) {
+        self.disks = unsafe { get_disks(DiskListFilter::LocalOnly) };
+    }
+
+    fn refresh_disks_list_with_all_file_systems(&mut self) {
+        self.disks = unsafe { get_disks(DiskListFilter::All) };
     }
 
     fn refresh_users_list(&mut self) {
         self.users = crate::apple::users::get_users_list();
+        self.user_names.clear();
     }
 
     // COMMON PART
@@ -372,6 +546,24 @@ impl SystemExt for System {
         self.process_list.get(&pid)
     }
 
+    fn last_errors(&self) -> &HashMap<Pid, Error> {
+        &self.process_errors
+    }
+
+    #[cfg(all(target_os = "macos", not(feature = "apple-sandbox")))]
+    fn process_by_port(&self, port: u16, protocol: Protocol) -> Option<&Process> {
+        let mut pids = self.process_list.keys().collect::<Vec<_>>();
+        pids.sort_unstable();
+        pids.into_iter()
+            .find(|pid| crate::sys::inner::process::has_socket_on_port(**pid, port, protocol))
+            .and_then(|pid| self.process(*pid))
+    }
+
+    #[cfg(not(all(target_os = "macos", not(feature = "apple-sandbox"))))]
+    fn process_by_port(&self, _port: u16, _protocol: Protocol) -> Option<&Process> {
+        None
+    }
+
     fn global_cpu_info(&self) -> &Cpu {
         &self.global_cpu
     }
@@ -380,6 +572,10 @@ impl SystemExt for System {
         &self.cpus
     }
 
+    fn cpu_count(&self) -> usize {
+        unsafe { sysconf(libc::_SC_NPROCESSORS_ONLN) }.max(1) as usize
+    }
+
     fn physical_core_count(&self) -> Option<usize> {
         let mut physical_core_count = 0;
 
@@ -404,6 +600,27 @@ impl SystemExt for System {
         &mut self.networks
     }
 
+    fn max_pid(&self) -> Option<Pid> {
+        // `PID_MAX` in the XNU kernel. It isn't exposed through `sysctl`.
+        Some(Pid(99_999))
+    }
+
+    fn read_sysctl(&self, name: &str) -> Option<i64> {
+        crate::utils::read_sysctl_integer(name)
+    }
+
+    fn read_proc_value(&self, _path: &str) -> Option<String> {
+        None
+    }
+
+    fn context_switches(&self) -> Option<u64> {
+        None
+    }
+
+    fn interrupts(&self) -> Option<u64> {
+        None
+    }
+
     fn total_memory(&self) -> u64 {
         self.mem_total
     }
@@ -417,7 +634,7 @@ impl SystemExt for System {
     }
 
     fn used_memory(&self) -> u64 {
-        self.mem_total - self.mem_free
+        self.mem_total.saturating_sub(self.mem_free)
     }
 
     fn total_swap(&self) -> u64 {
@@ -430,7 +647,56 @@ impl SystemExt for System {
 
     // TODO: need to be checked
     fn used_swap(&self) -> u64 {
-        self.swap_total - self.swap_free
+        self.swap_total.saturating_sub(self.swap_free)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn swap_devices(&self) -> Vec<SwapDevice> {
+        crate::sys::inner::swap::get_swap_devices()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn swap_devices(&self) -> Vec<SwapDevice> {
+        Vec::new()
+    }
+
+    fn is_swap_encrypted(&self) -> Option<bool> {
+        let mut mib = [0, 0];
+
+        unsafe {
+            let mut xs: libc::xsw_usage = mem::zeroed::<libc::xsw_usage>();
+            if get_sys_value(
+                libc::CTL_VM as _,
+                libc::VM_SWAPUSAGE as _,
+                mem::size_of::<libc::xsw_usage>(),
+                &mut xs as *mut _ as *mut c_void,
+                &mut mib,
+            ) {
+                Some(xs.xsu_encrypted != 0)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn page_size(&self) -> u64 {
+        self.page_size
+    }
+
+    fn swap_in_per_second(&self) -> f64 {
+        self.swap_activity.in_per_second
+    }
+
+    fn swap_out_per_second(&self) -> f64 {
+        self.swap_activity.out_per_second
+    }
+
+    fn swap_in(&self) -> u64 {
+        self.swap_activity.swapped_in
+    }
+
+    fn swap_out(&self) -> u64 {
+        self.swap_activity.swapped_out
     }
 
     #[cfg(not(any(target_os = "ios", feature = "apple-sandbox")))]
@@ -453,6 +719,34 @@ impl SystemExt for System {
         &mut []
     }
 
+    #[cfg(not(any(target_os = "ios", feature = "apple-sandbox")))]
+    fn sensors_available(&self) -> bool {
+        self.components.is_available()
+    }
+
+    #[cfg(any(target_os = "ios", feature = "apple-sandbox"))]
+    fn sensors_available(&self) -> bool {
+        false
+    }
+
+    #[cfg(not(any(target_os = "ios", feature = "apple-sandbox")))]
+    fn cpu_temperature(&self) -> Option<f32> {
+        self.components.cpu_temperature()
+    }
+
+    #[cfg(any(target_os = "ios", feature = "apple-sandbox"))]
+    fn cpu_temperature(&self) -> Option<f32> {
+        None
+    }
+
+    #[cfg(all(
+        not(any(target_os = "ios", feature = "apple-sandbox")),
+        any(target_arch = "x86", target_arch = "x86_64")
+    ))]
+    fn sensors(&self) -> Vec<crate::Sensor> {
+        self.components.sensors()
+    }
+
     fn disks(&self) -> &[Disk] {
         &self.disks
     }
@@ -461,6 +755,10 @@ impl SystemExt for System {
         &mut self.disks
     }
 
+    fn file_systems(&self) -> Vec<Disk> {
+        unsafe { get_disks(DiskListFilter::All) }
+    }
+
     fn sort_disks_by<F>(&mut self, compare: F)
     where
         F: FnMut(&Disk, &Disk) -> std::cmp::Ordering,
@@ -489,10 +787,48 @@ impl SystemExt for System {
         }
     }
 
+    fn cgroup_stats(&self, _path: &Path) -> Option<CgroupStats> {
+        None
+    }
+
+    fn cgroup_limits(&self) -> Option<CgroupLimits> {
+        None
+    }
+
+    fn pressure(&self) -> Option<PressureStats> {
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    fn battery(&self) -> Option<Battery> {
+        crate::sys::inner::battery::get_battery()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn battery(&self) -> Option<Battery> {
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    fn on_ac_power(&self) -> Option<bool> {
+        crate::sys::inner::battery::get_on_ac_power()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn on_ac_power(&self) -> Option<bool> {
+        None
+    }
+
     fn users(&self) -> &[User] {
         &self.users
     }
 
+    fn get_user_name_by_id(&self, user_id: &Uid) -> Option<String> {
+        self.get_user_by_id(user_id)
+            .map(|user| user.name().to_owned())
+            .or_else(|| self.user_names.get(user_id))
+    }
+
     fn boot_time(&self) -> u64 {
         self.boot_time
     }
@@ -547,6 +883,34 @@ impl SystemExt for System {
         get_system_info(libc::KERN_HOSTNAME, None)
     }
 
+    #[cfg(target_os = "ios")]
+    fn virtualization(&self) -> Option<Virtualization> {
+        None
+    }
+
+    #[cfg(not(target_os = "ios"))]
+    fn virtualization(&self) -> Option<Virtualization> {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if let Some(hypervisor) = crate::utils::cpuid_hypervisor() {
+            return Some(hypervisor);
+        }
+        // Apple Silicon doesn't provide CPUID, so we can only know that there is a hypervisor.
+        let mut present: c_int = 0;
+        let mut len = mem::size_of::<c_int>();
+        unsafe {
+            if get_sys_value_by_name(
+                b"kern.hv_vmm_present\0",
+                &mut len,
+                &mut present as *mut c_int as *mut c_void,
+            ) && present != 0
+            {
+                Some(Virtualization::Other)
+            } else {
+                None
+            }
+        }
+    }
+
     fn kernel_version(&self) -> Option<String> {
         get_system_info(libc::KERN_OSRELEASE, None)
     }
@@ -689,3 +1053,21 @@ fn get_system_info(value: c_int, default: Option<&str>) -> Option<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::System;
+    use crate::SystemExt;
+
+    #[test]
+    fn check_inconsistent_memory_values() {
+        let mut s = System::new();
+        // The kernel could report more free memory/swap than the total.
+        s.mem_total = 10;
+        s.mem_free = 20;
+        s.swap_total = 10;
+        s.swap_free = 20;
+        assert_eq!(s.used_memory(), 0);
+        assert_eq!(s.used_swap(), 0);
+    }
+}
@@ -1,10 +1,17 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use crate::{NetworkData, Networks, NetworksExt, UserExt};
+use crate::{
+    Component, ComponentExt, Cpu, CpuExt, Disk, DiskExt, NetworkData, NetworkExt, Networks,
+    NetworksExt, Process, ProcessExt, SystemExt, UserExt,
+};
 
+use std::collections::HashMap;
 use std::convert::From;
+use std::ffi::OsString;
 use std::fmt;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 /// Trait to have a common conversions for the [`Pid`][crate::Pid] type.
 ///
@@ -231,10 +238,21 @@ pub struct ProcessRefreshKind {
     cpu: bool,
     disk_usage: bool,
     user: bool,
+    // Stored the other way around so that the command line is retrieved by default.
+    without_cmd: bool,
+    kernel_threads: bool,
+    threads: bool,
+    open_files: bool,
+    network_usage: bool,
+    wait_channel: bool,
+    tracer: bool,
+    is_64bit: bool,
+    gpu: bool,
 }
 
 impl ProcessRefreshKind {
-    /// Creates a new `ProcessRefreshKind` with every refresh set to `false`.
+    /// Creates a new `ProcessRefreshKind` with every refresh set to `false`, except for
+    /// [`cmd`][ProcessRefreshKind::cmd].
     ///
     /// ```
     /// use sysinfo::ProcessRefreshKind;
@@ -243,12 +261,16 @@ impl ProcessRefreshKind {
     ///
     /// assert_eq!(r.cpu(), false);
     /// assert_eq!(r.disk_usage(), false);
+    /// assert_eq!(r.cmd(), true);
     /// ```
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Creates a new `ProcessRefreshKind` with every refresh set to `true`.
+    /// Creates a new `ProcessRefreshKind` with every refresh set to `true`, except for
+    /// [`kernel_threads`][ProcessRefreshKind::kernel_threads],
+    /// [`open_files`][ProcessRefreshKind::open_files] and
+    /// [`network_usage`][ProcessRefreshKind::network_usage].
     ///
     /// ```
     /// use sysinfo::ProcessRefreshKind;
@@ -257,12 +279,24 @@ impl ProcessRefreshKind {
     ///
     /// assert_eq!(r.cpu(), true);
     /// assert_eq!(r.disk_usage(), true);
+    /// assert_eq!(r.kernel_threads(), false);
+    /// assert_eq!(r.open_files(), false);
+    /// assert_eq!(r.network_usage(), false);
     /// ```
     pub fn everything() -> Self {
         Self {
             cpu: true,
             disk_usage: true,
             user: true,
+            without_cmd: false,
+            kernel_threads: false,
+            threads: true,
+            open_files: false,
+            network_usage: false,
+            wait_channel: true,
+            tracer: true,
+            is_64bit: true,
+            gpu: true,
         }
     }
 
@@ -281,6 +315,271 @@ impl ProcessRefreshKind {
         r#"This refresh is about `user_id` and `group_id`. Please note that it has an effect mostly
 on Windows as other platforms get this information alongside the Process information directly."#,
     );
+    impl_get_set!(
+        ProcessRefreshKind,
+        threads,
+        with_threads,
+        without_threads,
+        r#"This refresh is about [`ProcessExt::threads`]. Retrieving the information of each thread
+is expensive on macOS so it should only be enabled when needed. On Linux, the `tasks` of each
+process are retrieved in any case, only the information of the main thread depends on it.
+
+[`ProcessExt::threads`]: crate::ProcessExt::threads"#,
+    );
+    impl_get_set!(
+        ProcessRefreshKind,
+        wait_channel,
+        with_wait_channel,
+        without_wait_channel,
+        r#"This refresh is about [`ProcessExt::wait_channel`]. On Linux, it requires to read the
+`/proc/[pid]/wchan` file of each process (but not of their tasks).
+
+[`ProcessExt::wait_channel`]: crate::ProcessExt::wait_channel"#,
+    );
+    impl_get_set!(
+        ProcessRefreshKind,
+        tracer,
+        with_tracer,
+        without_tracer,
+        r#"This refresh is about [`ProcessExt::is_being_traced`] and [`ProcessExt::tracer_pid`]. It
+only has an effect on Linux, where it requires to read the `/proc/[pid]/status` file of each
+process (but not of their tasks).
+
+[`ProcessExt::is_being_traced`]: crate::ProcessExt::is_being_traced
+[`ProcessExt::tracer_pid`]: crate::ProcessExt::tracer_pid"#,
+    );
+    impl_get_set!(
+        ProcessRefreshKind,
+        is_64bit,
+        with_is_64bit,
+        without_is_64bit,
+        r#"This refresh is about [`ProcessExt::is_64bit`]. It only has an effect on Linux, where it
+requires to read the header of the executable of each new process.
+
+[`ProcessExt::is_64bit`]: crate::ProcessExt::is_64bit"#,
+    );
+    impl_get_set!(
+        ProcessRefreshKind,
+        gpu,
+        with_gpu,
+        without_gpu,
+        r#"This refresh is about [`ProcessExt::gpu_usage`]. It only has an effect on macOS, where
+it requires to walk the IOKit registry.
+
+[`ProcessExt::gpu_usage`]: crate::ProcessExt::gpu_usage"#,
+    );
+
+    /// Returns the value of the "cmd" refresh kind.
+    ///
+    /// This refresh is about `cmd` and `environ` (and `cwd` on Windows). Skipping it saves a lot
+    /// of memory and CPU time when there are many processes, in particular on macOS where the
+    /// arguments of each process have to be retrieved and parsed.
+    ///
+    /// Unlike the other refresh kinds, it is enabled by default (so in
+    /// [`ProcessRefreshKind::new`] too) and needs to be disabled explicitly.
+    ///
+    /// Since the command line of a process doesn't change, it is only retrieved when the process
+    /// is first seen: if it was skipped then, it stays empty.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new();
+    /// assert_eq!(r.cmd(), true);
+    ///
+    /// let r = r.without_cmd();
+    /// assert_eq!(r.cmd(), false);
+    ///
+    /// let r = r.with_cmd();
+    /// assert_eq!(r.cmd(), true);
+    /// ```
+    pub fn cmd(&self) -> bool {
+        !self.without_cmd
+    }
+
+    /// Sets the value of the "cmd" refresh kind to `true`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new().without_cmd().with_cmd();
+    /// assert_eq!(r.cmd(), true);
+    /// ```
+    #[must_use]
+    pub fn with_cmd(mut self) -> Self {
+        self.without_cmd = false;
+        self
+    }
+
+    /// Sets the value of the "cmd" refresh kind to `false`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::everything().without_cmd();
+    /// assert_eq!(r.cmd(), false);
+    /// ```
+    #[must_use]
+    pub fn without_cmd(mut self) -> Self {
+        self.without_cmd = true;
+        self
+    }
+
+    /// Returns the value of the "kernel_threads" refresh kind.
+    ///
+    /// When it is `false`, kernel threads (`kthreadd` and its children) are left out of
+    /// [`SystemExt::processes`] by [`SystemExt::refresh_processes_specifics`]. Since it filters
+    /// processes instead of retrieving information about them, it is disabled in
+    /// [`ProcessRefreshKind::everything`] too and needs to be enabled explicitly.
+    ///
+    /// Processes explicitly refreshed with [`SystemExt::refresh_process_specifics`] are never
+    /// filtered out.
+    ///
+    /// ⚠️ It only has an effect on Linux.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::everything();
+    /// assert_eq!(r.kernel_threads(), false);
+    ///
+    /// let r = r.with_kernel_threads();
+    /// assert_eq!(r.kernel_threads(), true);
+    /// ```
+    ///
+    /// [`SystemExt::processes`]: crate::SystemExt::processes
+    /// [`SystemExt::refresh_processes_specifics`]: crate::SystemExt::refresh_processes_specifics
+    /// [`SystemExt::refresh_process_specifics`]: crate::SystemExt::refresh_process_specifics
+    pub fn kernel_threads(&self) -> bool {
+        self.kernel_threads
+    }
+
+    /// Sets the value of the "kernel_threads" refresh kind to `true`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new().with_kernel_threads();
+    /// assert_eq!(r.kernel_threads(), true);
+    /// ```
+    #[must_use]
+    pub fn with_kernel_threads(mut self) -> Self {
+        self.kernel_threads = true;
+        self
+    }
+
+    /// Sets the value of the "kernel_threads" refresh kind to `false`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new().with_kernel_threads().without_kernel_threads();
+    /// assert_eq!(r.kernel_threads(), false);
+    /// ```
+    #[must_use]
+    pub fn without_kernel_threads(mut self) -> Self {
+        self.kernel_threads = false;
+        self
+    }
+
+    /// Returns the value of the "open_files" refresh kind.
+    ///
+    /// This refresh is about [`ProcessExt::open_files`]. On Linux, it requires to list the
+    /// `/proc/[pid]/fd` folder of each process, which is costly for processes with a lot of
+    /// file descriptors. Because of that, it is disabled in [`ProcessRefreshKind::everything`]
+    /// and needs to be enabled explicitly.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::everything();
+    /// assert_eq!(r.open_files(), false);
+    ///
+    /// let r = r.with_open_files();
+    /// assert_eq!(r.open_files(), true);
+    /// ```
+    ///
+    /// [`ProcessExt::open_files`]: crate::ProcessExt::open_files
+    pub fn open_files(&self) -> bool {
+        self.open_files
+    }
+
+    /// Sets the value of the "open_files" refresh kind to `true`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new().with_open_files();
+    /// assert_eq!(r.open_files(), true);
+    /// ```
+    #[must_use]
+    pub fn with_open_files(mut self) -> Self {
+        self.open_files = true;
+        self
+    }
+
+    /// Sets the value of the "open_files" refresh kind to `false`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new().with_open_files().without_open_files();
+    /// assert_eq!(r.open_files(), false);
+    /// ```
+    #[must_use]
+    pub fn without_open_files(mut self) -> Self {
+        self.open_files = false;
+        self
+    }
+
+    /// Returns the value of the "network_usage" refresh kind.
+    ///
+    /// This refresh is about [`ProcessExt::network_usage`]. On Linux, it requires to read the
+    /// network namespace and the `net/dev` file of each process. Because of that, it is disabled
+    /// in [`ProcessRefreshKind::everything`] and needs to be enabled explicitly.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::everything();
+    /// assert_eq!(r.network_usage(), false);
+    ///
+    /// let r = r.with_network_usage();
+    /// assert_eq!(r.network_usage(), true);
+    /// ```
+    ///
+    /// [`ProcessExt::network_usage`]: crate::ProcessExt::network_usage
+    pub fn network_usage(&self) -> bool {
+        self.network_usage
+    }
+
+    /// Sets the value of the "network_usage" refresh kind to `true`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new().with_network_usage();
+    /// assert_eq!(r.network_usage(), true);
+    /// ```
+    #[must_use]
+    pub fn with_network_usage(mut self) -> Self {
+        self.network_usage = true;
+        self
+    }
+
+    /// Sets the value of the "network_usage" refresh kind to `false`.
+    ///
+    /// ```
+    /// use sysinfo::ProcessRefreshKind;
+    ///
+    /// let r = ProcessRefreshKind::new().with_network_usage().without_network_usage();
+    /// assert_eq!(r.network_usage(), false);
+    /// ```
+    #[must_use]
+    pub fn without_network_usage(mut self) -> Self {
+        self.network_usage = false;
+        self
+    }
 }
 
 /// Used to determine what you want to refresh specifically on the [`Cpu`] type.
@@ -460,6 +759,63 @@ impl RefreshKind {
     impl_get_set!(RefreshKind, users_list, with_users_list, without_users_list);
 }
 
+/// Type containing when the information of the [`System`] was last refreshed.
+///
+/// It is returned by [`SystemExt::refresh_times`][crate::SystemExt::refresh_times].
+///
+/// Disks, networks and components aren't part of it because they can be refreshed directly
+/// (with [`NetworksExt::refresh`][crate::NetworksExt::refresh] for example).
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let mut s = System::new();
+/// s.refresh_memory();
+/// if let Some(instant) = s.refresh_times().memory() {
+///     println!("memory refreshed {:?} ago", instant.elapsed());
+/// }
+/// ```
+///
+/// [`System`]: crate::System
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RefreshTimes {
+    pub(crate) memory: Option<Instant>,
+    pub(crate) cpu: Option<Instant>,
+    pub(crate) processes: Option<Instant>,
+}
+
+impl RefreshTimes {
+    /// Returns when the memory and swap information was last refreshed (with
+    /// [`SystemExt::refresh_memory`][crate::SystemExt::refresh_memory] for example), or `None`
+    /// if it never was.
+    pub fn memory(&self) -> Option<Instant> {
+        self.memory
+    }
+
+    /// Returns when the CPU information was last refreshed (with
+    /// [`SystemExt::refresh_cpu`][crate::SystemExt::refresh_cpu] for example), or `None` if it
+    /// never was.
+    pub fn cpu(&self) -> Option<Instant> {
+        self.cpu
+    }
+
+    /// Returns when the process list was last refreshed (with
+    /// [`SystemExt::refresh_processes`][crate::SystemExt::refresh_processes] for example), or
+    /// `None` if it never was.
+    ///
+    /// Refreshing only some processes (with
+    /// [`SystemExt::refresh_process`][crate::SystemExt::refresh_process] for example) doesn't
+    /// update it.
+    pub fn processes(&self) -> Option<Instant> {
+        self.processes
+    }
+
+    /// Returns the most recent of the other values.
+    pub fn last(&self) -> Option<Instant> {
+        self.memory.max(self.cpu).max(self.processes)
+    }
+}
+
 /// Iterator over network interfaces.
 ///
 /// It is returned by [`Networks::iter`][crate::Networks#method.iter].
@@ -499,7 +855,8 @@ impl<'a> IntoIterator for &'a Networks {
 
 /// Enum containing the different supported disks types.
 ///
-/// This type is returned by [`Disk::get_type`][crate::Disk#method.type].
+/// This type is returned by [`Disk::get_type`][crate::Disk#method.type]. To know if a disk is
+/// removable, take a look at [`DiskExt::is_removable`][crate::DiskExt::is_removable].
 ///
 /// ```no_run
 /// use sysinfo::{System, SystemExt, DiskExt};
@@ -519,6 +876,46 @@ pub enum DiskType {
     Unknown(isize),
 }
 
+/// Transport protocol of a socket.
+///
+/// It is used by [`SystemExt::process_by_port`][crate::SystemExt::process_by_port].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Protocol {
+    /// TCP socket.
+    Tcp,
+    /// UDP socket.
+    Udp,
+}
+
+/// Hypervisor the system is running on.
+///
+/// It is returned by [`SystemExt::virtualization`][crate::SystemExt::virtualization].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let s = System::new();
+/// match s.virtualization() {
+///     Some(hypervisor) => println!("running on {:?}", hypervisor),
+///     None => println!("running on a physical machine"),
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Virtualization {
+    /// KVM (and QEMU with KVM acceleration).
+    Kvm,
+    /// VMware.
+    VMware,
+    /// Oracle VirtualBox.
+    VirtualBox,
+    /// Xen.
+    Xen,
+    /// Microsoft Hyper-V.
+    HyperV,
+    /// A hypervisor which isn't part of this list or couldn't be identified.
+    Other,
+}
+
 /// An enum representing signals on UNIX-like systems.
 ///
 /// On non-unix systems, this enum is mostly useless and is only there to keep coherency between
@@ -663,6 +1060,21 @@ pub struct LoadAvg {
     pub fifteen: f64,
 }
 
+impl LoadAvg {
+    // Divides each value by `count`. Returns `None` if `count` is 0.
+    pub(crate) fn per_core(&self, count: usize) -> Option<LoadAvg> {
+        if count == 0 {
+            return None;
+        }
+        let count = count as f64;
+        Some(LoadAvg {
+            one: self.one / count,
+            five: self.five / count,
+            fifteen: self.fifteen / count,
+        })
+    }
+}
+
 macro_rules! xid {
     ($(#[$outer:meta])+ $name:ident, $type:ty) => {
         $(#[$outer])+
@@ -803,6 +1215,568 @@ pub struct DiskUsage {
     pub read_bytes: u64,
 }
 
+/// Type containing the resources usage of a control group.
+///
+/// It is returned by [`SystemExt::cgroup_stats`][crate::SystemExt::cgroup_stats].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let s = System::new();
+/// if let Some(stats) = s.cgroup_stats("/system.slice".as_ref()) {
+///     println!("CPU time: {} µs", stats.cpu_usage_usec);
+///     println!("memory: {} B (limit: {:?})", stats.memory_current, stats.memory_max);
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CgroupStats {
+    /// Total CPU time consumed by the tasks of the cgroup, in microseconds.
+    pub cpu_usage_usec: u64,
+    /// Memory currently used by the cgroup, in bytes.
+    pub memory_current: u64,
+    /// Memory limit of the cgroup, in bytes. `None` means that there is no limit.
+    pub memory_max: Option<u64>,
+}
+
+/// Type containing the resources limits of the control group the current process is running in.
+///
+/// It is returned by [`SystemExt::cgroup_limits`][crate::SystemExt::cgroup_limits].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let mut s = System::new();
+/// s.refresh_memory();
+/// if let Some(limits) = s.cgroup_limits() {
+///     println!("memory: {} B (free: {} B)", limits.total_memory, limits.free_memory);
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CgroupLimits {
+    /// Memory available to the cgroup, in bytes. It is never greater than the total memory of
+    /// the system.
+    pub total_memory: u64,
+    /// Memory not used by the cgroup yet, in bytes.
+    pub free_memory: u64,
+    /// Maximum number of CPUs the cgroup can use (`1.5` means one CPU and a half). `None` means
+    /// that there is no limit.
+    pub cpu_quota: Option<f64>,
+}
+
+/// Type containing the pressure stall information (PSI) of the CPU, memory and I/O resources.
+///
+/// It is returned by [`SystemExt::pressure`][crate::SystemExt::pressure].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let mut s = System::new();
+/// s.refresh_cpu();
+/// if let Some(pressure) = s.pressure() {
+///     println!("memory stalled during the last 10 seconds: {}%", pressure.memory.some.avg10);
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PressureStats {
+    /// Pressure on the CPU.
+    pub cpu: Pressure,
+    /// Pressure on the memory.
+    pub memory: Pressure,
+    /// Pressure on the I/O.
+    pub io: Pressure,
+}
+
+/// Type containing the pressure stall information of one resource.
+///
+/// It is part of [`PressureStats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Pressure {
+    /// Share of time during which at least one task was stalled on the resource.
+    pub some: PressureAvg,
+    /// Share of time during which all non-idle tasks were stalled on the resource at the same
+    /// time. Its values are `0` if the kernel doesn't provide them (for the CPU before Linux
+    /// 5.13).
+    pub full: PressureAvg,
+}
+
+/// Type containing the averages of a [`Pressure`] value, in percent.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PressureAvg {
+    /// Average within the last ten seconds.
+    pub avg10: f64,
+    /// Average within the last minute.
+    pub avg60: f64,
+    /// Average within the last five minutes.
+    pub avg300: f64,
+}
+
+/// Type containing the charge information of the battery of the system.
+///
+/// It is returned by [`SystemExt::battery`][crate::SystemExt::battery].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let s = System::new();
+/// if let Some(battery) = s.battery() {
+///     println!("{}% (charging: {})", battery.percentage(), battery.is_charging());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Battery {
+    pub(crate) percentage: f32,
+    pub(crate) is_charging: bool,
+    pub(crate) time_remaining: Option<Duration>,
+}
+
+impl Battery {
+    /// Returns the charge level of the battery, in percent (from `0` to `100`).
+    pub fn percentage(&self) -> f32 {
+        self.percentage
+    }
+
+    /// Returns `true` if the battery is currently charging.
+    pub fn is_charging(&self) -> bool {
+        self.is_charging
+    }
+
+    /// Returns the estimated time until the battery is empty (when discharging) or fully
+    /// charged (when charging), or `None` if it is unknown.
+    pub fn time_remaining(&self) -> Option<Duration> {
+        self.time_remaining
+    }
+}
+
+/// Type containing the information of a swap area (a partition or a file).
+///
+/// It is returned by [`SystemExt::swap_devices`][crate::SystemExt::swap_devices].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let s = System::new();
+/// for device in s.swap_devices() {
+///     println!("{:?}: {} B", device.path(), device.total());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapDevice {
+    pub(crate) path: PathBuf,
+    pub(crate) total: u64,
+    pub(crate) used: Option<u64>,
+    pub(crate) priority: Option<i32>,
+}
+
+impl SwapDevice {
+    /// Returns the path of the swap partition or file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the size of the swap area, in bytes.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the amount of the swap area which is used, in bytes.
+    ///
+    /// ⚠️ Always `None` on macOS since this information isn't available for each swap file.
+    pub fn used(&self) -> Option<u64> {
+        self.used
+    }
+
+    /// Returns the priority of the swap area: areas with a higher priority are used first.
+    ///
+    /// ⚠️ Only available on Linux and Android, always `None` on other systems.
+    pub fn priority(&self) -> Option<i32> {
+        self.priority
+    }
+}
+
+/// Type containing the information of a thread of a process.
+///
+/// It is returned by [`ProcessExt::threads`][crate::ProcessExt::threads].
+///
+/// ```no_run
+/// use sysinfo::{Pid, ProcessExt, ProcessRefreshKind, System, SystemExt};
+///
+/// let mut s = System::new();
+/// s.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu().with_threads());
+/// if let Some(process) = s.process(Pid::from(1337)) {
+///     for thread in process.threads() {
+///         println!("{} {:?}: {}%", thread.tid(), thread.name(), thread.cpu_usage());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadInfo {
+    pub(crate) tid: u64,
+    pub(crate) name: String,
+    pub(crate) cpu_usage: f32,
+    pub(crate) status: ProcessStatus,
+}
+
+impl ThreadInfo {
+    /// Returns the identifier of the thread.
+    pub fn tid(&self) -> u64 {
+        self.tid
+    }
+
+    /// Returns the name of the thread. It is empty if the thread has no name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the CPU usage of the thread in percent.
+    ///
+    /// On Linux, it is computed like [`ProcessExt::cpu_usage`], between the last two refreshes.
+    /// On macOS, it is the estimate maintained by the scheduler (which decays over time), so it
+    /// doesn't need a previous refresh.
+    ///
+    /// [`ProcessExt::cpu_usage`]: crate::ProcessExt::cpu_usage
+    pub fn cpu_usage(&self) -> f32 {
+        self.cpu_usage
+    }
+
+    /// Returns the status of the thread.
+    pub fn status(&self) -> ProcessStatus {
+        self.status
+    }
+}
+
+/// Kind of value measured by a [`Sensor`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SensorKind {
+    /// Temperature, in celsius degree.
+    Temperature,
+    /// Voltage, in volts.
+    Voltage,
+    /// Current, in amperes.
+    Current,
+    /// Fan speed, in revolutions per minute.
+    Fan,
+}
+
+/// Type containing the value of a hardware sensor.
+///
+/// Unlike [`Component`], it isn't limited to temperatures. It is returned by
+/// [`SystemExt::sensors`][crate::SystemExt::sensors].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let s = System::new();
+/// for sensor in s.sensors() {
+///     println!("{}: {} ({:?})", sensor.label(), sensor.value(), sensor.kind());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensor {
+    pub(crate) label: String,
+    pub(crate) kind: SensorKind,
+    pub(crate) value: f32,
+}
+
+impl Sensor {
+    /// Returns the label of the sensor.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns what the sensor measures.
+    pub fn kind(&self) -> SensorKind {
+        self.kind
+    }
+
+    /// Returns the value of the sensor, in the unit of its [`kind`][Sensor::kind].
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Returns the temperature sensors of `components`.
+    pub(crate) fn temperatures(components: &[Component]) -> Vec<Sensor> {
+        components
+            .iter()
+            .map(|component| Sensor {
+                label: component.label().to_owned(),
+                kind: SensorKind::Temperature,
+                value: component.temperature(),
+            })
+            .collect()
+    }
+}
+
+/// Owned copy of the information of a [`System`][crate::System] at a given point in time.
+///
+/// Unlike the `System` accessors, it isn't tied to the `System` lifetime and doesn't hold any
+/// system resource, so it can be kept around (to be compared with a later snapshot for example).
+///
+/// It is returned by [`SystemExt::snapshot`][crate::SystemExt::snapshot].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let mut s = System::new_all();
+/// let before = s.snapshot();
+/// std::thread::sleep(std::time::Duration::from_secs(5));
+/// s.refresh_all();
+/// let after = s.snapshot();
+/// println!(
+///     "{} new processes",
+///     after.processes.keys().filter(|pid| !before.processes.contains_key(pid)).count(),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct SystemSnapshot {
+    /// Total memory, in bytes.
+    pub total_memory: u64,
+    /// Used memory, in bytes.
+    pub used_memory: u64,
+    /// Available memory, in bytes.
+    pub available_memory: u64,
+    /// Total swap, in bytes.
+    pub total_swap: u64,
+    /// Used swap, in bytes.
+    pub used_swap: u64,
+    /// Aggregated information of all the CPUs.
+    pub global_cpu: CpuSnapshot,
+    /// Information of each CPU.
+    pub cpus: Vec<CpuSnapshot>,
+    /// Processes, indexed by their PID.
+    pub processes: HashMap<Pid, ProcessSnapshot>,
+    /// Disks.
+    pub disks: Vec<DiskSnapshot>,
+    /// Network interfaces, indexed by their name.
+    pub networks: HashMap<String, NetworkSnapshot>,
+}
+
+impl SystemSnapshot {
+    pub(crate) fn new<S: SystemExt>(system: &S) -> Self {
+        Self {
+            total_memory: system.total_memory(),
+            used_memory: system.used_memory(),
+            available_memory: system.available_memory(),
+            total_swap: system.total_swap(),
+            used_swap: system.used_swap(),
+            global_cpu: CpuSnapshot::new(system.global_cpu_info()),
+            cpus: system.cpus().iter().map(CpuSnapshot::new).collect(),
+            processes: system
+                .processes()
+                .iter()
+                .map(|(pid, process)| (*pid, ProcessSnapshot::new(process)))
+                .collect(),
+            disks: system.disks().iter().map(DiskSnapshot::new).collect(),
+            networks: system
+                .networks()
+                .iter()
+                .map(|(name, data)| (name.clone(), NetworkSnapshot::new(data)))
+                .collect(),
+        }
+    }
+}
+
+/// Processes which started and ended between a [`SystemSnapshot`] and the current state of a
+/// [`System`][crate::System].
+///
+/// A process is identified by its PID and its start time, so if a PID was reused by a new
+/// process, it is listed in both `started` and `ended`.
+///
+/// It is returned by [`SystemExt::diff_processes`][crate::SystemExt::diff_processes].
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt};
+///
+/// let mut s = System::new_all();
+/// let before = s.snapshot();
+/// std::thread::sleep(std::time::Duration::from_secs(5));
+/// s.refresh_processes();
+/// let diff = s.diff_processes(&before);
+/// println!("started: {:?}, ended: {:?}", diff.started, diff.ended);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessDiff {
+    /// PIDs of the processes which weren't in the snapshot, sorted.
+    pub started: Vec<Pid>,
+    /// PIDs of the processes of the snapshot which don't exist anymore, sorted.
+    pub ended: Vec<Pid>,
+}
+
+impl ProcessDiff {
+    // Both iterators yield the PID and the start time of each process.
+    pub(crate) fn new(
+        previous: impl Iterator<Item = (Pid, u64)>,
+        current: impl Iterator<Item = (Pid, u64)>,
+    ) -> Self {
+        let mut previous = previous.collect::<HashMap<_, _>>();
+        let mut started = Vec::new();
+        for (pid, start_time) in current {
+            // If the start time differs, it's a new process which reused the PID.
+            match previous.remove(&pid) {
+                Some(previous_start_time) if previous_start_time == start_time => {}
+                Some(_) => {
+                    started.push(pid);
+                    previous.insert(pid, start_time);
+                }
+                None => started.push(pid),
+            }
+        }
+        let mut ended = previous.into_keys().collect::<Vec<_>>();
+        started.sort_unstable();
+        ended.sort_unstable();
+        Self { started, ended }
+    }
+}
+
+/// Owned copy of the information of a [`Process`][crate::Process], part of [`SystemSnapshot`].
+#[derive(Debug, Clone)]
+pub struct ProcessSnapshot {
+    /// PID of the process.
+    pub pid: Pid,
+    /// PID of the parent process.
+    pub parent: Option<Pid>,
+    /// Name of the process.
+    pub name: String,
+    /// Command line of the process.
+    pub cmd: Vec<String>,
+    /// Path of the process executable.
+    pub exe: PathBuf,
+    /// Memory usage, in bytes.
+    pub memory: u64,
+    /// Virtual memory usage, in bytes.
+    pub virtual_memory: u64,
+    /// CPU usage, in percent.
+    pub cpu_usage: f32,
+    /// Status of the process.
+    pub status: ProcessStatus,
+    /// Start time of the process, in seconds since UNIX epoch.
+    pub start_time: u64,
+    /// Run time of the process, in seconds.
+    pub run_time: u64,
+    /// Disk usage of the process.
+    pub disk_usage: DiskUsage,
+    /// ID of the owner of the process.
+    pub user_id: Option<Uid>,
+}
+
+impl ProcessSnapshot {
+    fn new(process: &Process) -> Self {
+        Self {
+            pid: process.pid(),
+            parent: process.parent(),
+            name: process.name().to_owned(),
+            cmd: process.cmd().to_vec(),
+            exe: process.exe().to_path_buf(),
+            memory: process.memory(),
+            virtual_memory: process.virtual_memory(),
+            cpu_usage: process.cpu_usage(),
+            status: process.status(),
+            start_time: process.start_time(),
+            run_time: process.run_time(),
+            disk_usage: process.disk_usage(),
+            user_id: process.user_id().cloned(),
+        }
+    }
+}
+
+/// Owned copy of the information of a [`Cpu`][crate::Cpu], part of [`SystemSnapshot`].
+#[derive(Debug, Clone)]
+pub struct CpuSnapshot {
+    /// Name of the CPU.
+    pub name: String,
+    /// CPU usage, in percent.
+    pub cpu_usage: f32,
+    /// Frequency of the CPU, in MHz.
+    pub frequency: u64,
+    /// Vendor ID of the CPU.
+    pub vendor_id: String,
+    /// Brand of the CPU.
+    pub brand: String,
+}
+
+impl CpuSnapshot {
+    fn new(cpu: &Cpu) -> Self {
+        Self {
+            name: cpu.name().to_owned(),
+            cpu_usage: cpu.cpu_usage(),
+            frequency: cpu.frequency(),
+            vendor_id: cpu.vendor_id().to_owned(),
+            brand: cpu.brand().to_owned(),
+        }
+    }
+}
+
+/// Owned copy of the information of a [`Disk`][crate::Disk], part of [`SystemSnapshot`].
+#[derive(Debug, Clone)]
+pub struct DiskSnapshot {
+    /// Name of the disk.
+    pub name: OsString,
+    /// Kind of disk.
+    pub type_: DiskType,
+    /// File system used on the disk.
+    pub file_system: Vec<u8>,
+    /// Mount point of the disk.
+    pub mount_point: PathBuf,
+    /// Total size of the disk, in bytes.
+    pub total_space: u64,
+    /// Available space on the disk, in bytes.
+    pub available_space: u64,
+    /// Free space on the disk (including the space reserved for `root`), in bytes.
+    pub free_space: u64,
+    /// Whether the disk is removable.
+    pub is_removable: bool,
+}
+
+impl DiskSnapshot {
+    fn new(disk: &Disk) -> Self {
+        Self {
+            name: disk.name().to_owned(),
+            type_: disk.type_(),
+            file_system: disk.file_system().to_vec(),
+            mount_point: disk.mount_point().to_path_buf(),
+            total_space: disk.total_space(),
+            available_space: disk.available_space(),
+            free_space: disk.free_space(),
+            is_removable: disk.is_removable(),
+        }
+    }
+}
+
+/// Owned copy of the information of a network interface, part of [`SystemSnapshot`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetworkSnapshot {
+    /// Number of bytes received since the last refresh.
+    pub received: u64,
+    /// Total number of bytes received.
+    pub total_received: u64,
+    /// Number of bytes transmitted since the last refresh.
+    pub transmitted: u64,
+    /// Total number of bytes transmitted.
+    pub total_transmitted: u64,
+    /// Total number of packets received.
+    pub total_packets_received: u64,
+    /// Total number of packets transmitted.
+    pub total_packets_transmitted: u64,
+    /// Total number of errors on received packets.
+    pub total_errors_on_received: u64,
+    /// Total number of errors on transmitted packets.
+    pub total_errors_on_transmitted: u64,
+}
+
+impl NetworkSnapshot {
+    fn new(data: &NetworkData) -> Self {
+        Self {
+            received: data.received(),
+            total_received: data.total_received(),
+            transmitted: data.transmitted(),
+            total_transmitted: data.total_transmitted(),
+            total_packets_received: data.total_packets_received(),
+            total_packets_transmitted: data.total_packets_transmitted(),
+            total_errors_on_received: data.total_errors_on_received(),
+            total_errors_on_transmitted: data.total_errors_on_transmitted(),
+        }
+    }
+}
+
 /// Enum describing the different status of a process.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ProcessStatus {
@@ -904,6 +1878,48 @@ pub enum ProcessStatus {
     Unknown(u32),
 }
 
+/// Error explaining why the information of a process couldn't be retrieved.
+///
+/// It is returned by [`SystemExt::last_errors`][crate::SystemExt::last_errors].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The process doesn't exist (anymore).
+    NotFound,
+    /// The current user isn't allowed to access the information.
+    PermissionDenied,
+    /// Any other IO error which happened while reading the system information.
+    Io(std::io::ErrorKind),
+    /// The system information was read but couldn't be parsed.
+    Parse,
+    /// This information isn't available on the current platform.
+    Unsupported,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::NotFound => f.write_str("process not found"),
+            Self::PermissionDenied => f.write_str("permission denied"),
+            Self::Io(kind) => write!(f, "IO error: {:?}", kind),
+            Self::Parse => f.write_str("failed to parse system information"),
+            Self::Unsupported => f.write_str("unsupported platform"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound,
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            kind => Self::Io(kind),
+        }
+    }
+}
+
 /// Returns the pid for the current process.
 ///
 /// `Err` is returned in case the platform isn't supported.
@@ -954,7 +1970,8 @@ pub fn get_current_pid() -> Result<Pid, &'static str> {
 
 #[cfg(test)]
 mod tests {
-    use super::ProcessStatus;
+    use super::{Error, LoadAvg, ProcessDiff, ProcessStatus};
+    use crate::{Pid, PidExt};
 
     // This test only exists to ensure that the `Display` trait is implemented on the
     // `ProcessStatus` enum on all targets.
@@ -962,4 +1979,53 @@ mod tests {
     fn check_display_impl_process_status() {
         println!("{} {:?}", ProcessStatus::Parked, ProcessStatus::Idle);
     }
+
+    #[test]
+    fn check_load_average_per_core() {
+        let load_avg = LoadAvg {
+            one: 2.,
+            five: 4.,
+            fifteen: 8.,
+        };
+        let per_core = load_avg.per_core(4).expect("per_core failed");
+        assert_eq!(per_core.one, 0.5);
+        assert_eq!(per_core.five, 1.);
+        assert_eq!(per_core.fifteen, 2.);
+
+        assert!(load_avg.per_core(0).is_none());
+    }
+
+    #[test]
+    fn check_error_from_io_error() {
+        use std::io::ErrorKind;
+
+        assert_eq!(
+            Error::from(std::io::Error::from(ErrorKind::NotFound)),
+            Error::NotFound
+        );
+        assert_eq!(
+            Error::from(std::io::Error::from(ErrorKind::PermissionDenied)),
+            Error::PermissionDenied
+        );
+        assert_eq!(
+            Error::from(std::io::Error::from(ErrorKind::InvalidData)),
+            Error::Io(ErrorKind::InvalidData)
+        );
+    }
+
+    #[test]
+    fn check_process_diff() {
+        let pid = Pid::from_u32;
+        let previous = vec![(pid(1), 10), (pid(2), 20), (pid(3), 30)];
+
+        // Nothing changed.
+        let diff = ProcessDiff::new(previous.clone().into_iter(), previous.clone().into_iter());
+        assert_eq!(diff, ProcessDiff::default());
+
+        // Process 2 ended, process 4 started and process 3 ended and its PID was reused.
+        let current = vec![(pid(4), 40), (pid(3), 35), (pid(1), 10)];
+        let diff = ProcessDiff::new(previous.into_iter(), current.into_iter());
+        assert_eq!(diff.started, vec![pid(3), pid(4)]);
+        assert_eq!(diff.ended, vec![pid(2), pid(3)]);
+    }
 }
@@ -1,24 +1,46 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+use crate::utils::CpuUsageHistory;
 use crate::CpuExt;
 
 #[doc = include_str!("../../md_doc/cpu.md")]
 pub struct Cpu {
     pub(crate) cpu_usage: f32,
+    pub(crate) user_usage: f32,
+    pub(crate) system_usage: f32,
+    pub(crate) nice_usage: f32,
+    pub(crate) idle_usage: f32,
     name: String,
     pub(crate) vendor_id: String,
     pub(crate) frequency: u64,
+    pub(crate) usage_history: CpuUsageHistory,
 }
 
 impl Cpu {
     pub(crate) fn new(name: String, vendor_id: String, frequency: u64) -> Cpu {
         Cpu {
             cpu_usage: 0.,
+            user_usage: 0.,
+            system_usage: 0.,
+            nice_usage: 0.,
+            idle_usage: 0.,
             name,
             vendor_id,
             frequency,
+            usage_history: CpuUsageHistory::default(),
         }
     }
+
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::CpuSnapshot) -> Cpu {
+        let mut cpu = Cpu::new(
+            snapshot.name.clone(),
+            snapshot.vendor_id.clone(),
+            snapshot.frequency,
+        );
+        cpu.cpu_usage = snapshot.cpu_usage;
+        cpu
+    }
 }
 
 impl CpuExt for Cpu {
@@ -26,6 +48,26 @@ impl CpuExt for Cpu {
         self.cpu_usage
     }
 
+    fn user_usage(&self) -> f32 {
+        self.user_usage
+    }
+
+    fn system_usage(&self) -> f32 {
+        self.system_usage
+    }
+
+    fn nice_usage(&self) -> f32 {
+        self.nice_usage
+    }
+
+    fn idle_usage(&self) -> f32 {
+        self.idle_usage
+    }
+
+    fn usage_history(&self) -> &[f32] {
+        self.usage_history.values()
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
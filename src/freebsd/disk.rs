@@ -1,5 +1,6 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+use crate::utils::DiskListFilter;
 use crate::{DiskExt, DiskType};
 
 use std::ffi::{OsStr, OsString};
@@ -14,8 +15,13 @@ pub struct Disk {
     mount_point: PathBuf,
     total_space: u64,
     available_space: u64,
+    free_space: u64,
+    total_inodes: u64,
+    available_inodes: u64,
     file_system: Vec<u8>,
     is_removable: bool,
+    mount_options: Vec<String>,
+    last_refresh_failed: bool,
 }
 
 impl DiskExt for Disk {
@@ -43,15 +49,45 @@ impl DiskExt for Disk {
         self.available_space
     }
 
+    fn free_space(&self) -> u64 {
+        self.free_space
+    }
+
+    fn total_inodes(&self) -> u64 {
+        self.total_inodes
+    }
+
+    fn available_inodes(&self) -> u64 {
+        self.available_inodes
+    }
+
     fn is_removable(&self) -> bool {
         self.is_removable
     }
 
+    fn mount_options(&self) -> &[String] {
+        &self.mount_options
+    }
+
+    fn model(&self) -> &str {
+        ""
+    }
+
+    fn serial(&self) -> &str {
+        ""
+    }
+
+    fn last_refresh_failed(&self) -> bool {
+        self.last_refresh_failed
+    }
+
     fn refresh(&mut self) -> bool {
-        unsafe {
+        let refreshed = unsafe {
             let mut vfs: libc::statvfs = std::mem::zeroed();
             refresh_disk(self, &mut vfs)
-        }
+        };
+        self.last_refresh_failed = !refreshed;
+        refreshed
     }
 }
 
@@ -65,11 +101,38 @@ unsafe fn refresh_disk(disk: &mut Disk, vfs: &mut libc::statvfs) -> bool {
     let f_frsize: u64 = vfs.f_frsize as _;
 
     disk.total_space = vfs.f_blocks.saturating_mul(f_frsize);
-    disk.available_space = vfs.f_favail.saturating_mul(f_frsize);
+    disk.available_space = vfs.f_bavail.saturating_mul(f_frsize);
+    disk.free_space = vfs.f_bfree.saturating_mul(f_frsize);
+    disk.total_inodes = vfs.f_files as _;
+    disk.available_inodes = vfs.f_favail as _;
     true
 }
 
-pub unsafe fn get_all_disks() -> Vec<Disk> {
+fn get_mount_options(flags: u64) -> Vec<String> {
+    let mut options = vec![if flags & libc::MNT_RDONLY as u64 != 0 {
+        "ro".to_owned()
+    } else {
+        "rw".to_owned()
+    }];
+    options.extend(
+        [
+            (libc::MNT_SYNCHRONOUS, "sync"),
+            (libc::MNT_ASYNC, "async"),
+            (libc::MNT_NOEXEC, "noexec"),
+            (libc::MNT_NOSUID, "nosuid"),
+            (libc::MNT_NOATIME, "noatime"),
+            (libc::MNT_UNION, "union"),
+            (libc::MNT_SUIDDIR, "suiddir"),
+            (libc::MNT_SOFTDEP, "softdep"),
+        ]
+        .iter()
+        .filter(|(flag, _)| flags & *flag as u64 != 0)
+        .map(|(_, name)| (*name).to_owned()),
+    );
+    options
+}
+
+pub unsafe fn get_all_disks(filter: DiskListFilter) -> Vec<Disk> {
     let mut fs_infos: *mut libc::statfs = std::ptr::null_mut();
 
     let count = libc::getmntinfo(&mut fs_infos, libc::MNT_WAIT);
@@ -93,16 +156,25 @@ pub unsafe fn get_all_disks() -> Vec<Disk> {
                 &fs_info.f_fstypename
             };
         let fs_type: &[u8] = std::slice::from_raw_parts(fs_type.as_ptr() as _, fs_type.len());
-        match fs_type {
-            b"autofs" | b"devfs" | b"linprocfs" | b"procfs" | b"fdesckfs" | b"tmpfs"
-            | b"linsysfs" => {
-                sysinfo_debug!(
-                    "Memory filesystem `{:?}`, ignoring it.",
-                    c_buf_to_str(&fs_info.f_fstypename).unwrap(),
-                );
-                continue;
+        if filter != DiskListFilter::All {
+            match fs_type {
+                b"autofs" | b"devfs" | b"linprocfs" | b"procfs" | b"fdesckfs" | b"tmpfs"
+                | b"linsysfs" => {
+                    sysinfo_debug!(
+                        "Memory filesystem `{:?}`, ignoring it.",
+                        c_buf_to_str(&fs_info.f_fstypename).unwrap(),
+                    );
+                    continue;
+                }
+                b"nfs" | b"smbfs" | b"fusefs.sshfs" if filter == DiskListFilter::LocalOnly => {
+                    sysinfo_debug!(
+                        "Network filesystem `{:?}`, ignoring it.",
+                        c_buf_to_str(&fs_info.f_fstypename).unwrap(),
+                    );
+                    continue;
+                }
+                _ => {}
             }
-            _ => {}
         }
 
         if libc::statvfs(fs_info.f_mntonname.as_ptr(), &mut vfs) != 0 {
@@ -134,9 +206,14 @@ pub unsafe fn get_all_disks() -> Vec<Disk> {
             c_mount_point: fs_info.f_mntonname.to_vec(),
             mount_point: PathBuf::from(mount_point),
             total_space: vfs.f_blocks.saturating_mul(f_frsize),
-            available_space: vfs.f_favail.saturating_mul(f_frsize),
+            available_space: vfs.f_bavail.saturating_mul(f_frsize),
+            free_space: vfs.f_bfree.saturating_mul(f_frsize),
+            total_inodes: vfs.f_files as _,
+            available_inodes: vfs.f_favail as _,
             file_system: fs_type.to_vec(),
             is_removable,
+            mount_options: get_mount_options(fs_info.f_flags),
+            last_refresh_failed: false,
         });
     }
     disks
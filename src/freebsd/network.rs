@@ -2,8 +2,10 @@
 
 use std::collections::{hash_map, HashMap};
 use std::mem::MaybeUninit;
+use std::time::Instant;
 
 use super::utils;
+use crate::utils::{keep_network_after_list_refresh, ElapsedTime};
 use crate::{NetworkExt, NetworksExt, NetworksIter};
 
 macro_rules! old_and_new {
@@ -27,7 +29,7 @@ impl Networks {
 }
 
 impl NetworksExt for Networks {
-    fn iter(&self) -> NetworksIter {
+    fn iter(&self) -> NetworksIter<'_> {
         NetworksIter::new(self.interfaces.iter())
     }
 
@@ -35,8 +37,8 @@ impl NetworksExt for Networks {
         unsafe {
             self.refresh_interfaces(true);
         }
-        // Remove interfaces which are gone.
-        self.interfaces.retain(|_, n| n.updated);
+        // Remove interfaces which are gone for too long.
+        self.interfaces.retain(|_, n| n.keep_after_list_refresh());
     }
 
     fn refresh(&mut self) {
@@ -68,6 +70,7 @@ impl Networks {
             }
         }
         let mut data: libc::ifmibdata = MaybeUninit::zeroed().assume_init();
+        let now = Instant::now();
         for row in 1..nb_interfaces {
             let mib = [
                 libc::CTL_NET,
@@ -93,6 +96,7 @@ impl Networks {
                         old_and_new!(interface, ifi_opackets, old_ifi_opackets, data);
                         old_and_new!(interface, ifi_ierrors, old_ifi_ierrors, data);
                         old_and_new!(interface, ifi_oerrors, old_ifi_oerrors, data);
+                        interface.elapsed.update(now);
                         interface.updated = true;
                     }
                     hash_map::Entry::Vacant(e) => {
@@ -113,7 +117,9 @@ impl Networks {
                             old_ifi_ierrors: 0,
                             ifi_oerrors: data.ifi_oerrors,
                             old_ifi_oerrors: 0,
+                            elapsed: ElapsedTime::new(now),
                             updated: true,
+                            inactive_refreshes: 0,
                         });
                     }
                 }
@@ -144,11 +150,38 @@ pub struct NetworkData {
     /// similar to `ifi_ierrors`
     ifi_oerrors: u64,
     old_ifi_oerrors: u64,
+    /// Time elapsed between the last two refreshes.
+    elapsed: ElapsedTime,
     /// Whether or not the above data has been updated during refresh
     updated: bool,
+    // Number of calls to `refresh_networks_list` since the interface disappeared.
+    inactive_refreshes: u8,
+}
+
+impl NetworkData {
+    // Called at the end of `refresh_networks_list`. Returns `false` once the interface should be
+    // removed.
+    fn keep_after_list_refresh(&mut self) -> bool {
+        keep_network_after_list_refresh(
+            self.updated,
+            &mut self.inactive_refreshes,
+            &mut [
+                (&mut self.old_ifi_ibytes, self.ifi_ibytes),
+                (&mut self.old_ifi_obytes, self.ifi_obytes),
+                (&mut self.old_ifi_ipackets, self.ifi_ipackets),
+                (&mut self.old_ifi_opackets, self.ifi_opackets),
+                (&mut self.old_ifi_ierrors, self.ifi_ierrors),
+                (&mut self.old_ifi_oerrors, self.ifi_oerrors),
+            ],
+        )
+    }
 }
 
 impl NetworkExt for NetworkData {
+    fn is_active(&self) -> bool {
+        self.inactive_refreshes == 0
+    }
+
     fn received(&self) -> u64 {
         self.ifi_ibytes.saturating_sub(self.old_ifi_ibytes)
     }
@@ -165,6 +198,14 @@ impl NetworkExt for NetworkData {
         self.ifi_obytes
     }
 
+    fn received_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.received())
+    }
+
+    fn transmitted_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.transmitted())
+    }
+
     fn packets_received(&self) -> u64 {
         self.ifi_ipackets.saturating_sub(self.old_ifi_ipackets)
     }
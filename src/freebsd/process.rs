@@ -1,13 +1,15 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use crate::{DiskUsage, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Signal, Uid};
+use crate::{
+    DiskUsage, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Signal, ThreadInfo, Uid,
+};
 
 use std::fmt;
 use std::path::{Path, PathBuf};
 
 use libc::kill;
 
-use super::utils::{get_sys_value_str, WrapMap};
+use super::utils::{c_buf_to_str, c_buf_to_string, get_sys_value_str, WrapMap};
 
 #[doc(hidden)]
 impl From<libc::c_char> for ProcessStatus {
@@ -54,17 +56,78 @@ pub struct Process {
     pub(crate) virtual_memory: u64,
     pub(crate) updated: bool,
     cpu_usage: f32,
+    cpu_time: u64,
     start_time: u64,
     run_time: u64,
     pub(crate) status: ProcessStatus,
     user_id: Uid,
     group_id: Gid,
+    being_traced: bool,
+    is_64bit: Option<bool>,
+    minor_faults: u64,
+    major_faults: u64,
+    thread_count: usize,
+    nice: i32,
+    priority: i32,
+    tty: Option<String>,
+    wait_channel: Option<String>,
+    process_group_id: Option<Pid>,
+    session_id: Option<Pid>,
     read_bytes: u64,
     old_read_bytes: u64,
     written_bytes: u64,
     old_written_bytes: u64,
 }
 
+impl Process {
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::ProcessSnapshot) -> Process {
+        let mut root = snapshot.exe.clone();
+        root.pop();
+        let user_id = snapshot.user_id.clone().unwrap_or(Uid(0));
+        let disk_usage = &snapshot.disk_usage;
+        Process {
+            name: snapshot.name.clone(),
+            cmd: snapshot.cmd.clone(),
+            exe: snapshot.exe.clone(),
+            pid: snapshot.pid,
+            parent: snapshot.parent,
+            environ: Vec::new(),
+            cwd: PathBuf::new(),
+            root,
+            memory: snapshot.memory,
+            virtual_memory: snapshot.virtual_memory,
+            updated: true,
+            cpu_usage: snapshot.cpu_usage,
+            cpu_time: 0,
+            start_time: snapshot.start_time,
+            run_time: snapshot.run_time,
+            status: snapshot.status,
+            user_id,
+            group_id: Gid(0),
+            being_traced: false,
+            is_64bit: None,
+            minor_faults: 0,
+            major_faults: 0,
+            thread_count: 0,
+            nice: 0,
+            priority: 0,
+            tty: None,
+            wait_channel: None,
+            process_group_id: None,
+            session_id: None,
+            read_bytes: disk_usage.total_read_bytes,
+            old_read_bytes: disk_usage
+                .total_read_bytes
+                .saturating_sub(disk_usage.read_bytes),
+            written_bytes: disk_usage.total_written_bytes,
+            old_written_bytes: disk_usage
+                .total_written_bytes
+                .saturating_sub(disk_usage.written_bytes),
+        }
+    }
+}
+
 impl ProcessExt for Process {
     fn kill_with(&self, signal: Signal) -> Option<bool> {
         let c_signal = super::system::convert_signal(signal)?;
@@ -79,6 +142,10 @@ impl ProcessExt for Process {
         &self.cmd
     }
 
+    fn is_cmd_truncated(&self) -> bool {
+        false
+    }
+
     fn exe(&self) -> &Path {
         self.exe.as_path()
     }
@@ -127,6 +194,18 @@ impl ProcessExt for Process {
         self.cpu_usage
     }
 
+    fn cpu_time(&self) -> u64 {
+        self.cpu_time
+    }
+
+    fn iowait_time(&self) -> Option<u64> {
+        None
+    }
+
+    fn gpu_usage(&self) -> Option<f32> {
+        None
+    }
+
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage {
             written_bytes: self.written_bytes.saturating_sub(self.old_written_bytes),
@@ -140,11 +219,86 @@ impl ProcessExt for Process {
         Some(&self.user_id)
     }
 
+    fn real_user_id(&self) -> Option<&Uid> {
+        // `user_id` is already the real user ID.
+        Some(&self.user_id)
+    }
+
     fn group_id(&self) -> Option<Gid> {
         Some(self.group_id)
     }
 
-    fn wait(&self) {
+    fn real_group_id(&self) -> Option<Gid> {
+        // `group_id` is already the real group ID.
+        Some(self.group_id)
+    }
+
+    fn is_being_traced(&self) -> Option<bool> {
+        Some(self.being_traced)
+    }
+
+    fn tracer_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    fn open_files(&self) -> Option<usize> {
+        None
+    }
+
+    fn network_usage(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    fn is_64bit(&self) -> Option<bool> {
+        self.is_64bit
+    }
+
+    fn minor_faults(&self) -> u64 {
+        self.minor_faults
+    }
+
+    fn major_faults(&self) -> u64 {
+        self.major_faults
+    }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn threads(&self) -> Vec<ThreadInfo> {
+        Vec::new()
+    }
+
+    fn nice(&self) -> i32 {
+        self.nice
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn set_nice(&self, value: i32) -> bool {
+        let value = value.clamp(-20, 19);
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, self.pid.0 as _, value) == 0 }
+    }
+
+    fn tty(&self) -> Option<&str> {
+        self.tty.as_deref()
+    }
+
+    fn wait_channel(&self) -> Option<&str> {
+        self.wait_channel.as_deref()
+    }
+
+    fn process_group_id(&self) -> Option<Pid> {
+        self.process_group_id
+    }
+
+    fn session_id(&self) -> Option<Pid> {
+        self.session_id
+    }
+
+    fn wait_status(&self) -> Option<i32> {
         let mut status = 0;
         // attempt waiting
         unsafe {
@@ -154,11 +308,46 @@ impl ProcessExt for Process {
                 while kill(self.pid.0, 0) == 0 {
                     std::thread::sleep(duration);
                 }
+                // The exit code of a non-child process cannot be retrieved.
+                None
+            } else if libc::WIFEXITED(status) {
+                Some(libc::WEXITSTATUS(status))
+            } else {
+                None
             }
         }
     }
 }
 
+unsafe fn get_tty(tdev: libc::dev_t) -> Option<String> {
+    // `NODEV` means that the process has no controlling terminal.
+    if tdev == !0 {
+        return None;
+    }
+    let mut buffer = [0; libc::SPECNAMELEN as usize + 1];
+    let name = libc::devname_r(tdev, libc::S_IFCHR, buffer.as_mut_ptr(), buffer.len() as _);
+    if name.is_null() {
+        None
+    } else {
+        std::ffi::CStr::from_ptr(name)
+            .to_str()
+            .ok()
+            .map(str::to_owned)
+    }
+}
+
+// The ABI of the process is something like "FreeBSD ELF64" or "Linux ELF32".
+fn get_is_64bit(kproc: &libc::kinfo_proc) -> Option<bool> {
+    let emul = c_buf_to_str(&kproc.ki_emul)?;
+    if emul.ends_with("ELF64") {
+        Some(true)
+    } else if emul.ends_with("ELF32") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 pub(crate) unsafe fn get_process_data(
     kproc: &libc::kinfo_proc,
     wrap: &WrapMap,
@@ -185,6 +374,19 @@ pub(crate) unsafe fn get_process_data(
         None
     };
     let status = ProcessStatus::from(kproc.ki_stat);
+    let being_traced = (kproc.ki_flag as libc::c_int & libc::P_TRACED) != 0;
+    let thread_count = kproc.ki_numthreads as _;
+    let nice = kproc.ki_nice as i32;
+    let priority = kproc.ki_pri.pri_level as i32;
+    let process_group_id = Some(Pid(kproc.ki_pgid));
+    let session_id = Some(Pid(kproc.ki_sid));
+    let wait_channel = if refresh_kind.wait_channel() {
+        c_buf_to_string(&kproc.ki_wmesg).filter(|s| !s.is_empty())
+    } else {
+        None
+    };
+    // `ki_runtime` is expressed in microseconds.
+    let cpu_time = kproc.ki_runtime as u64 / 1_000;
 
     // from FreeBSD source /src/usr.bin/top/machine.c
     let virtual_memory = kproc.ki_size as _;
@@ -200,10 +402,20 @@ pub(crate) unsafe fn get_process_data(
         // same process.
         if proc_.start_time == start_time {
             proc_.cpu_usage = cpu_usage;
+            proc_.cpu_time = cpu_time;
             proc_.parent = parent;
             proc_.status = status;
+            proc_.being_traced = being_traced;
+            proc_.thread_count = thread_count;
+            proc_.nice = nice;
+            proc_.priority = priority;
+            proc_.process_group_id = process_group_id;
+            proc_.session_id = session_id;
+            proc_.wait_channel = wait_channel;
             proc_.virtual_memory = virtual_memory;
             proc_.memory = memory;
+            proc_.minor_faults = kproc.ki_rusage.ru_minflt as _;
+            proc_.major_faults = kproc.ki_rusage.ru_majflt as _;
             proc_.run_time = now.saturating_sub(proc_.start_time);
 
             if refresh_kind.disk_usage() {
@@ -249,9 +461,21 @@ pub(crate) unsafe fn get_process_data(
         parent,
         user_id: Uid(kproc.ki_ruid),
         group_id: Gid(kproc.ki_rgid),
+        being_traced,
+        is_64bit: get_is_64bit(kproc),
+        minor_faults: kproc.ki_rusage.ru_minflt as _,
+        major_faults: kproc.ki_rusage.ru_majflt as _,
+        thread_count,
+        nice,
+        priority,
+        tty: get_tty(kproc.ki_tdev),
+        wait_channel,
+        process_group_id,
+        session_id,
         start_time,
         run_time: now.saturating_sub(start_time),
         cpu_usage,
+        cpu_time,
         virtual_memory,
         memory,
         // procstat_getfiles
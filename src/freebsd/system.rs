@@ -2,20 +2,25 @@
 
 use crate::{
     sys::{component::Component, Cpu, Disk, Networks, Process},
-    CpuRefreshKind, LoadAvg, Pid, ProcessRefreshKind, RefreshKind, SystemExt, User,
+    Battery, CgroupLimits, CgroupStats, ComponentExt, CpuRefreshKind, Error, LoadAvg, Pid,
+    PressureStats, ProcessRefreshKind, Protocol, RefreshKind, RefreshTimes, SwapDevice, SystemExt,
+    Uid, User, UserExt, Virtualization,
 };
 
+use crate::utils::{CounterDelta, DiskListFilter, RefreshInterval, SwapActivity, UserNameCache};
+
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
 use std::ffi::CStr;
 use std::mem::MaybeUninit;
 use std::path::{Path, PathBuf};
 use std::ptr::NonNull;
+use std::time::Duration;
 
 use super::utils::{
-    self, boot_time, c_buf_to_string, from_cstr_array, get_frequency_for_cpu, get_sys_value,
-    get_sys_value_array, get_sys_value_by_name, get_sys_value_str_by_name, get_system_info,
-    init_mib,
+    self, boot_time, c_buf_to_str, c_buf_to_string, from_cstr_array, get_frequency_for_cpu,
+    get_sys_value, get_sys_value_array, get_sys_value_by_name, get_sys_value_str_by_name,
+    get_system_info, init_mib,
 };
 
 use libc::c_int;
@@ -58,20 +63,28 @@ declare_signals! {
 #[doc = include_str!("../../md_doc/system.md")]
 pub struct System {
     process_list: HashMap<Pid, Process>,
+    process_errors: HashMap<Pid, Error>,
+    process_refresh_interval: RefreshInterval,
+    refresh_times: RefreshTimes,
     mem_total: u64,
     mem_free: u64,
     mem_used: u64,
     swap_total: u64,
     swap_used: u64,
+    swap_activity: SwapActivity,
     global_cpu: Cpu,
     cpus: Vec<Cpu>,
+    cpu_usage_history_capacity: usize,
     components: Vec<Component>,
     disks: Vec<Disk>,
     networks: Networks,
     users: Vec<User>,
+    user_names: UserNameCache,
     boot_time: u64,
     system_info: SystemInfo,
     got_cpu_frequency: bool,
+    context_switches: CounterDelta,
+    interrupts: CounterDelta,
 }
 
 impl SystemExt for System {
@@ -83,26 +96,35 @@ impl SystemExt for System {
 
         let mut s = System {
             process_list: HashMap::with_capacity(200),
+            process_errors: HashMap::new(),
+            process_refresh_interval: RefreshInterval::default(),
+            refresh_times: RefreshTimes::default(),
             mem_total: 0,
             mem_free: 0,
             mem_used: 0,
             swap_total: 0,
             swap_used: 0,
+            swap_activity: SwapActivity::default(),
             global_cpu: Cpu::new(String::new(), String::new(), 0),
             cpus: Vec::with_capacity(system_info.nb_cpus as _),
+            cpu_usage_history_capacity: 0,
             components: Vec::with_capacity(2),
             disks: Vec::with_capacity(1),
             networks: Networks::new(),
             users: Vec::new(),
+            user_names: UserNameCache::default(),
             boot_time: boot_time(),
             system_info,
             got_cpu_frequency: false,
+            context_switches: CounterDelta::default(),
+            interrupts: CounterDelta::default(),
         };
         s.refresh_specifics(refreshes);
         s
     }
 
     fn refresh_memory(&mut self) {
+        self.refresh_times.memory = Some(std::time::Instant::now());
         if self.mem_total == 0 {
             self.mem_total = self.system_info.get_total_memory();
         }
@@ -111,9 +133,13 @@ impl SystemExt for System {
         let (swap_used, swap_total) = self.system_info.get_swap_info();
         self.swap_total = swap_total;
         self.swap_used = swap_used;
+        let (pages_in, pages_out) = self.system_info.get_swap_activity();
+        self.swap_activity
+            .update(pages_in, pages_out, std::time::Instant::now());
     }
 
     fn refresh_cpu_specifics(&mut self, refresh_kind: CpuRefreshKind) {
+        self.refresh_times.cpu = Some(std::time::Instant::now());
         if self.cpus.is_empty() {
             let mut frequency = 0;
 
@@ -126,8 +152,10 @@ impl SystemExt for System {
                         frequency = get_frequency_for_cpu(pos);
                     }
                 }
-                self.cpus
-                    .push(Cpu::new(format!("cpu {pos}"), vendor_id.clone(), frequency));
+                let mut cpu = Cpu::new(format!("cpu {pos}"), vendor_id.clone(), frequency);
+                cpu.usage_history
+                    .set_capacity(self.cpu_usage_history_capacity);
+                self.cpus.push(cpu);
             }
             self.global_cpu.vendor_id = vendor_id;
             self.got_cpu_frequency = refresh_kind.frequency();
@@ -143,6 +171,25 @@ impl SystemExt for System {
             self.system_info
                 .get_cpu_usage(&mut self.global_cpu, &mut self.cpus);
         }
+
+        let mut context_switches: libc::c_uint = 0;
+        let mut interrupts: libc::c_uint = 0;
+        unsafe {
+            if get_sys_value_by_name(b"vm.stats.sys.v_swtch\0", &mut context_switches) {
+                self.context_switches.update(context_switches as _);
+            }
+            if get_sys_value_by_name(b"vm.stats.sys.v_intr\0", &mut interrupts) {
+                self.interrupts.update(interrupts as _);
+            }
+        }
+    }
+
+    fn set_cpu_usage_history_capacity(&mut self, capacity: usize) {
+        self.cpu_usage_history_capacity = capacity;
+        self.global_cpu.usage_history.set_capacity(capacity);
+        for cpu in self.cpus.iter_mut() {
+            cpu.usage_history.set_capacity(capacity);
+        }
     }
 
     fn refresh_components_list(&mut self) {
@@ -153,10 +200,31 @@ impl SystemExt for System {
     }
 
     fn refresh_processes_specifics(&mut self, refresh_kind: ProcessRefreshKind) {
-        unsafe { self.refresh_procs(refresh_kind) }
+        if !self
+            .process_refresh_interval
+            .should_refresh(std::time::Instant::now())
+        {
+            return;
+        }
+        self.refresh_times.processes = Some(std::time::Instant::now());
+        self.process_errors.clear();
+        unsafe { self.refresh_procs(refresh_kind) };
+    }
+
+    fn set_process_refresh_interval(&mut self, interval: Duration) {
+        self.process_refresh_interval.set_interval(interval);
+    }
+
+    fn processes_refresh_skipped(&self) -> bool {
+        self.process_refresh_interval.skipped()
+    }
+
+    fn refresh_times(&self) -> RefreshTimes {
+        self.refresh_times
     }
 
     fn refresh_process_specifics(&mut self, pid: Pid, refresh_kind: ProcessRefreshKind) -> bool {
+        self.process_errors.clear();
         unsafe {
             let kd = self.system_info.kd.as_ptr();
             let mut count = 0;
@@ -191,6 +259,7 @@ impl SystemExt for System {
             let kproc = if let Some(kproc) = ret {
                 kproc
             } else {
+                self.process_errors.insert(pid, Error::NotFound);
                 return false;
             };
             match super::process::get_process_data(
@@ -202,7 +271,12 @@ impl SystemExt for System {
                 refresh_kind,
             ) {
                 Ok(Some(proc_)) => {
-                    self.add_missing_proc_info(self.system_info.kd.as_ptr(), kproc, proc_);
+                    self.add_missing_proc_info(
+                        self.system_info.kd.as_ptr(),
+                        kproc,
+                        proc_,
+                        refresh_kind,
+                    );
                     true
                 }
                 Ok(None) => true,
@@ -211,12 +285,56 @@ impl SystemExt for System {
         }
     }
 
+    fn refresh_pids_specifics(&mut self, pids: &[Pid], refresh_kind: ProcessRefreshKind) {
+        self.process_errors.clear();
+        unsafe {
+            let kd = self.system_info.kd.as_ptr();
+            let now = super::utils::get_now();
+            let fscale = self.system_info.fscale;
+            let page_size = self.system_info.page_size as isize;
+
+            for pid in pids {
+                let mut count = 0;
+                // Only retrieves the information of this process instead of all of them.
+                let kproc = libc::kvm_getprocs(kd, libc::KERN_PROC_PID, pid.0, &mut count);
+                if count < 1 || kproc.is_null() {
+                    self.process_errors.insert(*pid, Error::NotFound);
+                    continue;
+                }
+                let kproc = &*kproc;
+                let proc_ = {
+                    let proc_list = utils::WrapMap(UnsafeCell::new(&mut self.process_list));
+                    super::process::get_process_data(
+                        kproc,
+                        &proc_list,
+                        page_size,
+                        fscale,
+                        now,
+                        refresh_kind,
+                    )
+                };
+                if let Ok(Some(proc_)) = proc_ {
+                    self.add_missing_proc_info(kd, kproc, proc_, refresh_kind);
+                }
+            }
+        }
+    }
+
     fn refresh_disks_list(&mut self) {
-        self.disks = unsafe { super::disk::get_all_disks() };
+        self.disks = unsafe { super::disk::get_all_disks(DiskListFilter::Default) };
+    }
+
+    fn refresh_disks_list_local_only(&mut self) {
+        self.disks = unsafe { super::disk::get_all_disks(DiskListFilter::LocalOnly) };
+    }
+
+    fn refresh_disks_list_with_all_file_systems(&mut self) {
+        self.disks = unsafe { super::disk::get_all_disks(DiskListFilter::All) };
     }
 
     fn refresh_users_list(&mut self) {
         self.users = crate::users::get_users_list();
+        self.user_names.clear();
     }
 
     // COMMON PART
@@ -231,6 +349,14 @@ impl SystemExt for System {
         self.process_list.get(&pid)
     }
 
+    fn last_errors(&self) -> &HashMap<Pid, Error> {
+        &self.process_errors
+    }
+
+    fn process_by_port(&self, _port: u16, _protocol: Protocol) -> Option<&Process> {
+        None
+    }
+
     fn networks(&self) -> &Networks {
         &self.networks
     }
@@ -247,6 +373,10 @@ impl SystemExt for System {
         &self.cpus
     }
 
+    fn cpu_count(&self) -> usize {
+        self.system_info.nb_cpus as usize
+    }
+
     fn physical_core_count(&self) -> Option<usize> {
         let mut physical_core_count: u32 = 0;
 
@@ -259,6 +389,34 @@ impl SystemExt for System {
         }
     }
 
+    fn max_pid(&self) -> Option<Pid> {
+        let mut pid_max: libc::c_int = 0;
+
+        unsafe {
+            if get_sys_value_by_name(b"kern.pid_max\0", &mut pid_max) {
+                Some(Pid(pid_max as _))
+            } else {
+                None
+            }
+        }
+    }
+
+    fn read_sysctl(&self, name: &str) -> Option<i64> {
+        crate::utils::read_sysctl_integer(name)
+    }
+
+    fn read_proc_value(&self, _path: &str) -> Option<String> {
+        None
+    }
+
+    fn context_switches(&self) -> Option<u64> {
+        Some(self.context_switches.delta)
+    }
+
+    fn interrupts(&self) -> Option<u64> {
+        Some(self.interrupts.delta)
+    }
+
     fn total_memory(&self) -> u64 {
         self.mem_total
     }
@@ -280,7 +438,7 @@ impl SystemExt for System {
     }
 
     fn free_swap(&self) -> u64 {
-        self.swap_total - self.swap_used
+        self.swap_total.saturating_sub(self.swap_used)
     }
 
     // TODO: need to be checked
@@ -288,6 +446,44 @@ impl SystemExt for System {
         self.swap_used
     }
 
+    fn swap_devices(&self) -> Vec<SwapDevice> {
+        self.system_info.get_swap_devices()
+    }
+
+    fn is_swap_encrypted(&self) -> Option<bool> {
+        let devices = self.swap_devices();
+        if devices.is_empty() {
+            return None;
+        }
+        // Encrypted swap devices are provided by the GELI or GBDE GEOM classes.
+        Some(devices.iter().all(|device| {
+            matches!(
+                device.path.extension().and_then(|ext| ext.to_str()),
+                Some("eli") | Some("bde")
+            )
+        }))
+    }
+
+    fn page_size(&self) -> u64 {
+        self.system_info.page_size as _
+    }
+
+    fn swap_in_per_second(&self) -> f64 {
+        self.swap_activity.in_per_second
+    }
+
+    fn swap_out_per_second(&self) -> f64 {
+        self.swap_activity.out_per_second
+    }
+
+    fn swap_in(&self) -> u64 {
+        self.swap_activity.swapped_in
+    }
+
+    fn swap_out(&self) -> u64 {
+        self.swap_activity.swapped_out
+    }
+
     fn components(&self) -> &[Component] {
         &self.components
     }
@@ -296,6 +492,19 @@ impl SystemExt for System {
         &mut self.components
     }
 
+    fn sensors_available(&self) -> bool {
+        !self.components.is_empty()
+    }
+
+    fn cpu_temperature(&self) -> Option<f32> {
+        // Only the CPUs temperatures are retrieved.
+        if self.components.is_empty() {
+            return None;
+        }
+        let total: f32 = self.components.iter().map(|c| c.temperature()).sum();
+        Some(total / self.components.len() as f32)
+    }
+
     fn disks(&self) -> &[Disk] {
         &self.disks
     }
@@ -304,6 +513,10 @@ impl SystemExt for System {
         &mut self.disks
     }
 
+    fn file_systems(&self) -> Vec<Disk> {
+        unsafe { super::disk::get_all_disks(DiskListFilter::All) }
+    }
+
     fn sort_disks_by<F>(&mut self, compare: F)
     where
         F: FnMut(&Disk, &Disk) -> std::cmp::Ordering,
@@ -335,10 +548,68 @@ impl SystemExt for System {
         }
     }
 
+    fn cgroup_stats(&self, _path: &Path) -> Option<CgroupStats> {
+        None
+    }
+
+    fn cgroup_limits(&self) -> Option<CgroupLimits> {
+        None
+    }
+
+    fn pressure(&self) -> Option<PressureStats> {
+        None
+    }
+
+    fn battery(&self) -> Option<Battery> {
+        unsafe {
+            let mut units: libc::c_int = 0;
+            if !get_sys_value_by_name(b"hw.acpi.battery.units\0", &mut units) || units < 1 {
+                return None;
+            }
+            let mut life: libc::c_int = 0;
+            if !get_sys_value_by_name(b"hw.acpi.battery.life\0", &mut life) || life < 0 {
+                return None;
+            }
+            let mut state: libc::c_int = 0;
+            get_sys_value_by_name(b"hw.acpi.battery.state\0", &mut state);
+            // It is `-1` if the remaining time is unknown or if the battery is charging.
+            let mut time: libc::c_int = -1;
+            get_sys_value_by_name(b"hw.acpi.battery.time\0", &mut time);
+            Some(Battery {
+                percentage: life as f32,
+                // `ACPI_BATT_STAT_CHARGING`
+                is_charging: state & 0x2 != 0,
+                time_remaining: if time > 0 {
+                    Some(Duration::from_secs(time as u64 * 60))
+                } else {
+                    None
+                },
+            })
+        }
+    }
+
+    fn on_ac_power(&self) -> Option<bool> {
+        let mut acline: libc::c_int = 0;
+
+        unsafe {
+            if get_sys_value_by_name(b"hw.acpi.acline\0", &mut acline) {
+                Some(acline != 0)
+            } else {
+                None
+            }
+        }
+    }
+
     fn users(&self) -> &[User] {
         &self.users
     }
 
+    fn get_user_name_by_id(&self, user_id: &Uid) -> Option<String> {
+        self.get_user_by_id(user_id)
+            .map(|user| user.name().to_owned())
+            .or_else(|| self.user_names.get(user_id))
+    }
+
     fn name(&self) -> Option<String> {
         self.system_info.get_os_name()
     }
@@ -351,6 +622,20 @@ impl SystemExt for System {
         self.system_info.get_hostname()
     }
 
+    fn virtualization(&self) -> Option<Virtualization> {
+        Some(
+            match get_sys_value_str_by_name(b"kern.vm_guest\0")?.as_str() {
+                "none" => return None,
+                "kvm" => Virtualization::Kvm,
+                "vmware" => Virtualization::VMware,
+                "vbox" => Virtualization::VirtualBox,
+                "xen" => Virtualization::Xen,
+                "hv" => Virtualization::HyperV,
+                _ => Virtualization::Other,
+            },
+        )
+    }
+
     fn kernel_version(&self) -> Option<String> {
         self.system_info.get_kernel_version()
     }
@@ -412,7 +697,7 @@ impl System {
             .retain(|_, v| std::mem::replace(&mut v.updated, false));
 
         for (kproc, proc_) in procs {
-            self.add_missing_proc_info(kd, kproc, proc_);
+            self.add_missing_proc_info(kd, kproc, proc_, refresh_kind);
         }
     }
 
@@ -421,8 +706,11 @@ impl System {
         kd: *mut libc::kvm_t,
         kproc: &libc::kinfo_proc,
         mut proc_: Process,
+        refresh_kind: ProcessRefreshKind,
     ) {
-        proc_.cmd = from_cstr_array(libc::kvm_getargv(kd, kproc, 0) as _);
+        if refresh_kind.cmd() {
+            proc_.cmd = from_cstr_array(libc::kvm_getargv(kd, kproc, 0) as _);
+        }
         self.system_info.get_proc_missing_info(kproc, &mut proc_);
         if !proc_.cmd.is_empty() {
             // First, we try to retrieve the name from the command line.
@@ -442,7 +730,9 @@ impl System {
             // possible.
             proc_.name = c_buf_to_string(&kproc.ki_comm).unwrap_or_default();
         }
-        proc_.environ = from_cstr_array(libc::kvm_getenvv(kd, kproc, 0) as _);
+        if refresh_kind.cmd() {
+            proc_.environ = from_cstr_array(libc::kvm_getenvv(kd, kproc, 0) as _);
+        }
         self.process_list.insert(proc_.pid, proc_);
     }
 }
@@ -634,34 +924,65 @@ impl SystemInfo {
         get_system_info(&[self.hostname[0], self.hostname[1]], Some(""))
     }
 
-    /// Returns (used, total).
-    fn get_swap_info(&self) -> (u64, u64) {
+    fn get_kvm_swaps(&self) -> Vec<libc::kvm_swap> {
         // Magic number used in htop. Cannot find how they got it when reading `kvm_getswapinfo`
         // source code so here we go...
         const LEN: usize = 16;
         let mut swap = MaybeUninit::<[libc::kvm_swap; LEN]>::uninit();
         unsafe {
             let nswap =
-                libc::kvm_getswapinfo(self.kd.as_ptr(), swap.as_mut_ptr() as *mut _, LEN as _, 0)
-                    as usize;
+                libc::kvm_getswapinfo(self.kd.as_ptr(), swap.as_mut_ptr() as *mut _, LEN as _, 0);
             if nswap < 1 {
-                return (0, 0);
+                return Vec::new();
             }
-            let swap =
-                std::slice::from_raw_parts(swap.as_ptr() as *mut libc::kvm_swap, nswap.min(LEN));
-            let (used, total) = swap.iter().fold((0, 0), |(used, total): (u64, u64), swap| {
-                (
-                    used.saturating_add(swap.ksw_used as _),
-                    total.saturating_add(swap.ksw_total as _),
-                )
-            });
-            (
-                used.saturating_mul(self.page_size as _),
-                total.saturating_mul(self.page_size as _),
+            std::slice::from_raw_parts(
+                swap.as_ptr() as *mut libc::kvm_swap,
+                (nswap as usize).min(LEN),
             )
+            .to_vec()
         }
     }
 
+    /// Returns (used, total).
+    fn get_swap_info(&self) -> (u64, u64) {
+        let (used, total) =
+            self.get_kvm_swaps()
+                .iter()
+                .fold((0, 0), |(used, total): (u64, u64), swap| {
+                    (
+                        used.saturating_add(swap.ksw_used as _),
+                        total.saturating_add(swap.ksw_total as _),
+                    )
+                });
+        (
+            used.saturating_mul(self.page_size as _),
+            total.saturating_mul(self.page_size as _),
+        )
+    }
+
+    fn get_swap_devices(&self) -> Vec<SwapDevice> {
+        self.get_kvm_swaps()
+            .iter()
+            .map(|swap| SwapDevice {
+                path: Path::new("/dev").join(c_buf_to_str(&swap.ksw_devname).unwrap_or("")),
+                total: (swap.ksw_total as u64).saturating_mul(self.page_size as _),
+                used: Some((swap.ksw_used as u64).saturating_mul(self.page_size as _)),
+                priority: None,
+            })
+            .collect()
+    }
+
+    /// Returns the number of pages swapped (in, out) since boot.
+    fn get_swap_activity(&self) -> (u64, u64) {
+        let mut pages_in: libc::c_uint = 0;
+        let mut pages_out: libc::c_uint = 0;
+        unsafe {
+            get_sys_value_by_name(b"vm.stats.vm.v_swappgsin\0", &mut pages_in);
+            get_sys_value_by_name(b"vm.stats.vm.v_swappgsout\0", &mut pages_out);
+        }
+        (pages_in as _, pages_out as _)
+    }
+
     fn get_total_memory(&self) -> u64 {
         let mut nb_pages: u64 = 0;
         unsafe {
@@ -689,7 +1010,7 @@ impl SystemInfo {
             // We need to subtract "ZFS ARC" from the "wired memory" because it should belongs to cache
             // but the kernel reports it as "wired memory" instead...
             if let Some(arc_size) = self.zfs.arc_size() {
-                mem_wire -= arc_size;
+                mem_wire = mem_wire.saturating_sub(arc_size);
             }
             mem_active
                 .saturating_mul(self.page_size as _)
@@ -740,11 +1061,22 @@ impl SystemInfo {
             }
 
             let total_diff = total_new - total_old;
-            if total_diff < 1 {
-                proc_.cpu_usage = 0.;
-            } else {
-                proc_.cpu_usage = cp_diff as f32 / total_diff as f32 * 100.;
-            }
+            let usage = |diff: libc::c_ulong| {
+                if total_diff < 1 {
+                    0.
+                } else {
+                    diff as f32 / total_diff as f32 * 100.
+                }
+            };
+            let state_usage = |state: libc::c_int| {
+                usage(new_cp_time[state as usize] - old_cp_time[state as usize])
+            };
+            proc_.cpu_usage = usage(cp_diff);
+            proc_.user_usage = state_usage(libc::CP_USER);
+            proc_.system_usage = state_usage(libc::CP_SYS);
+            proc_.nice_usage = state_usage(libc::CP_NICE);
+            proc_.idle_usage = state_usage(libc::CP_IDLE);
+            proc_.usage_history.push(proc_.cpu_usage);
         }
 
         fill_cpu(global, self.cp_time.get_new(), self.cp_time.get_old());
@@ -8,7 +8,6 @@
 #![allow(clippy::non_send_fields_in_send_ty)]
 #![allow(renamed_and_removed_lints)]
 #![allow(clippy::assertions_on_constants)]
-#![allow(unknown_lints)]
 
 #[macro_use]
 mod macros;
@@ -60,8 +59,11 @@ cfg_if::cfg_if! {
 }
 
 pub use common::{
-    get_current_pid, CpuRefreshKind, DiskType, DiskUsage, Gid, LoadAvg, NetworksIter, Pid, PidExt,
-    ProcessRefreshKind, ProcessStatus, RefreshKind, Signal, Uid, User,
+    get_current_pid, Battery, CgroupLimits, CgroupStats, CpuRefreshKind, CpuSnapshot, DiskSnapshot,
+    DiskType, DiskUsage, Error, Gid, LoadAvg, NetworkSnapshot, NetworksIter, Pid, PidExt, Pressure,
+    PressureAvg, PressureStats, ProcessDiff, ProcessRefreshKind, ProcessSnapshot, ProcessStatus,
+    Protocol, RefreshKind, RefreshTimes, Sensor, SensorKind, Signal, SwapDevice, SystemSnapshot,
+    ThreadInfo, Uid, User, Virtualization,
 };
 pub use sys::{Component, Cpu, Disk, NetworkData, Networks, Process, System};
 pub use traits::{
@@ -76,6 +78,8 @@ mod c_interface;
 mod common;
 mod debug;
 mod system;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod traits;
 mod utils;
 
@@ -113,17 +117,15 @@ pub fn set_open_files_limit(mut _new_limit: isize) -> bool {
             if _new_limit > max {
                 _new_limit = max;
             }
-            unsafe {
-                if let Ok(ref mut x) = sys::system::REMAINING_FILES.lock() {
-                    // If files are already open, to be sure that the number won't be bigger when those
-                    // files are closed, we subtract the current number of opened files to the new
-                    // limit.
-                    let diff = max.saturating_sub(**x);
-                    **x = _new_limit.saturating_sub(diff);
-                    true
-                } else {
-                    false
-                }
+            if let Ok(ref mut x) = sys::system::REMAINING_FILES.lock() {
+                // If files are already open, to be sure that the number won't be bigger when those
+                // files are closed, we subtract the current number of opened files to the new
+                // limit.
+                let diff = max.saturating_sub(**x);
+                **x = _new_limit.saturating_sub(diff);
+                true
+            } else {
+                false
             }
         } else {
             false
@@ -259,6 +261,38 @@ mod test {
             .any(|(_, proc_)| proc_.cpu_usage() > 0.0));
     }
 
+    #[test]
+    fn check_page_size() {
+        let s = System::new();
+        if System::IS_SUPPORTED {
+            assert!(s.page_size().is_power_of_two());
+        } else {
+            assert_eq!(s.page_size(), 0);
+        }
+    }
+
+    #[test]
+    fn check_refresh_times() {
+        let mut s = System::new();
+        assert_eq!(s.last_refresh(), None);
+        s.refresh_memory();
+        if System::IS_SUPPORTED {
+            let times = s.refresh_times();
+            assert!(times.memory().is_some());
+            assert!(times.cpu().is_none());
+            assert!(times.processes().is_none());
+            s.refresh_cpu();
+            s.refresh_processes();
+            let times = s.refresh_times();
+            let last = s.last_refresh().expect("no last refresh");
+            assert!(times.memory().unwrap() <= times.cpu().unwrap());
+            assert!(times.cpu().unwrap() <= last);
+            assert!(times.processes().unwrap() <= last);
+        } else {
+            assert_eq!(s.last_refresh(), None);
+        }
+    }
+
     #[test]
     fn check_cpu_usage() {
         if !System::IS_SUPPORTED {
@@ -321,12 +355,84 @@ mod test {
             s.refresh_processes();
             assert!(s
                 .processes()
-                .iter()
-                .filter_map(|(_, p)| p.user_id())
+                .values()
+                .filter_map(|p| p.user_id())
                 .any(|uid| s.get_user_by_id(uid).is_some()));
         }
     }
 
+    #[test]
+    fn check_user_name_by_id() {
+        let s = System::new();
+        assert!(s.users().is_empty());
+
+        #[cfg(all(not(target_os = "windows"), not(feature = "unknown-ci")))]
+        if System::IS_SUPPORTED {
+            // The users list is empty, so it has to ask the system directly.
+            assert_eq!(s.get_user_name_by_id(&Uid(0)).as_deref(), Some("root"));
+        }
+
+        assert!(s.logged_in_users().iter().all(|user| !user.is_empty()));
+    }
+
+    #[test]
+    fn check_snapshot() {
+        let mut s = System::new_all();
+        let snapshot = s.snapshot();
+        assert_eq!(snapshot.total_memory, s.total_memory());
+        assert_eq!(snapshot.cpus.len(), s.cpus().len());
+        assert_eq!(snapshot.disks.len(), s.disks().len());
+        assert_eq!(snapshot.networks.len(), s.networks().iter().count());
+        assert_eq!(snapshot.processes.len(), s.processes().len());
+        for (pid, process) in snapshot.processes.iter() {
+            assert_eq!(*pid, process.pid);
+        }
+
+        // The snapshot isn't tied to the `System`.
+        let snapshot2 = snapshot.clone();
+        s.refresh_all();
+        drop(s);
+        assert_eq!(snapshot.processes.len(), snapshot2.processes.len());
+    }
+
+    #[test]
+    fn check_battery() {
+        if let Some(battery) = System::new().battery() {
+            assert!(battery.percentage() >= 0. && battery.percentage() <= 100.);
+        }
+    }
+
+    #[cfg(feature = "background-refresh")]
+    #[test]
+    fn check_refresh_in_background() {
+        let receiver = System::refresh_in_background(
+            RefreshKind::new()
+                .with_memory()
+                .with_cpu(CpuRefreshKind::everything()),
+        );
+        let snapshot = receiver.recv().expect("failed to receive the snapshot");
+        let mut s = System::new();
+        s.refresh_cpu();
+        assert_eq!(snapshot.cpus.len(), s.cpus().len());
+        assert!(snapshot.processes.is_empty());
+        if System::IS_SUPPORTED {
+            assert!(snapshot.total_memory > 0);
+        }
+    }
+
+    #[test]
+    fn check_sensors_available() {
+        let mut s = System::new();
+        s.refresh_components_list();
+        if !System::IS_SUPPORTED {
+            assert!(!s.sensors_available());
+        }
+        // If we found components, it means that the sensors are available.
+        if !s.components().is_empty() {
+            assert!(s.sensors_available());
+        }
+    }
+
     #[test]
     fn check_system_info() {
         let s = System::new();
@@ -0,0 +1,201 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::sys::utils::get_all_data;
+use crate::Battery;
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Location of the power supplies, relative to the sysfs root.
+pub(crate) const POWER_SUPPLY_ROOT: &str = "class/power_supply";
+
+fn read_str(path: &Path) -> Option<String> {
+    get_all_data(path, 64)
+        .ok()
+        .map(|data| data.trim().to_owned())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_str(path)?.parse().ok()
+}
+
+// Returns the `(now, full, rate)` values of the battery. Depending on the driver, they are
+// either expressed in µWh and µW or in µAh and µA.
+fn get_charge_values(dir: &Path) -> Option<(u64, u64, Option<u64>)> {
+    [
+        ("energy_now", "energy_full", "power_now"),
+        ("charge_now", "charge_full", "current_now"),
+    ]
+    .iter()
+    .find_map(|(now, full, rate)| {
+        let now = read_u64(&dir.join(now))?;
+        let full = read_u64(&dir.join(full)).filter(|full| *full != 0)?;
+        Some((
+            now,
+            full,
+            read_u64(&dir.join(rate)).filter(|rate| *rate != 0),
+        ))
+    })
+}
+
+fn get_battery_info(dir: &Path) -> Option<Battery> {
+    let status = read_str(&dir.join("status")).unwrap_or_default();
+    let is_charging = status == "Charging";
+    let charge_values = get_charge_values(dir);
+
+    let percentage = match read_u64(&dir.join("capacity")) {
+        Some(capacity) => capacity as f32,
+        None => {
+            let (now, full, _) = charge_values?;
+            now as f32 * 100. / full as f32
+        }
+    };
+    let time_remaining = match status.as_str() {
+        "Charging" => read_u64(&dir.join("time_to_full_now"))
+            .map(Duration::from_secs)
+            .or_else(|| {
+                let (now, full, rate) = charge_values?;
+                Some(Duration::from_secs_f64(
+                    full.saturating_sub(now) as f64 * 3_600. / rate? as f64,
+                ))
+            }),
+        "Discharging" => read_u64(&dir.join("time_to_empty_now"))
+            .map(Duration::from_secs)
+            .or_else(|| {
+                let (now, _, rate) = charge_values?;
+                Some(Duration::from_secs_f64(now as f64 * 3_600. / rate? as f64))
+            }),
+        _ => None,
+    };
+    Some(Battery {
+        percentage: percentage.min(100.),
+        is_charging,
+        time_remaining,
+    })
+}
+
+pub(crate) fn get_battery(root: &Path) -> Option<Battery> {
+    let mut dirs = fs::read_dir(root)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .collect::<Vec<_>>();
+    // To always return the same battery (`BAT0` before `BAT1`).
+    dirs.sort();
+    dirs.iter()
+        .filter(|dir| {
+            read_str(&dir.join("type")).as_deref() == Some("Battery")
+                // Peripherals (like a wireless mouse) have a "Device" scope.
+                && read_str(&dir.join("scope")).as_deref() != Some("Device")
+        })
+        .find_map(|dir| get_battery_info(dir))
+}
+
+/// Returns `true` if one of the AC adapters (power supplies of the "Mains" type) is online, or
+/// `None` if there is no AC adapter.
+pub(crate) fn get_on_ac_power(root: &Path) -> Option<bool> {
+    let mut online = fs::read_dir(root)
+        .ok()?
+        .filter_map(|entry| {
+            let dir = entry.ok()?.path();
+            if read_str(&dir.join("type")).as_deref() != Some("Mains") {
+                return None;
+            }
+            read_u64(&dir.join("online")).map(|online| online != 0)
+        })
+        .peekable();
+    online.peek()?;
+    Some(online.any(|online| online))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{get_battery, get_on_ac_power};
+    use crate::Battery;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn check_get_battery() {
+        let root = tempfile::tempdir().expect("failed to create temporary directory");
+        let root = root.path();
+        assert_eq!(get_battery(root), None);
+
+        // AC adapters are not batteries.
+        let ac = root.join("AC");
+        fs::create_dir_all(&ac).expect("failed to create AC directory");
+        fs::write(ac.join("type"), "Mains\n").expect("failed to write type");
+        assert_eq!(get_battery(root), None);
+
+        // Neither are peripherals.
+        let mouse = root.join("hidpp_battery_0");
+        fs::create_dir_all(&mouse).expect("failed to create mouse directory");
+        fs::write(mouse.join("type"), "Battery\n").expect("failed to write type");
+        fs::write(mouse.join("scope"), "Device\n").expect("failed to write scope");
+        fs::write(mouse.join("capacity"), "50\n").expect("failed to write capacity");
+        assert_eq!(get_battery(root), None);
+
+        let battery = root.join("BAT0");
+        fs::create_dir_all(&battery).expect("failed to create battery directory");
+        fs::write(battery.join("type"), "Battery\n").expect("failed to write type");
+        fs::write(battery.join("status"), "Discharging\n").expect("failed to write status");
+        fs::write(battery.join("energy_now"), "25000000\n").expect("failed to write energy_now");
+        fs::write(battery.join("energy_full"), "50000000\n").expect("failed to write energy_full");
+        fs::write(battery.join("power_now"), "10000000\n").expect("failed to write power_now");
+        assert_eq!(
+            get_battery(root),
+            Some(Battery {
+                percentage: 50.,
+                is_charging: false,
+                time_remaining: Some(Duration::from_secs(9_000)),
+            })
+        );
+
+        fs::write(battery.join("status"), "Charging\n").expect("failed to write status");
+        fs::write(battery.join("capacity"), "49\n").expect("failed to write capacity");
+        assert_eq!(
+            get_battery(root),
+            Some(Battery {
+                percentage: 49.,
+                is_charging: true,
+                time_remaining: Some(Duration::from_secs(9_000)),
+            })
+        );
+
+        fs::write(battery.join("status"), "Full\n").expect("failed to write status");
+        fs::write(battery.join("capacity"), "100\n").expect("failed to write capacity");
+        assert_eq!(
+            get_battery(root),
+            Some(Battery {
+                percentage: 100.,
+                is_charging: false,
+                time_remaining: None,
+            })
+        );
+    }
+
+    #[test]
+    fn check_get_on_ac_power() {
+        let root = tempfile::tempdir().expect("failed to create temporary directory");
+        let root = root.path();
+        assert_eq!(get_on_ac_power(root), None);
+
+        let battery = root.join("BAT0");
+        fs::create_dir_all(&battery).expect("failed to create battery directory");
+        fs::write(battery.join("type"), "Battery\n").expect("failed to write type");
+        fs::write(battery.join("online"), "1\n").expect("failed to write online");
+        assert_eq!(get_on_ac_power(root), None);
+
+        let ac = root.join("AC");
+        fs::create_dir_all(&ac).expect("failed to create AC directory");
+        fs::write(ac.join("type"), "Mains\n").expect("failed to write type");
+        fs::write(ac.join("online"), "0\n").expect("failed to write online");
+        assert_eq!(get_on_ac_power(root), Some(false));
+
+        let adapter = root.join("ADP1");
+        fs::create_dir_all(&adapter).expect("failed to create adapter directory");
+        fs::write(adapter.join("type"), "Mains\n").expect("failed to write type");
+        fs::write(adapter.join("online"), "1\n").expect("failed to write online");
+        assert_eq!(get_on_ac_power(root), Some(true));
+    }
+}
@@ -0,0 +1,314 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::sys::utils::get_all_data;
+use crate::{CgroupLimits, CgroupStats};
+
+use std::path::{Path, PathBuf};
+
+/// Location of the cgroup hierarchy, relative to the sysfs root.
+pub(crate) const CGROUP_ROOT: &str = "fs/cgroup";
+
+fn read_u64(path: &Path) -> Option<u64> {
+    get_all_data(path, 64).ok()?.trim().parse().ok()
+}
+
+pub(crate) fn get_cgroup_stats(root: &Path, path: &Path) -> Option<CgroupStats> {
+    // `path` is absolute when it comes from `/proc/[pid]/cgroup`, so we need to strip the leading
+    // `/` otherwise `join` would replace `root`.
+    let path = path.strip_prefix("/").unwrap_or(path);
+    // Only allow paths which cannot escape from the cgroup hierarchy.
+    if !path
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)))
+    {
+        return None;
+    }
+    let dir = root.join(path);
+    if !dir.is_dir() {
+        return None;
+    }
+    let cpu_usage_usec = get_all_data(dir.join("cpu.stat"), 4_096)
+        .ok()
+        .and_then(|data| {
+            data.lines()
+                .find_map(|line| line.strip_prefix("usage_usec "))
+                .and_then(|value| value.trim().parse().ok())
+        })
+        .unwrap_or(0);
+    let memory_current = read_u64(&dir.join("memory.current")).unwrap_or(0);
+    // The file contains "max" if there is no limit.
+    let memory_max = read_u64(&dir.join("memory.max"));
+
+    Some(CgroupStats {
+        cpu_usage_usec,
+        memory_current,
+        memory_max,
+    })
+}
+
+/// Parses a cgroup limit. Returns `Some(None)` if there is no limit, which is written "max" with
+/// cgroup v2 and "-1" with cgroup v1.
+fn parse_limit(value: &str) -> Option<Option<u64>> {
+    match value.trim() {
+        "max" | "-1" => Some(None),
+        value => value.parse().ok().map(Some),
+    }
+}
+
+fn read_limit(path: &Path) -> Option<Option<u64>> {
+    parse_limit(&get_all_data(path, 64).ok()?)
+}
+
+/// Returns the directory of the cgroup the current process belongs to, using the content of
+/// `/proc/self/cgroup`. `controller` is the name of the cgroup v1 controller, or an empty string
+/// for cgroup v2.
+fn get_cgroup_dir(root: &Path, self_cgroup: &str, controller: &str) -> Option<PathBuf> {
+    // Each line is "$ID:$CONTROLLERS:$PATH", where `$CONTROLLERS` is empty for cgroup v2.
+    let path = self_cgroup.lines().find_map(|line| {
+        let mut parts = line.splitn(3, ':');
+        let controllers = parts.nth(1)?;
+        let path = parts.next()?;
+        let found = if controller.is_empty() {
+            controllers.is_empty()
+        } else {
+            controllers.split(',').any(|name| name == controller)
+        };
+        if found {
+            Some(path)
+        } else {
+            None
+        }
+    })?;
+    let root = if controller.is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(controller)
+    };
+    let dir = root.join(path.trim_start_matches('/'));
+    // Without cgroup namespace, the path is the one of the host, which isn't available in the
+    // container: the cgroup of the container is then mounted at the root of the hierarchy.
+    if dir.is_dir() {
+        Some(dir)
+    } else {
+        Some(root)
+    }
+}
+
+/// Returns the `(limit, usage)` memory values of the cgroup, in bytes.
+fn get_memory_values(root: &Path, self_cgroup: &str) -> Option<(Option<u64>, u64)> {
+    if let Some(dir) = get_cgroup_dir(root, self_cgroup, "") {
+        let memory_max = dir.join("memory.max");
+        if memory_max.exists() {
+            let limit = read_limit(&memory_max)?;
+            let usage = read_u64(&dir.join("memory.current")).unwrap_or(0);
+            return Some((limit, usage));
+        }
+    }
+    // cgroup v1. If there is no limit, the file contains a value close to `i64::MAX`.
+    let dir = get_cgroup_dir(root, self_cgroup, "memory")?;
+    let limit = read_limit(&dir.join("memory.limit_in_bytes"))?;
+    let usage = read_u64(&dir.join("memory.usage_in_bytes")).unwrap_or(0);
+    Some((limit, usage))
+}
+
+/// Returns the `(quota, period)` CPU values of the cgroup, in microseconds.
+fn get_cpu_values(root: &Path, self_cgroup: &str) -> Option<(Option<u64>, u64)> {
+    if let Some(dir) = get_cgroup_dir(root, self_cgroup, "") {
+        // The file contains "$MAX $PERIOD".
+        if let Ok(data) = get_all_data(dir.join("cpu.max"), 64) {
+            let mut parts = data.split_whitespace();
+            let quota = parse_limit(parts.next()?)?;
+            let period = parts.next()?.parse().ok()?;
+            return Some((quota, period));
+        }
+    }
+    let dir = get_cgroup_dir(root, self_cgroup, "cpu")?;
+    let quota = read_limit(&dir.join("cpu.cfs_quota_us"))?;
+    let period = read_u64(&dir.join("cpu.cfs_period_us"))?;
+    Some((quota, period))
+}
+
+/// `self_cgroup` is the content of `/proc/self/cgroup`. If `mem_total` is 0, the memory limit
+/// isn't compared to the memory of the system.
+pub(crate) fn get_cgroup_limits(
+    root: &Path,
+    self_cgroup: &str,
+    mem_total: u64,
+) -> Option<CgroupLimits> {
+    let (limit, usage) = get_memory_values(root, self_cgroup)?;
+    let limit = limit?;
+    // The limit can be greater than the memory of the system if it isn't set (cgroup v1).
+    if mem_total != 0 && limit >= mem_total {
+        return None;
+    }
+    let cpu_quota = get_cpu_values(root, self_cgroup).and_then(|(quota, period)| {
+        if period == 0 {
+            None
+        } else {
+            quota.map(|quota| quota as f64 / period as f64)
+        }
+    });
+    Some(CgroupLimits {
+        total_memory: limit,
+        free_memory: limit.saturating_sub(usage),
+        cpu_quota,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{get_cgroup_limits, get_cgroup_stats};
+    use crate::{CgroupLimits, CgroupStats};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn check_get_cgroup_stats() {
+        let root = tempfile::tempdir().expect("failed to create temporary directory");
+        let service = root.path().join("system.slice/foo.service");
+        fs::create_dir_all(&service).expect("failed to create subdirectories");
+        fs::write(
+            service.join("cpu.stat"),
+            "usage_usec 1234567\nuser_usec 1000000\nsystem_usec 234567\n",
+        )
+        .expect("failed to write cpu.stat");
+        fs::write(service.join("memory.current"), "4096\n")
+            .expect("failed to write memory.current");
+        fs::write(service.join("memory.max"), "max\n").expect("failed to write memory.max");
+
+        let expected = CgroupStats {
+            cpu_usage_usec: 1_234_567,
+            memory_current: 4_096,
+            memory_max: None,
+        };
+        assert_eq!(
+            get_cgroup_stats(root.path(), Path::new("/system.slice/foo.service")),
+            Some(expected)
+        );
+        assert_eq!(
+            get_cgroup_stats(root.path(), Path::new("system.slice/foo.service")),
+            Some(expected)
+        );
+
+        fs::write(service.join("memory.max"), "1048576\n").expect("failed to write memory.max");
+        assert_eq!(
+            get_cgroup_stats(root.path(), Path::new("/system.slice/foo.service"))
+                .and_then(|stats| stats.memory_max),
+            Some(1_048_576)
+        );
+
+        // The root cgroup doesn't have the memory files.
+        assert_eq!(
+            get_cgroup_stats(root.path(), Path::new("/system.slice")),
+            Some(CgroupStats::default())
+        );
+        assert_eq!(
+            get_cgroup_stats(root.path(), Path::new("/system.slice/bar.service")),
+            None
+        );
+        // Paths escaping from the cgroup hierarchy are rejected.
+        assert_eq!(
+            get_cgroup_stats(&service, Path::new("/../foo.service")),
+            None
+        );
+        assert_eq!(
+            get_cgroup_stats(&service, Path::new("..//foo.service")),
+            None
+        );
+        assert_eq!(
+            get_cgroup_stats(root.path(), Path::new("//system.slice/foo.service"))
+                .map(|stats| stats.cpu_usage_usec),
+            Some(1_234_567)
+        );
+    }
+
+    #[test]
+    fn check_get_cgroup_limits() {
+        let v1 = "12:memory:/\n4:cpu,cpuacct:/\n";
+        let hybrid = "12:memory:/\n4:cpu,cpuacct:/\n0::/\n";
+        let root = tempfile::tempdir().expect("failed to create temporary directory");
+        let root = root.path();
+        // No memory files: not in a container.
+        assert_eq!(get_cgroup_limits(root, v1, 1_000_000), None);
+
+        // cgroup v1 without limit.
+        fs::create_dir_all(root.join("memory")).expect("failed to create memory directory");
+        fs::create_dir_all(root.join("cpu")).expect("failed to create cpu directory");
+        fs::write(
+            root.join("memory/memory.limit_in_bytes"),
+            "9223372036854771712\n",
+        )
+        .expect("failed to write memory.limit_in_bytes");
+        fs::write(root.join("memory/memory.usage_in_bytes"), "1000\n")
+            .expect("failed to write memory.usage_in_bytes");
+        assert_eq!(get_cgroup_limits(root, v1, 1_000_000), None);
+        fs::write(root.join("memory/memory.limit_in_bytes"), "-1\n")
+            .expect("failed to write memory.limit_in_bytes");
+        assert_eq!(get_cgroup_limits(root, v1, 1_000_000), None);
+        assert_eq!(get_cgroup_limits(root, v1, 0), None);
+
+        // cgroup v1 with limits.
+        fs::write(root.join("memory/memory.limit_in_bytes"), "4000\n")
+            .expect("failed to write memory.limit_in_bytes");
+        fs::write(root.join("cpu/cpu.cfs_quota_us"), "-1\n")
+            .expect("failed to write cpu.cfs_quota_us");
+        fs::write(root.join("cpu/cpu.cfs_period_us"), "100000\n")
+            .expect("failed to write cpu.cfs_period_us");
+        let expected = CgroupLimits {
+            total_memory: 4_000,
+            free_memory: 3_000,
+            cpu_quota: None,
+        };
+        assert_eq!(get_cgroup_limits(root, v1, 1_000_000), Some(expected));
+        // The memory of the system is unknown.
+        assert_eq!(get_cgroup_limits(root, v1, 0), Some(expected));
+        assert_eq!(get_cgroup_limits(root, "", 1_000_000), None);
+        fs::write(root.join("cpu/cpu.cfs_quota_us"), "150000\n")
+            .expect("failed to write cpu.cfs_quota_us");
+        assert_eq!(
+            get_cgroup_limits(root, v1, 1_000_000).and_then(|limits| limits.cpu_quota),
+            Some(1.5)
+        );
+
+        // cgroup v2 files take precedence.
+        fs::write(root.join("memory.max"), "max\n").expect("failed to write memory.max");
+        fs::write(root.join("cpu.max"), "max 100000\n").expect("failed to write cpu.max");
+        assert_eq!(get_cgroup_limits(root, hybrid, 1_000_000), None);
+        fs::write(root.join("memory.max"), "2000\n").expect("failed to write memory.max");
+        fs::write(root.join("memory.current"), "2500\n").expect("failed to write memory.current");
+        assert_eq!(
+            get_cgroup_limits(root, hybrid, 1_000_000),
+            Some(CgroupLimits {
+                total_memory: 2_000,
+                free_memory: 0,
+                cpu_quota: None,
+            })
+        );
+        fs::write(root.join("cpu.max"), "50000 100000\n").expect("failed to write cpu.max");
+        assert_eq!(
+            get_cgroup_limits(root, hybrid, 1_000_000).and_then(|limits| limits.cpu_quota),
+            Some(0.5)
+        );
+
+        // The cgroup of the process is used if it is available.
+        let service = root.join("system.slice/foo.service");
+        fs::create_dir_all(&service).expect("failed to create subdirectories");
+        fs::write(service.join("memory.max"), "3000\n").expect("failed to write memory.max");
+        fs::write(service.join("memory.current"), "1000\n")
+            .expect("failed to write memory.current");
+        assert_eq!(
+            get_cgroup_limits(root, "0::/system.slice/foo.service\n", 1_000_000),
+            Some(CgroupLimits {
+                total_memory: 3_000,
+                free_memory: 2_000,
+                cpu_quota: None,
+            })
+        );
+        // Otherwise, the root of the hierarchy is used.
+        assert_eq!(
+            get_cgroup_limits(root, "0::/system.slice/bar.service\n", 1_000_000)
+                .map(|limits| limits.total_memory),
+            Some(2_000)
+        );
+    }
+}
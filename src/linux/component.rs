@@ -4,7 +4,7 @@
 //
 // Values in /sys/class/hwmonN are `c_long` or `c_ulong`
 // transposed to rust we only read `u32` or `i32` values.
-use crate::ComponentExt;
+use crate::{ComponentExt, Sensor, SensorKind};
 
 use std::collections::HashMap;
 use std::fs::{read_dir, File};
@@ -56,6 +56,7 @@ pub struct Component {
     /// - 4: thermistor
     /// - 5: AMD AMDSI
     /// - 6: Intel PECI
+    ///
     /// Not all types are supported by all chips
     sensor_type: Option<TermalSensorType>,
     /// Component Label
@@ -155,6 +156,7 @@ enum TermalSensorType {
     IntelPECI,
     /// Not all types are supported by all chips so we keep space for
     /// unknown sensors.
+    #[allow(dead_code)]
     Unknown(u8),
 }
 
@@ -248,7 +250,7 @@ impl Component {
             let (id, item) = filename.split_once('_')?;
             let id = id.get(4..)?.parse::<u32>().ok()?;
 
-            let component = matchings.entry(id).or_insert_with(Component::default);
+            let component = matchings.entry(id).or_default();
             let name = get_file_line(&folder.join("name"), 16);
             component.name = name.unwrap_or_default();
             let device_model = get_file_line(&folder.join("device/model"), 16);
@@ -330,23 +332,185 @@ impl ComponentExt for Component {
     }
 }
 
-pub(crate) fn get_components() -> Vec<Component> {
-    let mut components = Vec::with_capacity(10);
-    if let Ok(dir) = read_dir(Path::new("/sys/class/hwmon/")) {
-        for entry in dir.flatten() {
-            let entry = entry.path();
-            if !entry.is_dir()
-                || !entry
+/// Label prefixes of the sensors reporting the CPU die temperature, by order of preference.
+const CPU_TEMPERATURE_LABELS: &[&str] = &[
+    // Intel
+    "coretemp Package id",
+    // AMD
+    "k10temp Tdie",
+    "zenpower Tdie",
+    "k10temp Tctl",
+    "zenpower Tctl",
+    // ARM SoCs
+    "cpu_thermal",
+    "cpu-thermal",
+];
+
+pub(crate) fn cpu_temperature(components: &[Component]) -> Option<f32> {
+    CPU_TEMPERATURE_LABELS.iter().find_map(|prefix| {
+        let (sum, count) = components
+            .iter()
+            .filter(|c| c.label.starts_with(prefix))
+            .filter_map(|c| c.temperature)
+            .fold((0., 0), |(sum, count), temperature| {
+                (sum + temperature, count + 1)
+            });
+        if count == 0 {
+            None
+        } else {
+            Some(sum / count as f32)
+        }
+    })
+}
+
+/// Location of the `hwmon` folders, relative to the sysfs root.
+pub(crate) const HWMON_ROOT: &str = "class/hwmon";
+
+pub(crate) fn sensors_available(root: &Path) -> bool {
+    root.is_dir()
+}
+
+// Returns the `hwmon*` folders of `root` (which is `/sys/class/hwmon/`).
+fn hwmon_folders(root: &Path) -> impl Iterator<Item = PathBuf> {
+    read_dir(root)
+        .into_iter()
+        .flat_map(|dir| dir.flatten())
+        .map(|entry| entry.path())
+        .filter(|entry| {
+            entry.is_dir()
+                && entry
                     .file_name()
                     .and_then(|x| x.to_str())
                     .unwrap_or("")
                     .starts_with("hwmon")
-            {
+        })
+}
+
+pub(crate) fn get_components(root: &Path) -> Vec<Component> {
+    let mut components = Vec::with_capacity(10);
+    for entry in hwmon_folders(root) {
+        Component::from_hwmon(&mut components, &entry);
+    }
+    components.sort_by_key(|c| c.label.to_lowercase());
+    components
+}
+
+/// Returns the voltage (`in[0-*]_input`, in millivolts), current (`curr[1-*]_input`, in
+/// milliamperes) and fan (`fan[1-*]_input`, in RPM) sensors of the `hwmon` folders of `root`.
+///
+/// Temperature sensors are handled by `Component`.
+pub(crate) fn get_sensors(root: &Path) -> Vec<Sensor> {
+    let mut sensors = Vec::new();
+    for folder in hwmon_folders(root) {
+        let name = get_file_line(&folder.join("name"), 16).unwrap_or_default();
+        let dir = match read_dir(&folder) {
+            Ok(dir) => dir,
+            Err(_) => continue,
+        };
+        for entry in dir.flatten() {
+            let file_name = entry.file_name();
+            let channel = match file_name.to_str().and_then(|x| x.strip_suffix("_input")) {
+                Some(channel) => channel,
+                None => continue,
+            };
+            let (class, kind, unit_divider) = if channel.starts_with("curr") {
+                ("curr", SensorKind::Current, 1000.)
+            } else if channel.starts_with("fan") {
+                ("fan", SensorKind::Fan, 1.)
+            } else if channel.starts_with("in") {
+                ("in", SensorKind::Voltage, 1000.)
+            } else {
+                continue;
+            };
+            // Skips files like `intrusion0_input`.
+            if channel[class.len()..].parse::<u32>().is_err() {
                 continue;
             }
-            Component::from_hwmon(&mut components, &entry);
+            let value = match read_number_from_file::<i64>(&entry.path()) {
+                Some(value) => value as f32 / unit_divider,
+                None => continue,
+            };
+            let label = match get_file_line(&folder.join(format!("{channel}_label")), 16) {
+                Some(label) if !label.is_empty() => format!("{name} {label}"),
+                _ => format!("{name} {channel}"),
+            };
+            sensors.push(Sensor { label, kind, value });
         }
-        components.sort_by(|c1, c2| c1.label.to_lowercase().cmp(&c2.label.to_lowercase()));
     }
-    components
+    sensors.sort_by_key(|s| s.label.to_lowercase());
+    sensors
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cpu_temperature, get_sensors, Component};
+    use crate::{Sensor, SensorKind};
+    use std::fs;
+
+    fn component(label: &str, temperature: f32) -> Component {
+        Component {
+            label: label.to_owned(),
+            temperature: Some(temperature),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn check_cpu_temperature() {
+        assert_eq!(cpu_temperature(&[]), None);
+        assert_eq!(cpu_temperature(&[component("acpitz temp1", 30.)]), None);
+
+        let components = [
+            component("acpitz temp1", 30.),
+            component("coretemp Core 0", 45.),
+            component("coretemp Package id 0", 50.),
+            component("coretemp Package id 1", 60.),
+        ];
+        assert_eq!(cpu_temperature(&components), Some(55.));
+
+        // `Tdie` is preferred over `Tctl` which can have an offset.
+        let components = [
+            component("k10temp Tctl", 70.),
+            component("k10temp Tdie", 60.),
+        ];
+        assert_eq!(cpu_temperature(&components), Some(60.));
+    }
+
+    #[test]
+    fn check_get_sensors() {
+        let root = tempfile::tempdir().expect("failed to create temporary directory");
+        let hwmon = root.path().join("hwmon0");
+        fs::create_dir(&hwmon).expect("failed to create hwmon folder");
+        for (file, content) in [
+            ("name", "nct6775\n"),
+            ("in0_input", "1040\n"),
+            ("in0_label", "Vcore\n"),
+            ("in1_input", "3312\n"),
+            ("curr1_input", "1500\n"),
+            ("fan2_input", "1200\n"),
+            ("temp1_input", "45000\n"),
+            ("intrusion0_input", "1\n"),
+        ] {
+            fs::write(hwmon.join(file), content).expect("failed to write hwmon file");
+        }
+        // Not a `hwmon` folder.
+        fs::create_dir(root.path().join("other")).expect("failed to create folder");
+        fs::write(root.path().join("other/in0_input"), "1").expect("failed to write file");
+
+        let sensor = |label: &str, kind, value| Sensor {
+            label: label.to_owned(),
+            kind,
+            value,
+        };
+        assert_eq!(
+            get_sensors(root.path()),
+            vec![
+                sensor("nct6775 curr1", SensorKind::Current, 1.5),
+                sensor("nct6775 fan2", SensorKind::Fan, 1200.),
+                sensor("nct6775 in1", SensorKind::Voltage, 3.312),
+                sensor("nct6775 Vcore", SensorKind::Voltage, 1.04),
+            ]
+        );
+        assert!(get_sensors(&root.path().join("does_not_exist")).is_empty());
+    }
 }
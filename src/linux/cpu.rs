@@ -5,8 +5,10 @@
 use std::collections::HashSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
 
 use crate::sys::utils::to_u64;
+use crate::utils::{CounterDelta, CpuUsageHistory};
 use crate::{CpuExt, CpuRefreshKind};
 
 macro_rules! to_str {
@@ -24,6 +26,9 @@ pub(crate) struct CpusWrapper {
     /// For example when running `refresh_all` or `refresh_specifics`.
     need_cpus_update: bool,
     got_cpu_frequency: bool,
+    usage_history_capacity: usize,
+    pub(crate) context_switches: CounterDelta,
+    pub(crate) interrupts: CounterDelta,
 }
 
 impl CpusWrapper {
@@ -48,21 +53,40 @@ impl CpusWrapper {
             cpus: Vec::with_capacity(4),
             need_cpus_update: true,
             got_cpu_frequency: false,
+            usage_history_capacity: 0,
+            context_switches: CounterDelta::default(),
+            interrupts: CounterDelta::default(),
+        }
+    }
+
+    pub(crate) fn set_usage_history_capacity(&mut self, capacity: usize) {
+        self.usage_history_capacity = capacity;
+        self.global_cpu.usage_history.set_capacity(capacity);
+        for cpu in self.cpus.iter_mut() {
+            cpu.usage_history.set_capacity(capacity);
         }
     }
 
     pub(crate) fn refresh_if_needed(
         &mut self,
+        proc_root: &Path,
+        sys_root: &Path,
         only_update_global_cpu: bool,
         refresh_kind: CpuRefreshKind,
     ) {
         if self.need_cpus_update {
-            self.refresh(only_update_global_cpu, refresh_kind);
+            self.refresh(proc_root, sys_root, only_update_global_cpu, refresh_kind);
         }
     }
 
-    pub(crate) fn refresh(&mut self, only_update_global_cpu: bool, refresh_kind: CpuRefreshKind) {
-        let f = match File::open("/proc/stat") {
+    pub(crate) fn refresh(
+        &mut self,
+        proc_root: &Path,
+        sys_root: &Path,
+        only_update_global_cpu: bool,
+        refresh_kind: CpuRefreshKind,
+    ) {
+        let f = match File::open(proc_root.join("stat")) {
             Ok(f) => f,
             Err(_e) => {
                 sysinfo_debug!("failed to retrieve CPU information: {:?}", _e);
@@ -76,7 +100,7 @@ impl CpusWrapper {
         let first = self.cpus.is_empty();
         let mut it = buf.split(b'\n');
         let (vendor_id, brand) = if first {
-            get_vendor_id_and_brand()
+            get_vendor_id_and_brand(proc_root)
         } else {
             (String::new(), String::new())
         };
@@ -108,6 +132,7 @@ impl CpusWrapper {
             if first || !only_update_global_cpu {
                 while let Some(Ok(line)) = it.next() {
                     if &line[..3] != b"cpu" {
+                        self.update_counters(&line);
                         break;
                     }
 
@@ -129,9 +154,11 @@ impl CpusWrapper {
                             vendor_id.clone(),
                             brand.clone(),
                         ));
-                    } else {
+                    } else if let Some(cpu) = self.cpus.get_mut(i) {
+                        // A CPU which was offline when the list was created could now be
+                        // listed, so we need to check the index.
                         parts.next(); // we don't want the name again
-                        self.cpus[i].set(
+                        cpu.set(
                             parts.next().map(to_u64).unwrap_or(0),
                             parts.next().map(to_u64).unwrap_or(0),
                             parts.next().map(to_u64).unwrap_or(0),
@@ -150,6 +177,13 @@ impl CpusWrapper {
             }
         }
 
+        for line in it {
+            match line {
+                Ok(line) => self.update_counters(&line),
+                Err(_) => break,
+            }
+        }
+
         if refresh_kind.frequency() {
             #[cfg(feature = "multithread")]
             use rayon::iter::{
@@ -176,7 +210,7 @@ impl CpusWrapper {
             self.global_cpu.frequency = iter_mut(&mut self.cpus)
                 .enumerate()
                 .map(|(pos, proc_)| {
-                    proc_.frequency = get_cpu_frequency(pos);
+                    proc_.frequency = get_cpu_frequency(proc_root, sys_root, pos);
                     proc_.frequency
                 })
                 .max()
@@ -188,6 +222,23 @@ impl CpusWrapper {
         if first {
             self.global_cpu.vendor_id = vendor_id;
             self.global_cpu.brand = brand;
+            for cpu in self.cpus.iter_mut() {
+                cpu.usage_history.set_capacity(self.usage_history_capacity);
+            }
+        }
+    }
+
+    // Updates the interrupts and context switches counters if `line` contains one of them.
+    fn update_counters(&mut self, line: &[u8]) {
+        let mut parts = line.split(|x| *x == b' ').filter(|s| !s.is_empty());
+        let counter = match parts.next() {
+            Some(b"intr") => &mut self.interrupts,
+            Some(b"ctxt") => &mut self.context_switches,
+            _ => return,
+        };
+        // For `intr`, the first value is the total of all interrupts.
+        if let Some(total) = parts.next() {
+            counter.update(to_u64(total));
         }
     }
 
@@ -330,6 +381,7 @@ pub struct Cpu {
     pub(crate) frequency: u64,
     pub(crate) vendor_id: String,
     pub(crate) brand: String,
+    usage_history: CpuUsageHistory,
 }
 
 impl Cpu {
@@ -361,9 +413,32 @@ impl Cpu {
             frequency,
             vendor_id,
             brand,
+            usage_history: CpuUsageHistory::default(),
         }
     }
 
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::CpuSnapshot) -> Cpu {
+        let mut cpu = Cpu::new_with_values(
+            &snapshot.name,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            snapshot.frequency,
+            snapshot.vendor_id.clone(),
+            snapshot.brand.clone(),
+        );
+        cpu.cpu_usage = snapshot.cpu_usage;
+        cpu
+    }
+
     pub(crate) fn set(
         &mut self,
         user: u64,
@@ -398,6 +473,16 @@ impl Cpu {
         if self.cpu_usage > 100. {
             self.cpu_usage = 100.; // to prevent the percentage to go above 100%
         }
+        self.usage_history.push(self.cpu_usage);
+    }
+
+    fn state_usage<F: Fn(&CpuValues) -> u64>(&self, get_value: F) -> f32 {
+        let total_time = self.total_time.saturating_sub(self.old_total_time);
+        if total_time == 0 {
+            return 0.;
+        }
+        let time = get_value(&self.new_values).saturating_sub(get_value(&self.old_values));
+        (time as f32 / total_time as f32 * 100.).min(100.)
     }
 }
 
@@ -406,6 +491,26 @@ impl CpuExt for Cpu {
         self.cpu_usage
     }
 
+    fn user_usage(&self) -> f32 {
+        self.state_usage(|values| values.user)
+    }
+
+    fn system_usage(&self) -> f32 {
+        self.state_usage(|values| values.system)
+    }
+
+    fn nice_usage(&self) -> f32 {
+        self.state_usage(|values| values.nice)
+    }
+
+    fn idle_usage(&self) -> f32 {
+        self.state_usage(|values| values.idle)
+    }
+
+    fn usage_history(&self) -> &[f32] {
+        self.usage_history.values()
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -424,12 +529,12 @@ impl CpuExt for Cpu {
     }
 }
 
-pub(crate) fn get_cpu_frequency(cpu_core_index: usize) -> u64 {
+pub(crate) fn get_cpu_frequency(proc_root: &Path, sys_root: &Path, cpu_core_index: usize) -> u64 {
     let mut s = String::new();
-    if File::open(format!(
-        "/sys/devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq",
+    if File::open(sys_root.join(format!(
+        "devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq",
         cpu_core_index
-    ))
+    )))
     .and_then(|mut f| f.read_to_string(&mut s))
     .is_ok()
     {
@@ -441,7 +546,7 @@ pub(crate) fn get_cpu_frequency(cpu_core_index: usize) -> u64 {
         }
     }
     s.clear();
-    if File::open("/proc/cpuinfo")
+    if File::open(proc_root.join("cpuinfo"))
         .and_then(|mut f| f.read_to_string(&mut s))
         .is_err()
     {
@@ -454,16 +559,18 @@ pub(crate) fn get_cpu_frequency(cpu_core_index: usize) -> u64 {
             || line.starts_with("bogomips per cpu")
     });
     find_cpu_mhz
-        .and_then(|line| line.split(':').last())
+        .and_then(|line| line.split(':').next_back())
         .and_then(|val| val.replace("MHz", "").trim().parse::<f64>().ok())
         .map(|speed| speed as u64)
         .unwrap_or_default()
 }
 
 #[allow(unused_assignments)]
-pub(crate) fn get_physical_core_count() -> Option<usize> {
+pub(crate) fn get_physical_core_count(proc_root: &Path) -> Option<usize> {
     let mut s = String::new();
-    if let Err(_e) = File::open("/proc/cpuinfo").and_then(|mut f| f.read_to_string(&mut s)) {
+    if let Err(_e) =
+        File::open(proc_root.join("cpuinfo")).and_then(|mut f| f.read_to_string(&mut s))
+    {
         sysinfo_debug!("Cannot read `/proc/cpuinfo` file: {:?}", _e);
         return None;
     }
@@ -518,9 +625,9 @@ pub(crate) fn get_physical_core_count() -> Option<usize> {
 }
 
 /// Returns the brand/vendor string for the first CPU (which should be the same for all CPUs).
-pub(crate) fn get_vendor_id_and_brand() -> (String, String) {
+pub(crate) fn get_vendor_id_and_brand(proc_root: &Path) -> (String, String) {
     let mut s = String::new();
-    if File::open("/proc/cpuinfo")
+    if File::open(proc_root.join("cpuinfo"))
         .and_then(|mut f| f.read_to_string(&mut s))
         .is_err()
     {
@@ -529,7 +636,7 @@ pub(crate) fn get_vendor_id_and_brand() -> (String, String) {
 
     fn get_value(s: &str) -> String {
         s.split(':')
-            .last()
+            .next_back()
             .map(|x| x.trim().to_owned())
             .unwrap_or_default()
     }
@@ -1,14 +1,23 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+use crate::sys::procfs::unescape_path;
 use crate::sys::utils::{get_all_data, to_cpath};
+use crate::utils::DiskListFilter;
 use crate::{DiskExt, DiskType};
 
 use libc::statvfs;
+use std::collections::HashSet;
 use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::mem;
 use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// How long we wait for the server of a network file system to answer.
+const NETWORK_FILE_SYSTEM_TIMEOUT: Duration = Duration::from_secs(1);
 
 macro_rules! cast {
     ($x:expr) => {
@@ -25,7 +34,14 @@ pub struct Disk {
     mount_point: PathBuf,
     total_space: u64,
     available_space: u64,
+    free_space: u64,
+    total_inodes: u64,
+    available_inodes: u64,
     is_removable: bool,
+    mount_options: Vec<String>,
+    model: String,
+    serial: String,
+    last_refresh_failed: bool,
 }
 
 impl DiskExt for Disk {
@@ -53,65 +69,175 @@ impl DiskExt for Disk {
         self.available_space
     }
 
+    fn free_space(&self) -> u64 {
+        self.free_space
+    }
+
+    fn total_inodes(&self) -> u64 {
+        self.total_inodes
+    }
+
+    fn available_inodes(&self) -> u64 {
+        self.available_inodes
+    }
+
     fn is_removable(&self) -> bool {
         self.is_removable
     }
 
+    fn mount_options(&self) -> &[String] {
+        &self.mount_options
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    fn last_refresh_failed(&self) -> bool {
+        self.last_refresh_failed
+    }
+
     fn refresh(&mut self) -> bool {
-        unsafe {
-            let mut stat: statvfs = mem::zeroed();
-            let mount_point_cpath = to_cpath(&self.mount_point);
-            if statvfs(mount_point_cpath.as_ptr() as *const _, &mut stat) == 0 {
+        match get_statvfs(&self.mount_point, &self.file_system) {
+            Some(stat) => {
                 let tmp = cast!(stat.f_bsize).saturating_mul(cast!(stat.f_bavail));
                 self.available_space = cast!(tmp);
+                let tmp = cast!(stat.f_bsize).saturating_mul(cast!(stat.f_bfree));
+                self.free_space = cast!(tmp);
+                self.total_inodes = cast!(stat.f_files);
+                self.available_inodes = cast!(stat.f_favail);
+                self.last_refresh_failed = false;
                 true
-            } else {
+            }
+            None => {
+                self.last_refresh_failed = true;
                 false
             }
         }
     }
 }
 
+fn is_network_file_system(file_system: &[u8]) -> bool {
+    matches!(
+        file_system,
+        b"nfs" | b"nfs4" | b"cifs" | b"smbfs" | b"smb3" | b"ncpfs" | b"afs" | b"fuse.sshfs"
+    )
+}
+
+fn statvfs_blocking(mount_point: &Path) -> Option<statvfs> {
+    let mount_point_cpath = to_cpath(mount_point);
+    unsafe {
+        let mut stat: statvfs = mem::zeroed();
+        if statvfs(mount_point_cpath.as_ptr() as *const _, &mut stat) == 0 {
+            Some(stat)
+        } else {
+            None
+        }
+    }
+}
+
+// `statvfs` blocks as long as the server of a network file system doesn't answer, so for them,
+// it is called from another thread and given up after `NETWORK_FILE_SYSTEM_TIMEOUT`.
+fn get_statvfs(mount_point: &Path, file_system: &[u8]) -> Option<statvfs> {
+    // Mount points whose `statvfs` call is still blocked. They're not queried again until it
+    // returns so that we don't end up with more and more blocked threads.
+    static PENDING: once_cell::sync::Lazy<Mutex<HashSet<PathBuf>>> =
+        once_cell::sync::Lazy::new(Default::default);
+
+    if !is_network_file_system(file_system) {
+        return statvfs_blocking(mount_point);
+    }
+    if !PENDING.lock().ok()?.insert(mount_point.to_owned()) {
+        sysinfo_debug!("{:?} is still not answering", mount_point);
+        return None;
+    }
+    let (sender, receiver) = mpsc::channel();
+    let path = mount_point.to_owned();
+    let spawned = thread::Builder::new().spawn(move || {
+        let stat = statvfs_blocking(&path);
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.remove(&path);
+        }
+        let _ = sender.send(stat);
+    });
+    if spawned.is_err() {
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.remove(mount_point);
+        }
+        return None;
+    }
+    match receiver.recv_timeout(NETWORK_FILE_SYSTEM_TIMEOUT) {
+        Ok(stat) => stat,
+        Err(_) => {
+            sysinfo_debug!("{:?} didn't answer in time", mount_point);
+            None
+        }
+    }
+}
+
 fn new_disk(
+    sys_root: &Path,
     device_name: &OsStr,
     mount_point: &Path,
     file_system: &[u8],
+    mount_options: &str,
     removable_entries: &[PathBuf],
 ) -> Option<Disk> {
-    let mount_point_cpath = to_cpath(mount_point);
-    let type_ = find_type_for_device_name(device_name);
     let mut total = 0;
     let mut available = 0;
-    unsafe {
-        let mut stat: statvfs = mem::zeroed();
-        if statvfs(mount_point_cpath.as_ptr() as *const _, &mut stat) == 0 {
-            let bsize = cast!(stat.f_bsize);
-            let blocks = cast!(stat.f_blocks);
-            let bavail = cast!(stat.f_bavail);
-            total = bsize.saturating_mul(blocks);
-            available = bsize.saturating_mul(bavail);
-        }
-        if total == 0 {
-            return None;
-        }
-        let mount_point = mount_point.to_owned();
-        let is_removable = removable_entries
-            .iter()
-            .any(|e| e.as_os_str() == device_name);
-        Some(Disk {
-            type_,
-            device_name: device_name.to_owned(),
-            file_system: file_system.to_owned(),
-            mount_point,
-            total_space: cast!(total),
-            available_space: cast!(available),
-            is_removable,
-        })
+    let mut free = 0;
+    let mut total_inodes = 0;
+    let mut available_inodes = 0;
+    let stat = get_statvfs(mount_point, file_system);
+    let last_refresh_failed = stat.is_none();
+    if let Some(stat) = stat {
+        let bsize = cast!(stat.f_bsize);
+        let blocks = cast!(stat.f_blocks);
+        let bavail = cast!(stat.f_bavail);
+        let bfree = cast!(stat.f_bfree);
+        total = bsize.saturating_mul(blocks);
+        available = bsize.saturating_mul(bavail);
+        free = bsize.saturating_mul(bfree);
+        total_inodes = cast!(stat.f_files);
+        available_inodes = cast!(stat.f_favail);
     }
+    // A network file system whose server doesn't answer is kept so it can be refreshed later.
+    if total == 0 && !(last_refresh_failed && is_network_file_system(file_system)) {
+        return None;
+    }
+    let mount_point = mount_point.to_owned();
+    let is_removable = removable_entries
+        .iter()
+        .any(|e| e.as_os_str() == device_name);
+    let block_device = find_block_device_name(device_name);
+    let type_ = find_type_for_block_device(sys_root, &block_device);
+    let model = read_block_device_info(sys_root, &block_device, "device/model");
+    let serial = read_block_device_info(sys_root, &block_device, "device/serial");
+    Some(Disk {
+        type_,
+        device_name: device_name.to_owned(),
+        file_system: file_system.to_owned(),
+        mount_point,
+        total_space: cast!(total),
+        available_space: cast!(available),
+        free_space: cast!(free),
+        total_inodes,
+        available_inodes,
+        is_removable,
+        mount_options: mount_options.split(',').map(|o| o.to_owned()).collect(),
+        model,
+        serial,
+        last_refresh_failed,
+    })
 }
 
+// Returns the name of the device under `/sys/block/` that `device_name` belongs to.
 #[allow(clippy::manual_range_contains)]
-fn find_type_for_device_name(device_name: &OsStr) -> DiskType {
+fn find_block_device_name(device_name: &OsStr) -> OsString {
     // The format of devices are as follows:
     //  - device_name is symbolic link in the case of /dev/mapper/
     //     and /dev/root, and the target is corresponding device under
@@ -128,7 +254,7 @@ fn find_type_for_device_name(device_name: &OsStr) -> DiskType {
     if device_name_path.starts_with("/dev/mapper/") {
         // Recursively solve, for example /dev/dm-0
         if real_path != device_name_path {
-            return find_type_for_device_name(OsStr::new(&real_path));
+            return find_block_device_name(OsStr::new(&real_path));
         }
     } else if device_name_path.starts_with("/dev/sd") || device_name_path.starts_with("/dev/vd") {
         // Turn "sda1" into "sda" or "vda1" into "vda"
@@ -143,7 +269,7 @@ fn find_type_for_device_name(device_name: &OsStr) -> DiskType {
     } else if device_name_path.starts_with("/dev/root") {
         // Recursively solve, for example /dev/mmcblk0p1
         if real_path != device_name_path {
-            return find_type_for_device_name(OsStr::new(&real_path));
+            return find_block_device_name(OsStr::new(&real_path));
         }
     } else if device_name_path.starts_with("/dev/mmcblk") {
         // Turn "mmcblk0p1" into "mmcblk0"
@@ -157,11 +283,13 @@ fn find_type_for_device_name(device_name: &OsStr) -> DiskType {
         real_path = real_path.trim_start_matches("/dev/");
     }
 
-    let trimmed: &OsStr = OsStrExt::from_bytes(real_path.as_bytes());
+    OsStr::from_bytes(real_path.as_bytes()).to_owned()
+}
 
-    let path = Path::new("/sys/block/")
-        .to_owned()
-        .join(trimmed)
+fn find_type_for_block_device(sys_root: &Path, block_device: &OsStr) -> DiskType {
+    let path = sys_root
+        .join("block")
+        .join(block_device)
         .join("queue/rotational");
     // Normally, this file only contains '0' or '1' but just in case, we get 8 bytes...
     match get_all_data(path, 8)
@@ -181,7 +309,13 @@ fn find_type_for_device_name(device_name: &OsStr) -> DiskType {
     }
 }
 
-fn get_all_disks_inner(content: &str) -> Vec<Disk> {
+fn read_block_device_info(sys_root: &Path, block_device: &OsStr, file: &str) -> String {
+    get_all_data(sys_root.join("block").join(block_device).join(file), 256)
+        .map(|s| s.trim().to_owned())
+        .unwrap_or_default()
+}
+
+fn get_all_disks_inner(sys_root: &Path, content: &str, filter: DiskListFilter) -> Vec<Disk> {
     // The goal of this array is to list all removable devices (the ones whose name starts with
     // "usb-"). Then we check if
     let removable_entries = match fs::read_dir("/dev/disk/by-id/") {
@@ -210,17 +344,15 @@ fn get_all_disks_inner(content: &str) -> Vec<Disk> {
             // fs_spec<tab>fs_file<tab>fs_vfstype<tab>other fields
             let mut fields = line.split_whitespace();
             let fs_spec = fields.next().unwrap_or("");
-            let fs_file = fields
-                .next()
-                .unwrap_or("")
-                .replace("\\134", "\\")
-                .replace("\\040", " ")
-                .replace("\\011", "\t")
-                .replace("\\012", "\n");
+            let fs_file = unescape_path(fields.next().unwrap_or(""));
             let fs_vfstype = fields.next().unwrap_or("");
-            (fs_spec, fs_file, fs_vfstype)
+            let fs_mntops = fields.next().unwrap_or("");
+            (fs_spec, fs_file, fs_vfstype, fs_mntops)
         })
-        .filter(|(fs_spec, fs_file, fs_vfstype)| {
+        .filter(|(fs_spec, fs_file, fs_vfstype, _)| {
+            if filter == DiskListFilter::All {
+                return true;
+            }
             // Check if fs_vfstype is one of our 'ignored' file systems.
             let filtered = matches!(
                 *fs_vfstype,
@@ -236,26 +368,39 @@ fn get_all_disks_inner(content: &str) -> Vec<Disk> {
                 "rpc_pipefs" | // The pipefs pseudo file system service
                 "iso9660" // optical media
             );
+            let local_only_filtered = filter == DiskListFilter::LocalOnly
+                && (*fs_vfstype == "autofs" || is_network_file_system(fs_vfstype.as_bytes()));
 
             !(filtered ||
+               local_only_filtered ||
                fs_file.starts_with("/sys") || // check if fs_file is an 'ignored' mount point
                fs_file.starts_with("/proc") ||
                (fs_file.starts_with("/run") && !fs_file.starts_with("/run/media")) ||
                fs_spec.starts_with("sunrpc"))
         })
-        .filter_map(|(fs_spec, fs_file, fs_vfstype)| {
+        .filter_map(|(fs_spec, fs_file, fs_vfstype, fs_mntops)| {
             new_disk(
+                sys_root,
                 fs_spec.as_ref(),
                 Path::new(&fs_file),
                 fs_vfstype.as_bytes(),
+                fs_mntops,
                 &removable_entries,
             )
         })
         .collect()
 }
 
-pub(crate) fn get_all_disks() -> Vec<Disk> {
-    get_all_disks_inner(&get_all_data("/proc/mounts", 16_385).unwrap_or_default())
+pub(crate) fn get_all_disks(
+    proc_root: &Path,
+    sys_root: &Path,
+    filter: DiskListFilter,
+) -> Vec<Disk> {
+    get_all_disks_inner(
+        sys_root,
+        &get_all_data(proc_root.join("mounts"), 16_385).unwrap_or_default(),
+        filter,
+    )
 }
 
 // #[test]
@@ -1,10 +1,13 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+pub(crate) mod battery;
+pub(crate) mod cgroup;
 pub mod component;
 pub mod cpu;
 pub mod disk;
 pub mod network;
 pub mod process;
+pub(crate) mod procfs;
 pub mod system;
 pub(crate) mod utils;
 
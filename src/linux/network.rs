@@ -2,14 +2,17 @@
 
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
+use crate::utils::{keep_network_after_list_refresh, ElapsedTime};
 use crate::{NetworkExt, NetworksExt, NetworksIter};
 use std::collections::{hash_map, HashMap};
 
 #[doc = include_str!("../../md_doc/networks.md")]
 pub struct Networks {
     interfaces: HashMap<String, NetworkData>,
+    sysfs_net: PathBuf,
 }
 
 macro_rules! old_and_new {
@@ -46,8 +49,13 @@ impl Networks {
     pub(crate) fn new() -> Self {
         Networks {
             interfaces: HashMap::new(),
+            sysfs_net: PathBuf::from("/sys/class/net"),
         }
     }
+
+    pub(crate) fn set_sys_root(&mut self, sys_root: &Path) {
+        self.sysfs_net = sys_root.join("class/net");
+    }
 }
 
 fn refresh_networks_list_from_sysfs(
@@ -56,6 +64,7 @@ fn refresh_networks_list_from_sysfs(
 ) {
     if let Ok(dir) = std::fs::read_dir(sysfs_net) {
         let mut data = vec![0; 30];
+        let now = Instant::now();
 
         for stats in interfaces.values_mut() {
             stats.updated = false;
@@ -77,7 +86,7 @@ fn refresh_networks_list_from_sysfs(
             // let tx_compressed = read(parent, "tx_compressed", &mut data);
             match interfaces.entry(entry) {
                 hash_map::Entry::Occupied(mut e) => {
-                    let mut interface = e.get_mut();
+                    let interface = e.get_mut();
                     old_and_new!(interface, rx_bytes, old_rx_bytes);
                     old_and_new!(interface, tx_bytes, old_tx_bytes);
                     old_and_new!(interface, rx_packets, old_rx_packets);
@@ -86,6 +95,7 @@ fn refresh_networks_list_from_sysfs(
                     old_and_new!(interface, tx_errors, old_tx_errors);
                     // old_and_new!(e, rx_compressed, old_rx_compressed);
                     // old_and_new!(e, tx_compressed, old_tx_compressed);
+                    interface.elapsed.update(now);
                     interface.updated = true;
                 }
                 hash_map::Entry::Vacant(e) => {
@@ -106,19 +116,21 @@ fn refresh_networks_list_from_sysfs(
                         // old_rx_compressed: rx_compressed,
                         // tx_compressed,
                         // old_tx_compressed: tx_compressed,
+                        elapsed: ElapsedTime::new(now),
                         updated: true,
+                        inactive_refreshes: 0,
                     });
                 }
             };
         }
 
-        // Remove interfaces which are gone.
-        interfaces.retain(|_, d| d.updated);
+        // Remove interfaces which are gone for too long.
+        interfaces.retain(|_, d| d.keep_after_list_refresh());
     }
 }
 
 impl NetworksExt for Networks {
-    fn iter(&self) -> NetworksIter {
+    fn iter(&self) -> NetworksIter<'_> {
         NetworksIter::new(self.interfaces.iter())
     }
 
@@ -126,12 +138,15 @@ impl NetworksExt for Networks {
         let mut v = vec![0; 30];
 
         for (interface_name, data) in self.interfaces.iter_mut() {
-            data.update(interface_name, &mut v);
+            // The files of the interfaces which are gone cannot be read anymore.
+            if data.is_active() {
+                data.update(&self.sysfs_net.join(interface_name), &mut v);
+            }
         }
     }
 
     fn refresh_networks_list(&mut self) {
-        refresh_networks_list_from_sysfs(&mut self.interfaces, Path::new("/sys/class/net/"));
+        refresh_networks_list_from_sysfs(&mut self.interfaces, &self.sysfs_net);
     }
 }
 
@@ -167,13 +182,34 @@ pub struct NetworkData {
     // /// compression (e.g: PPP).
     // tx_compressed: usize,
     // old_tx_compressed: usize,
+    elapsed: ElapsedTime,
     /// Whether or not the above data has been updated during refresh
     updated: bool,
+    // Number of calls to `refresh_networks_list` since the interface disappeared.
+    inactive_refreshes: u8,
 }
 
 impl NetworkData {
-    fn update(&mut self, path: &str, data: &mut Vec<u8>) {
-        let path = &Path::new("/sys/class/net/").join(path).join("statistics");
+    // Called at the end of `refresh_networks_list`. Returns `false` once the interface should be
+    // removed.
+    fn keep_after_list_refresh(&mut self) -> bool {
+        keep_network_after_list_refresh(
+            self.updated,
+            &mut self.inactive_refreshes,
+            &mut [
+                (&mut self.old_rx_bytes, self.rx_bytes),
+                (&mut self.old_tx_bytes, self.tx_bytes),
+                (&mut self.old_rx_packets, self.rx_packets),
+                (&mut self.old_tx_packets, self.tx_packets),
+                (&mut self.old_rx_errors, self.rx_errors),
+                (&mut self.old_tx_errors, self.tx_errors),
+            ],
+        )
+    }
+
+    fn update(&mut self, path: &Path, data: &mut Vec<u8>) {
+        let path = &path.join("statistics");
+        self.elapsed.update(Instant::now());
         old_and_new!(self, rx_bytes, old_rx_bytes, read(path, "rx_bytes", data));
         old_and_new!(self, tx_bytes, old_tx_bytes, read(path, "tx_bytes", data));
         old_and_new!(
@@ -216,6 +252,10 @@ impl NetworkData {
 }
 
 impl NetworkExt for NetworkData {
+    fn is_active(&self) -> bool {
+        self.inactive_refreshes == 0
+    }
+
     fn received(&self) -> u64 {
         self.rx_bytes.saturating_sub(self.old_rx_bytes)
     }
@@ -232,6 +272,14 @@ impl NetworkExt for NetworkData {
         self.tx_bytes
     }
 
+    fn received_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.received())
+    }
+
+    fn transmitted_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.transmitted())
+    }
+
     fn packets_received(&self) -> u64 {
         self.rx_packets.saturating_sub(self.old_rx_packets)
     }
@@ -268,6 +316,7 @@ impl NetworkExt for NetworkData {
 #[cfg(test)]
 mod test {
     use super::refresh_networks_list_from_sysfs;
+    use crate::NetworkExt;
     use std::collections::HashMap;
     use std::fs;
 
@@ -308,7 +357,68 @@ mod test {
 
         fs::remove_dir(&itf1_dir).expect("failed to remove subdirectory");
 
+        // The interface is kept for a few refreshes but marked as inactive.
+        for _ in 0..3 {
+            refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
+            assert!(!interfaces["itf1"].is_active());
+            assert!(interfaces["itf2"].is_active());
+        }
+
         refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
         assert_eq!(interfaces.keys().collect::<Vec<_>>(), ["itf2"]);
     }
+
+    #[test]
+    fn refresh_networks_list_inactive_interface() {
+        let sys_net_dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let statistics = sys_net_dir.path().join("tun0").join("statistics");
+        fs::create_dir_all(&statistics).expect("failed to create subdirectory");
+        fs::write(statistics.join("rx_bytes"), "1000\n").expect("failed to write rx_bytes");
+
+        let mut interfaces = HashMap::new();
+
+        refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
+        fs::write(statistics.join("rx_bytes"), "3000\n").expect("failed to write rx_bytes");
+        refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
+        assert_eq!(interfaces["tun0"].received(), 2_000);
+
+        fs::remove_dir_all(sys_net_dir.path().join("tun0")).expect("failed to remove subdirectory");
+        refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
+        let interface = &interfaces["tun0"];
+        assert!(!interface.is_active());
+        // The last values are kept but there is no more traffic.
+        assert_eq!(interface.total_received(), 3_000);
+        assert_eq!(interface.received(), 0);
+        assert_eq!(interface.received_per_second(), 0.);
+
+        // If it comes back, it's active again.
+        fs::create_dir_all(&statistics).expect("failed to create subdirectory");
+        fs::write(statistics.join("rx_bytes"), "3500\n").expect("failed to write rx_bytes");
+        refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
+        let interface = &interfaces["tun0"];
+        assert!(interface.is_active());
+        assert_eq!(interface.received(), 500);
+    }
+
+    #[test]
+    fn refresh_networks_list_rates() {
+        let sys_net_dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let statistics = sys_net_dir.path().join("itf1").join("statistics");
+        fs::create_dir_all(&statistics).expect("failed to create subdirectory");
+        fs::write(statistics.join("rx_bytes"), "1000\n").expect("failed to write rx_bytes");
+
+        let mut interfaces = HashMap::new();
+
+        refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
+        // No previous refresh so no rate.
+        assert_eq!(interfaces["itf1"].received_per_second(), 0.);
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        fs::write(statistics.join("rx_bytes"), "3000\n").expect("failed to write rx_bytes");
+        refresh_networks_list_from_sysfs(&mut interfaces, sys_net_dir.path());
+        let interface = &interfaces["itf1"];
+        assert_eq!(interface.received(), 2_000);
+        assert!(interface.received_per_second() > 0.);
+        assert_eq!(interface.transmitted_per_second(), 0.);
+    }
 }
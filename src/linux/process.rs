@@ -6,17 +6,24 @@ use std::fmt;
 use std::fs::{self, File};
 use std::io::Read;
 use std::mem::MaybeUninit;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use libc::{gid_t, kill, uid_t};
 
+use crate::sys::procfs::{
+    is_kernel_thread, parse_net_dev, parse_socket_inode, parse_stat_line, parse_status_kv,
+};
 use crate::sys::system::SystemInfo;
 use crate::sys::utils::{
     get_all_data, get_all_data_from_file, realpath, FileCounter, PathHandler, PathPush,
 };
 use crate::utils::into_iter;
-use crate::{DiskUsage, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Signal, Uid};
+use crate::{
+    DiskUsage, Error, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Signal, ThreadInfo,
+    Uid,
+};
 
 #[doc(hidden)]
 impl From<u32> for ProcessStatus {
@@ -72,6 +79,8 @@ impl fmt::Display for ProcessStatus {
 #[doc = include_str!("../../md_doc/process.md")]
 pub struct Process {
     pub(crate) name: String,
+    // For tasks, `name` is the one of the process and this is the name of the thread.
+    thread_name: String,
     pub(crate) cmd: Vec<String>,
     pub(crate) exe: PathBuf,
     pub(crate) pid: Pid,
@@ -90,11 +99,31 @@ pub struct Process {
     run_time: u64,
     pub(crate) updated: bool,
     cpu_usage: f32,
+    cpu_time: u64,
+    iowait_time: u64,
     user_id: Option<Uid>,
+    real_user_id: Option<Uid>,
     group_id: Option<Gid>,
+    real_group_id: Option<Gid>,
+    tracer_pid: Option<Pid>,
+    open_files: Option<usize>,
+    network_usage: Option<(u64, u64)>,
+    is_64bit: Option<bool>,
+    minor_faults: u64,
+    major_faults: u64,
+    thread_count: usize,
+    nice: i32,
+    priority: i32,
+    tty_nr: u64,
+    tty: Option<String>,
+    wait_channel: Option<String>,
+    process_group_id: Option<Pid>,
+    session_id: Option<Pid>,
     pub(crate) status: ProcessStatus,
     /// Tasks run by this process.
     pub tasks: HashMap<Pid, Process>,
+    // The main thread isn't part of `tasks` since it has the same ID as the process.
+    main_thread: Option<Box<Process>>,
     pub(crate) stat_file: Option<FileCounter>,
     old_read_bytes: u64,
     old_written_bytes: u64,
@@ -106,6 +135,7 @@ impl Process {
     pub(crate) fn new(pid: Pid) -> Process {
         Process {
             name: String::with_capacity(20),
+            thread_name: String::new(),
             pid,
             parent: None,
             cmd: Vec::with_capacity(2),
@@ -116,6 +146,8 @@ impl Process {
             memory: 0,
             virtual_memory: 0,
             cpu_usage: 0.,
+            cpu_time: 0,
+            iowait_time: 0,
             utime: 0,
             stime: 0,
             old_utime: 0,
@@ -125,13 +157,30 @@ impl Process {
             start_time: 0,
             run_time: 0,
             user_id: None,
+            real_user_id: None,
             group_id: None,
+            real_group_id: None,
+            tracer_pid: None,
+            open_files: None,
+            network_usage: None,
+            is_64bit: None,
+            minor_faults: 0,
+            major_faults: 0,
+            thread_count: 0,
+            nice: 0,
+            priority: 0,
+            tty_nr: 0,
+            tty: None,
+            wait_channel: None,
+            process_group_id: None,
+            session_id: None,
             status: ProcessStatus::Unknown(0),
             tasks: if pid.0 == 0 {
                 HashMap::with_capacity(1000)
             } else {
                 HashMap::new()
             },
+            main_thread: None,
             stat_file: None,
             old_read_bytes: 0,
             old_written_bytes: 0,
@@ -139,6 +188,29 @@ impl Process {
             written_bytes: 0,
         }
     }
+
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::ProcessSnapshot) -> Process {
+        let mut p = Process::new(snapshot.pid);
+        p.parent = snapshot.parent;
+        p.name = snapshot.name.clone();
+        p.cmd = snapshot.cmd.clone();
+        p.exe = snapshot.exe.clone();
+        p.memory = snapshot.memory;
+        p.virtual_memory = snapshot.virtual_memory;
+        p.cpu_usage = snapshot.cpu_usage;
+        p.status = snapshot.status;
+        p.start_time = snapshot.start_time;
+        p.run_time = snapshot.run_time;
+        p.read_bytes = snapshot.disk_usage.total_read_bytes;
+        p.old_read_bytes = p.read_bytes.saturating_sub(snapshot.disk_usage.read_bytes);
+        p.written_bytes = snapshot.disk_usage.total_written_bytes;
+        p.old_written_bytes = p
+            .written_bytes
+            .saturating_sub(snapshot.disk_usage.written_bytes);
+        p.user_id = snapshot.user_id.clone();
+        p
+    }
 }
 
 impl ProcessExt for Process {
@@ -155,6 +227,10 @@ impl ProcessExt for Process {
         &self.cmd
     }
 
+    fn is_cmd_truncated(&self) -> bool {
+        false
+    }
+
     fn exe(&self) -> &Path {
         self.exe.as_path()
     }
@@ -203,6 +279,18 @@ impl ProcessExt for Process {
         self.cpu_usage
     }
 
+    fn cpu_time(&self) -> u64 {
+        self.cpu_time
+    }
+
+    fn iowait_time(&self) -> Option<u64> {
+        Some(self.iowait_time)
+    }
+
+    fn gpu_usage(&self) -> Option<f32> {
+        None
+    }
+
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage {
             written_bytes: self.written_bytes.saturating_sub(self.old_written_bytes),
@@ -216,26 +304,162 @@ impl ProcessExt for Process {
         self.user_id.as_ref()
     }
 
+    fn real_user_id(&self) -> Option<&Uid> {
+        self.real_user_id.as_ref()
+    }
+
     fn group_id(&self) -> Option<Gid> {
         self.group_id
     }
 
-    fn wait(&self) {
+    fn real_group_id(&self) -> Option<Gid> {
+        self.real_group_id
+    }
+
+    fn is_being_traced(&self) -> Option<bool> {
+        self.tracer_pid.map(|pid| pid.0 != 0)
+    }
+
+    fn tracer_pid(&self) -> Option<Pid> {
+        self.tracer_pid.filter(|pid| pid.0 != 0)
+    }
+
+    fn open_files(&self) -> Option<usize> {
+        self.open_files
+    }
+
+    fn network_usage(&self) -> Option<(u64, u64)> {
+        self.network_usage
+    }
+
+    fn is_64bit(&self) -> Option<bool> {
+        self.is_64bit
+    }
+
+    fn minor_faults(&self) -> u64 {
+        self.minor_faults
+    }
+
+    fn major_faults(&self) -> u64 {
+        self.major_faults
+    }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn threads(&self) -> Vec<ThreadInfo> {
+        // The main thread is only retrieved when the threads refresh is enabled.
+        let main_thread = match self.main_thread {
+            Some(ref main_thread) => main_thread,
+            None => return Vec::new(),
+        };
+        let mut threads = self
+            .tasks
+            .values()
+            .map(|task| ThreadInfo {
+                tid: task.pid.0 as _,
+                name: task.thread_name.clone(),
+                cpu_usage: task.cpu_usage,
+                status: task.status,
+            })
+            .collect::<Vec<_>>();
+        threads.push(ThreadInfo {
+            tid: main_thread.pid.0 as _,
+            name: main_thread.thread_name.clone(),
+            cpu_usage: main_thread.cpu_usage,
+            status: main_thread.status,
+        });
+        threads.sort_unstable_by_key(|thread| thread.tid);
+        threads
+    }
+
+    fn nice(&self) -> i32 {
+        self.nice
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn set_nice(&self, value: i32) -> bool {
+        let value = value.clamp(-20, 19);
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, self.pid.0 as _, value) == 0 }
+    }
+
+    fn tty(&self) -> Option<&str> {
+        self.tty.as_deref()
+    }
+
+    fn wait_channel(&self) -> Option<&str> {
+        self.wait_channel.as_deref()
+    }
+
+    fn process_group_id(&self) -> Option<Pid> {
+        self.process_group_id
+    }
+
+    fn session_id(&self) -> Option<Pid> {
+        self.session_id
+    }
+
+    fn wait_status(&self) -> Option<i32> {
         let mut status = 0;
         // attempt waiting
         unsafe {
             if libc::waitpid(self.pid.0, &mut status, 0) < 0 {
-                // attempt failed (non-child process) so loop until process ends
-                let duration = std::time::Duration::from_millis(10);
-                while kill(self.pid.0, 0) == 0 {
-                    std::thread::sleep(duration);
+                // attempt failed (non-child process) so wait until process ends
+                if !wait_with_pidfd(self.pid) {
+                    let duration = std::time::Duration::from_millis(10);
+                    while kill(self.pid.0, 0) == 0 {
+                        std::thread::sleep(duration);
+                    }
                 }
+                // The exit code of a non-child process cannot be retrieved.
+                None
+            } else if libc::WIFEXITED(status) {
+                Some(libc::WEXITSTATUS(status))
+            } else {
+                None
             }
         }
     }
 }
 
+// Blocks until the process exits using a pidfd. Returns `false` if pidfds aren't supported.
+#[cfg(target_os = "linux")]
+unsafe fn wait_with_pidfd(pid: Pid) -> bool {
+    let fd = libc::syscall(libc::SYS_pidfd_open, pid.0, 0);
+    if fd < 0 {
+        return false;
+    }
+    let mut poll_fd = libc::pollfd {
+        fd: fd as _,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let ret = loop {
+        let ret = libc::poll(&mut poll_fd, 1, -1);
+        if ret >= 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+            break ret;
+        }
+    };
+    libc::close(fd as _);
+    ret > 0
+}
+
+#[cfg(not(target_os = "linux"))]
+unsafe fn wait_with_pidfd(_pid: Pid) -> bool {
+    false
+}
+
 pub(crate) fn compute_cpu_usage(p: &mut Process, total_time: f32, max_value: f32) {
+    for task in p.tasks.values_mut() {
+        compute_cpu_usage(task, total_time, max_value);
+    }
+    if let Some(ref mut main_thread) = p.main_thread {
+        compute_cpu_usage(main_thread, total_time, max_value);
+    }
     // First time updating the values without reference, wait for a second cycle to update cpu_usage
     if p.old_utime == 0 && p.old_stime == 0 {
         return;
@@ -310,9 +534,9 @@ fn compute_start_time_without_boot_time(parts: &[&str], info: &SystemInfo) -> u6
     u64::from_str(parts[21]).unwrap_or(0) / info.clock_cycle
 }
 
-fn _get_stat_data(path: &Path, stat_file: &mut Option<FileCounter>) -> Result<String, ()> {
-    let mut file = File::open(path.join("stat")).map_err(|_| ())?;
-    let data = get_all_data_from_file(&mut file, 1024).map_err(|_| ())?;
+fn _get_stat_data(path: &Path, stat_file: &mut Option<FileCounter>) -> Result<String, Error> {
+    let mut file = File::open(path.join("stat"))?;
+    let data = get_all_data_from_file(&mut file, 1024)?;
     *stat_file = FileCounter::new(file);
     Ok(data)
 }
@@ -326,13 +550,150 @@ fn get_status(p: &mut Process, part: &str) {
         .unwrap_or_else(|| ProcessStatus::Unknown(0));
 }
 
-fn refresh_user_group_ids<P: PathPush>(p: &mut Process, path: &mut P) {
-    if let Some((user_id, group_id)) = get_uid_and_gid(path.join("status")) {
-        p.user_id = Some(Uid(user_id));
-        p.group_id = Some(Gid(group_id));
+#[inline(always)]
+fn get_priority_and_nice(p: &mut Process, parts: &[&str]) {
+    p.priority = i32::from_str(parts[17]).unwrap_or(0);
+    p.nice = i32::from_str(parts[18]).unwrap_or(0);
+}
+
+#[inline(always)]
+fn get_group_and_session(p: &mut Process, parts: &[&str]) {
+    p.process_group_id = Pid::from_str(parts[4]).ok();
+    p.session_id = Pid::from_str(parts[5]).ok();
+}
+
+// Converts the `tty_nr` field of `/proc/[pid]/stat` into the terminal name, relative to `/dev`.
+fn get_tty_name(tty_nr: u64) -> Option<String> {
+    if tty_nr == 0 {
+        // The process has no controlling terminal.
+        return None;
+    }
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    match major {
+        // Unix98 pseudo-terminals.
+        136..=143 => return Some(format!("pts/{}", (major - 136) * 256 + minor)),
+        4 if minor < 64 => return Some(format!("tty{}", minor)),
+        4 if minor < 256 => return Some(format!("ttyS{}", minor - 64)),
+        _ => {}
+    }
+    // Otherwise, we look for a character device with the same number in `/dev`.
+    fs::read_dir("/dev")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .find(|entry| {
+            entry
+                .metadata()
+                .map(|m| m.file_type().is_char_device() && m.rdev() == tty_nr)
+                .unwrap_or(false)
+        })
+        .and_then(|entry| entry.file_name().into_string().ok())
+}
+
+fn refresh_tty(p: &mut Process, parts: &[&str]) {
+    let tty_nr = u64::from_str(parts[6]).unwrap_or(0);
+    // Resolving the name can be costly, so it's only done when the terminal changes.
+    if tty_nr != p.tty_nr {
+        p.tty_nr = tty_nr;
+        p.tty = get_tty_name(tty_nr);
     }
 }
 
+// Refreshes the fields coming from `/proc/[pid]/status`, which is read only once for all of them.
+fn refresh_status<P: PathPush>(p: &mut Process, path: &mut P, user: bool, tracer: bool) {
+    if !user && !tracer {
+        return;
+    }
+    let status_path = path.join("status");
+    let status = get_all_data(status_path, 16_385)
+        .map(|data| parse_status(&data))
+        .unwrap_or_default();
+    if user {
+        // The owner of the file is the effective u/gid of the process.
+        let effective_ids =
+            get_file_owner(status_path).or_else(|| Some((status.user_ids?.1, status.group_ids?.1)));
+        if let Some((user_id, group_id)) = effective_ids {
+            p.user_id = Some(Uid(user_id));
+            p.group_id = Some(Gid(group_id));
+        }
+        if let (Some((real_user_id, _)), Some((real_group_id, _))) =
+            (status.user_ids, status.group_ids)
+        {
+            p.real_user_id = Some(Uid(real_user_id));
+            p.real_group_id = Some(Gid(real_group_id));
+        }
+    }
+    if tracer {
+        p.tracer_pid = status.tracer_pid;
+    }
+}
+
+// `wchan` contains "0" if the process is running.
+fn parse_wait_channel(data: &str) -> Option<String> {
+    match data.trim() {
+        "" | "0" => None,
+        wait_channel => Some(wait_channel.to_owned()),
+    }
+}
+
+fn refresh_wait_channel(p: &mut Process, path: &Path) {
+    p.wait_channel = get_all_data(path.join("wchan"), 256)
+        .ok()
+        .and_then(|data| parse_wait_channel(&data));
+}
+
+fn refresh_open_files(p: &mut Process, path: &Path) {
+    p.open_files = fs::read_dir(path.join("fd"))
+        .ok()
+        .map(|entries| entries.count());
+}
+
+// The network counters are only meaningful if the process has its own network namespace,
+// otherwise they are the ones of the whole namespace shared with the current process.
+fn get_network_usage(path: &Path) -> Option<(u64, u64)> {
+    let own_namespace = path.parent()?.join("self/ns/net").read_link().ok()?;
+    if path.join("ns/net").read_link().ok()? == own_namespace {
+        return None;
+    }
+    let data = get_all_data(path.join("net/dev"), 16_385).ok()?;
+    Some(parse_net_dev(&data))
+}
+
+fn refresh_network_usage(p: &mut Process, path: &Path) {
+    p.network_usage = get_network_usage(path);
+}
+
+/// Returns `true` if one of the file descriptors of the process at `path` is one of the
+/// `sockets` (identified by their inode).
+pub(crate) fn has_socket(path: &Path, sockets: &[u64]) -> bool {
+    let fds = match fs::read_dir(path.join("fd")) {
+        Ok(fds) => fds,
+        Err(_) => return false,
+    };
+    fds.filter_map(|fd| fs::read_link(fd.ok()?.path()).ok())
+        .filter_map(|link| parse_socket_inode(link.to_str()?))
+        .any(|inode| sockets.contains(&inode))
+}
+
+// Returns `true` if the header is the one of a 64-bit ELF file, `false` if it's the one of a 32-bit
+// one and `None` if it's not an ELF file.
+fn parse_elf_class(header: &[u8]) -> Option<bool> {
+    // The 5th byte of the ELF identification is the class of the file.
+    match header {
+        [0x7f, b'E', b'L', b'F', 1, ..] => Some(false),
+        [0x7f, b'E', b'L', b'F', 2, ..] => Some(true),
+        _ => None,
+    }
+}
+
+fn get_is_64bit(path: &Path) -> Option<bool> {
+    let mut header = [0; 5];
+    File::open(path.join("exe"))
+        .and_then(|mut f| f.read_exact(&mut header))
+        .ok()?;
+    parse_elf_class(&header)
+}
+
 fn retrieve_all_new_process_info(
     pid: Pid,
     proc_list: &Process,
@@ -345,8 +706,9 @@ fn retrieve_all_new_process_info(
     let mut p = Process::new(pid);
     let mut tmp = PathHandler::new(path);
     let name = parts[1];
+    let is_task = proc_list.pid.0 != 0;
 
-    p.parent = if proc_list.pid.0 != 0 {
+    p.parent = if is_task {
         Some(proc_list.pid)
     } else {
         match Pid::from_str(parts[3]) {
@@ -361,20 +723,33 @@ fn retrieve_all_new_process_info(
         .saturating_add(info.boot_time);
 
     get_status(&mut p, parts[2]);
+    get_priority_and_nice(&mut p, parts);
+    get_group_and_session(&mut p, parts);
+    refresh_tty(&mut p, parts);
 
-    if refresh_kind.user() {
-        refresh_user_group_ids(&mut p, &mut tmp);
-    }
+    // The tracer is only retrieved for processes.
+    refresh_status(
+        &mut p,
+        &mut tmp,
+        refresh_kind.user(),
+        !is_task && refresh_kind.tracer(),
+    );
 
-    if proc_list.pid.0 != 0 {
+    if is_task {
         // If we're getting information for a child, no need to get those info since we
         // already have them...
         p.cmd = proc_list.cmd.clone();
         p.name = proc_list.name.clone();
+        p.thread_name = name.into();
         p.environ = proc_list.environ.clone();
         p.exe = proc_list.exe.clone();
         p.cwd = proc_list.cwd.clone();
         p.root = proc_list.root.clone();
+        p.is_64bit = proc_list.is_64bit;
+        // All the tasks share the file descriptors table and the network namespace of the
+        // process.
+        p.open_files = proc_list.open_files;
+        p.network_usage = proc_list.network_usage;
     } else {
         p.name = name.into();
 
@@ -389,10 +764,24 @@ fn retrieve_all_new_process_info(
             }
         }
 
-        p.cmd = copy_from_file(tmp.join("cmdline"));
-        p.environ = copy_from_file(tmp.join("environ"));
+        if refresh_kind.cmd() {
+            p.cmd = copy_from_file(tmp.join("cmdline"));
+            p.environ = copy_from_file(tmp.join("environ"));
+        }
         p.cwd = realpath(tmp.join("cwd"));
         p.root = realpath(tmp.join("root"));
+        if refresh_kind.is_64bit() {
+            p.is_64bit = get_is_64bit(path);
+        }
+        if refresh_kind.open_files() {
+            refresh_open_files(&mut p, path);
+        }
+        if refresh_kind.network_usage() {
+            refresh_network_usage(&mut p, path);
+        }
+        if refresh_kind.wait_channel() {
+            refresh_wait_channel(&mut p, path);
+        }
     }
 
     update_time_and_memory(
@@ -418,14 +807,19 @@ pub(crate) fn _get_process_data(
     uptime: u64,
     info: &SystemInfo,
     refresh_kind: ProcessRefreshKind,
-) -> Result<(Option<Process>, Pid), ()> {
+) -> Result<(Option<Process>, Pid), Error> {
     let pid = match path.file_name().and_then(|x| x.to_str()).map(Pid::from_str) {
         Some(Ok(nb)) if nb != pid => nb,
-        _ => return Err(()),
+        _ => return Err(Error::NotFound),
     };
 
     let parent_memory = proc_list.memory;
     let parent_virtual_memory = proc_list.virtual_memory;
+    let parent_open_files = proc_list.open_files;
+    let parent_network_usage = proc_list.network_usage;
+    let is_task = proc_list.pid.0 != 0;
+    // Tasks of a process are never filtered out.
+    let skip_kernel_threads = !is_task && !refresh_kind.kernel_threads();
 
     let data;
     let parts = if let Some(ref mut entry) = proc_list.tasks.get_mut(&pid) {
@@ -445,7 +839,11 @@ pub(crate) fn _get_process_data(
         } else {
             _get_stat_data(path, &mut entry.stat_file)?
         };
-        let parts = parse_stat_file(&data).ok_or(())?;
+        let parts = parse_stat_line(&data).ok_or(Error::Parse)?;
+        if skip_kernel_threads && is_kernel_thread(&parts) {
+            // Since it isn't updated, it'll be removed from the list.
+            return Ok((None, pid));
+        }
         let start_time_without_boot_time = compute_start_time_without_boot_time(&parts, info);
 
         // It's possible that a new process took this same PID when the "original one" terminated.
@@ -453,6 +851,33 @@ pub(crate) fn _get_process_data(
         // need to get all its information, hence why we check it here.
         if start_time_without_boot_time == entry.start_time_without_boot_time {
             get_status(entry, parts[2]);
+            // Threads can be renamed at any time.
+            if is_task && entry.thread_name != parts[1] {
+                entry.thread_name = parts[1].into();
+            }
+            get_priority_and_nice(entry, &parts);
+            get_group_and_session(entry, &parts);
+            refresh_tty(entry, &parts);
+            if refresh_kind.open_files() {
+                if is_task {
+                    // All the tasks share the file descriptors table of the process.
+                    entry.open_files = parent_open_files;
+                } else {
+                    refresh_open_files(entry, path);
+                }
+            }
+            // Like the tracer, the wait channel is only retrieved for processes.
+            if !is_task && refresh_kind.wait_channel() {
+                refresh_wait_channel(entry, path);
+            }
+            if refresh_kind.network_usage() {
+                if is_task {
+                    // All the tasks share the network namespace of the process.
+                    entry.network_usage = parent_network_usage;
+                } else {
+                    refresh_network_usage(entry, path);
+                }
+            }
             update_time_and_memory(
                 path,
                 entry,
@@ -466,8 +891,19 @@ pub(crate) fn _get_process_data(
             if refresh_kind.disk_usage() {
                 update_process_disk_activity(entry, path);
             }
-            if refresh_kind.user() && entry.user_id.is_none() {
-                refresh_user_group_ids(entry, &mut PathBuf::from(path));
+            if refresh_kind.is_64bit() && entry.is_64bit.is_none() {
+                entry.is_64bit = get_is_64bit(path);
+            }
+            refresh_status(
+                entry,
+                &mut PathBuf::from(path),
+                refresh_kind.user() && entry.user_id.is_none(),
+                !is_task && refresh_kind.tracer(),
+            );
+            if !still_exists(path) {
+                // Since it isn't updated, it'll be removed from the list.
+                entry.updated = false;
+                return Err(Error::NotFound);
             }
             return Ok((None, pid));
         }
@@ -475,16 +911,31 @@ pub(crate) fn _get_process_data(
     } else {
         let mut stat_file = None;
         let data = _get_stat_data(path, &mut stat_file)?;
-        let parts = parse_stat_file(&data).ok_or(())?;
+        let parts = parse_stat_line(&data).ok_or(Error::Parse)?;
+        if skip_kernel_threads && is_kernel_thread(&parts) {
+            return Ok((None, pid));
+        }
 
         let mut p =
             retrieve_all_new_process_info(pid, proc_list, &parts, path, info, refresh_kind, uptime);
+        if !still_exists(path) {
+            return Err(Error::NotFound);
+        }
         p.stat_file = stat_file;
         return Ok((Some(p), pid));
     };
 
     // If we're here, it means that the PID still exists but it's a different process.
     let p = retrieve_all_new_process_info(pid, proc_list, &parts, path, info, refresh_kind, uptime);
+    if !still_exists(path) {
+        // The old entry is outdated and the new one is incomplete so neither is kept. The entry
+        // isn't removed here since other entries might be accessed at the same time (when the
+        // `multithread` feature is enabled): it'll be removed from the list since it isn't updated.
+        if let Some(entry) = proc_list.tasks.get_mut(&pid) {
+            entry.updated = false;
+        }
+        return Err(Error::NotFound);
+    }
     match proc_list.tasks.get_mut(&pid) {
         Some(ref mut entry) => **entry = p,
         // If it ever enters this case, it means that the process was removed from the HashMap
@@ -495,6 +946,12 @@ pub(crate) fn _get_process_data(
     Ok((None, pid))
 }
 
+/// Returns `false` if the process exited while its information was being read. In this case,
+/// some of its fields were likely not retrieved so the process must be discarded.
+fn still_exists(path: &Path) -> bool {
+    path.join("stat").exists()
+}
+
 #[allow(clippy::too_many_arguments)]
 fn update_time_and_memory(
     path: &Path,
@@ -510,7 +967,7 @@ fn update_time_and_memory(
         // rss
         entry.memory = u64::from_str(parts[23])
             .unwrap_or(0)
-            .saturating_mul(info.page_size_kb);
+            .saturating_mul(info.page_size);
         if entry.memory >= parent_memory {
             entry.memory -= parent_memory;
         }
@@ -520,20 +977,72 @@ fn update_time_and_memory(
         if entry.virtual_memory >= parent_virtual_memory {
             entry.virtual_memory -= parent_virtual_memory;
         }
+        entry.minor_faults = u64::from_str(parts[9]).unwrap_or(0);
+        entry.major_faults = u64::from_str(parts[11]).unwrap_or(0);
+        entry.thread_count = usize::from_str(parts[19]).unwrap_or(0);
         set_time(
             entry,
             u64::from_str(parts[13]).unwrap_or(0),
             u64::from_str(parts[14]).unwrap_or(0),
         );
+        // `utime` and `stime` are expressed in clock ticks.
+        entry.cpu_time = entry
+            .utime
+            .saturating_add(entry.stime)
+            .saturating_mul(1_000)
+            / info.clock_cycle;
+        // `delayacct_blkio_ticks` is expressed in clock ticks too.
+        entry.iowait_time = parts
+            .get(41)
+            .and_then(|ticks| u64::from_str(ticks).ok())
+            .unwrap_or(0)
+            .saturating_mul(1_000)
+            / info.clock_cycle;
         entry.run_time = uptime.saturating_sub(entry.start_time_without_boot_time);
     }
-    refresh_procs(
+    let tasks_path = path.join("task");
+    // Tasks don't have a `task` folder.
+    if !refresh_procs(
         entry,
-        &path.join("task"),
+        &tasks_path,
         entry.pid,
         uptime,
         info,
         refresh_kind,
+        None,
+    ) {
+        return;
+    }
+    if refresh_kind.threads() {
+        refresh_main_thread(entry, &Path::join(&tasks_path, entry.pid.to_string()));
+    } else {
+        entry.main_thread = None;
+    }
+}
+
+/// Refreshes the main thread of the process from `task/[pid]/stat`, like the other tasks.
+fn refresh_main_thread(p: &mut Process, path: &Path) {
+    let pid = p.pid;
+    let main_thread = p
+        .main_thread
+        .get_or_insert_with(|| Box::new(Process::new(pid)));
+    // Unlike the processes, the file isn't kept open to not use more file descriptors.
+    let data = get_all_data(path.join("stat"), 1024);
+    let parts = match data.as_deref().ok().and_then(parse_stat_line) {
+        Some(parts) => parts,
+        None => {
+            p.main_thread = None;
+            return;
+        }
+    };
+    if main_thread.thread_name != parts[1] {
+        main_thread.thread_name = parts[1].into();
+    }
+    get_status(main_thread, parts[2]);
+    set_time(
+        main_thread,
+        u64::from_str(parts[13]).unwrap_or(0),
+        u64::from_str(parts[14]).unwrap_or(0),
     );
 }
 
@@ -544,6 +1053,7 @@ pub(crate) fn refresh_procs(
     uptime: u64,
     info: &SystemInfo,
     refresh_kind: ProcessRefreshKind,
+    mut errors: Option<&mut HashMap<Pid, Error>>,
 ) -> bool {
     let d = match fs::read_dir(path) {
         Ok(d) => d,
@@ -567,20 +1077,38 @@ pub(crate) fn refresh_procs(
         #[cfg(feature = "multithread")]
         use rayon::iter::ParallelIterator;
 
-        into_iter(folders)
+        let results = into_iter(folders)
             .filter_map(|e| {
-                let (p, _) = _get_process_data(
+                match _get_process_data(
                     e.as_path(),
                     proc_list.get(),
                     pid,
                     uptime,
                     info,
                     refresh_kind,
-                )
-                .ok()?;
-                p
+                ) {
+                    Ok((p, _)) => p.map(Ok),
+                    Err(err) => Some(Err((e, err))),
+                }
             })
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>();
+        let mut new_processes = Vec::with_capacity(results.len());
+        for result in results {
+            match result {
+                Ok(p) => new_processes.push(p),
+                Err((path, err)) => {
+                    // Folders which aren't processes (like `/proc/sys`) are ignored.
+                    let pid = path
+                        .file_name()
+                        .and_then(|x| x.to_str())
+                        .and_then(|x| Pid::from_str(x).ok());
+                    if let (Some(errors), Some(pid)) = (errors.as_mut(), pid) {
+                        errors.insert(pid, err);
+                    }
+                }
+            }
+        }
+        new_processes
     } else {
         let mut updated_pids = Vec::with_capacity(folders.len());
         let new_tasks = folders
@@ -594,9 +1122,7 @@ pub(crate) fn refresh_procs(
             })
             .collect::<Vec<_>>();
         // Sub-tasks are not cleaned up outside so we do it here directly.
-        proc_list
-            .tasks
-            .retain(|&pid, _| updated_pids.iter().any(|&x| x == pid));
+        proc_list.tasks.retain(|pid, _| updated_pids.contains(pid));
         new_tasks
     }
     .into_iter()
@@ -606,6 +1132,34 @@ pub(crate) fn refresh_procs(
     true
 }
 
+// Same as `refresh_procs` for the processes in `path` but instead of being stored, each process
+// is given to `f` once its information was retrieved.
+pub(crate) fn stream_procs<F: FnMut(&Process)>(
+    path: &Path,
+    uptime: u64,
+    info: &SystemInfo,
+    refresh_kind: ProcessRefreshKind,
+    mut f: F,
+) {
+    let d = match fs::read_dir(path) {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+    // Since its list of tasks is empty, all processes are considered as new ones.
+    let mut proc_list = Process::new(Pid(0));
+    for entry in d.filter_map(|entry| entry.ok()) {
+        let entry = entry.path();
+        if !entry.is_dir() {
+            continue;
+        }
+        if let Ok((Some(p), _)) =
+            _get_process_data(&entry, &mut proc_list, Pid(0), uptime, info, refresh_kind)
+        {
+            f(&p);
+        }
+    }
+}
+
 fn copy_from_file(entry: &Path) -> Vec<String> {
     match File::open(entry) {
         Ok(mut f) => {
@@ -636,7 +1190,7 @@ fn copy_from_file(entry: &Path) -> Vec<String> {
     }
 }
 
-fn get_uid_and_gid(file_path: &Path) -> Option<(uid_t, gid_t)> {
+fn get_file_owner(file_path: &Path) -> Option<(uid_t, gid_t)> {
     use std::os::unix::ffi::OsStrExt;
 
     unsafe {
@@ -650,62 +1204,90 @@ fn get_uid_and_gid(file_path: &Path) -> Option<(uid_t, gid_t)> {
             return Some((sstat.st_uid, sstat.st_gid));
         }
     }
+    None
+}
 
-    let status_data = get_all_data(file_path, 16_385).ok()?;
+// The fields retrieved from `/proc/[pid]/status`.
+#[derive(Default)]
+struct Status {
+    // The real and effective IDs, in this order.
+    user_ids: Option<(uid_t, uid_t)>,
+    group_ids: Option<(gid_t, gid_t)>,
+    tracer_pid: Option<Pid>,
+}
 
-    // We're only interested in the lines starting with Uid: and Gid:
-    // here. From these lines, we're looking at the second entry to get
-    // the effective u/gid.
+fn parse_status(status_data: &str) -> Status {
+    // The `Uid:` and `Gid:` lines contain the real, effective, saved set and filesystem IDs, in
+    // this order.
+    fn parse_ids(value: &str) -> Option<(u32, u32)> {
+        let mut ids = value.split_whitespace().map(|id| id.parse().ok());
+        Some((ids.next()??, ids.next()??))
+    }
 
-    let f = |h: &str, n: &str| -> Option<uid_t> {
-        if h.starts_with(n) {
-            h.split_whitespace().nth(2).unwrap_or("0").parse().ok()
-        } else {
-            None
-        }
-    };
-    let mut uid = None;
-    let mut gid = None;
-    for line in status_data.lines() {
-        if let Some(u) = f(line, "Uid:") {
-            assert!(uid.is_none());
-            uid = Some(u);
-        } else if let Some(g) = f(line, "Gid:") {
-            assert!(gid.is_none());
-            gid = Some(g);
-        } else {
-            continue;
-        }
-        if uid.is_some() && gid.is_some() {
-            break;
+    let mut status = Status::default();
+    for (key, value) in parse_status_kv(status_data) {
+        match key {
+            "TracerPid" => status.tracer_pid = Pid::from_str(value).ok(),
+            "Uid" => status.user_ids = parse_ids(value),
+            // `Gid:` comes after the two other ones.
+            "Gid" => {
+                status.group_ids = parse_ids(value);
+                break;
+            }
+            _ => {}
         }
     }
-    match (uid, gid) {
-        (Some(u), Some(g)) => Some((u, g)),
-        _ => None,
-    }
+    status
 }
 
-fn parse_stat_file(data: &str) -> Option<Vec<&str>> {
-    // The stat file is "interesting" to parse, because spaces cannot
-    // be used as delimiters. The second field stores the command name
-    // surrounded by parentheses. Unfortunately, whitespace and
-    // parentheses are legal parts of the command, so parsing has to
-    // proceed like this: The first field is delimited by the first
-    // whitespace, the second field is everything until the last ')'
-    // in the entire string. All other fields are delimited by
-    // whitespace.
-
-    let mut parts = Vec::with_capacity(52);
-    let mut data_it = data.splitn(2, ' ');
-    parts.push(data_it.next()?);
-    let mut data_it = data_it.next()?.rsplitn(2, ')');
-    let data = data_it.next()?;
-    parts.push(data_it.next()?);
-    parts.extend(data.split_whitespace());
-    // Remove command name '('
-    if let Some(name) = parts[1].strip_prefix('(') {
-        parts[1] = name;
-    }
-    Some(parts)
+#[cfg(test)]
+mod test {
+    use super::{get_tty_name, parse_elf_class, parse_status, parse_wait_channel};
+    use crate::Pid;
+
+    #[test]
+    fn check_get_tty_name() {
+        assert_eq!(get_tty_name(0), None);
+        // major 136, minor 3
+        assert_eq!(get_tty_name(34_819).as_deref(), Some("pts/3"));
+        // major 137, minor 1
+        assert_eq!(get_tty_name(35_073).as_deref(), Some("pts/257"));
+        // major 4, minor 1
+        assert_eq!(get_tty_name(1_025).as_deref(), Some("tty1"));
+        // major 4, minor 64
+        assert_eq!(get_tty_name(1_088).as_deref(), Some("ttyS0"));
+    }
+
+    #[test]
+    fn check_parse_wait_channel() {
+        assert_eq!(parse_wait_channel("0"), None);
+        assert_eq!(parse_wait_channel(""), None);
+        assert_eq!(
+            parse_wait_channel("do_epoll_wait").as_deref(),
+            Some("do_epoll_wait")
+        );
+    }
+
+    #[test]
+    fn check_parse_elf_class() {
+        assert_eq!(parse_elf_class(b"\x7fELF\x01"), Some(false));
+        assert_eq!(parse_elf_class(b"\x7fELF\x02\x01\x01"), Some(true));
+        assert_eq!(parse_elf_class(b"\x7fELF\x00"), None);
+        assert_eq!(parse_elf_class(b"#!/bin/sh"), None);
+        assert_eq!(parse_elf_class(b""), None);
+    }
+
+    #[test]
+    fn check_parse_status() {
+        let status = "Name:\tsu\nTracerPid:\t12\nUid:\t1000\t0\t0\t0\nGid:\t1001\t0\t0\t0\n";
+        let status = parse_status(status);
+        assert_eq!(status.user_ids, Some((1000, 0)));
+        assert_eq!(status.group_ids, Some((1001, 0)));
+        assert_eq!(status.tracer_pid, Some(Pid(12)));
+
+        let status = parse_status("Name:\tsu\nUid:\t1000\n");
+        assert_eq!(status.user_ids, None);
+        assert_eq!(status.group_ids, None);
+        assert_eq!(status.tracer_pid, None);
+    }
 }
@@ -0,0 +1,457 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+// Helpers to parse the files provided by the `/proc` filesystem.
+
+use crate::{Pressure, PressureAvg, SwapDevice};
+
+/// Splits the content of a `/proc/[pid]/stat` file into its fields.
+///
+/// The second field (`comm`) is returned without its surrounding parentheses. Fields are
+/// indexed as described in `proc(5)` minus one (so `state` is at index `2`).
+pub(crate) fn parse_stat_line(data: &str) -> Option<Vec<&str>> {
+    // The stat file is "interesting" to parse, because spaces cannot
+    // be used as delimiters. The second field stores the command name
+    // surrounded by parentheses. Unfortunately, whitespace and
+    // parentheses are legal parts of the command, so parsing has to
+    // proceed like this: The first field is delimited by the first
+    // whitespace, the second field is everything until the last ')'
+    // in the entire string. All other fields are delimited by
+    // whitespace.
+
+    let mut parts = Vec::with_capacity(52);
+    let mut data_it = data.splitn(2, ' ');
+    parts.push(data_it.next()?);
+    let mut data_it = data_it.next()?.rsplitn(2, ')');
+    let data = data_it.next()?;
+    parts.push(data_it.next()?);
+    parts.extend(data.split_whitespace());
+    // Remove command name '('
+    if let Some(name) = parts[1].strip_prefix('(') {
+        parts[1] = name;
+    }
+    Some(parts)
+}
+
+/// Returns `true` if the fields of a `/proc/[pid]/stat` file (as returned by
+/// [`parse_stat_line`]) belong to a kernel thread.
+///
+/// Kernel threads have the `PF_KTHREAD` bit set in their `flags` field, which is more reliable
+/// than looking for an empty `cmdline` (zombie processes have one too).
+pub(crate) fn is_kernel_thread(parts: &[&str]) -> bool {
+    const PF_KTHREAD: u64 = 0x0020_0000;
+
+    parts
+        .get(8)
+        .and_then(|flags| flags.parse::<u64>().ok())
+        .map(|flags| flags & PF_KTHREAD != 0)
+        .unwrap_or(false)
+}
+
+/// Returns an iterator over the `key: value` lines of a file like `/proc/[pid]/status`.
+///
+/// Values are trimmed and lines without a `:` separator are skipped.
+pub(crate) fn parse_status_kv(data: &str) -> impl Iterator<Item = (&str, &str)> {
+    data.lines().filter_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        Some((key.trim(), value.trim()))
+    })
+}
+
+/// Parses a value from `/proc/meminfo` (or from the `Vm*` fields of `/proc/[pid]/status`).
+///
+/// If the value has a `kB` suffix, it is converted into bytes.
+pub(crate) fn parse_kb_value(value: &str) -> Option<u64> {
+    let mut iter = value.split_whitespace();
+    let nb = iter.next()?.parse::<u64>().ok()?;
+    match iter.next() {
+        // The kernel says "kB" but actually means KiB.
+        Some("kB") => Some(nb.saturating_mul(1_024)),
+        Some(_) => None,
+        None => Some(nb),
+    }
+}
+
+/// Returns an iterator over the fields of a `/proc/meminfo` file. Values are in bytes, except
+/// for the fields without unit (like `HugePages_Total`) which are returned as is.
+///
+/// Lines with a value which cannot be parsed are skipped.
+pub(crate) fn parse_meminfo(data: &str) -> impl Iterator<Item = (&str, u64)> {
+    parse_status_kv(data).filter_map(|(key, value)| Some((key, parse_kb_value(value)?)))
+}
+
+/// Decodes the octal escape sequences (like `\040` for a space) used by the kernel for the paths
+/// in files like `/proc/mounts` or `/proc/swaps`.
+///
+/// The path is decoded in a single pass so that an escaped backslash (`\134`) followed by digits
+/// isn't decoded twice.
+pub(crate) fn unescape_path(path: &str) -> String {
+    fn parse_octal(digits: &[u8]) -> Option<u8> {
+        match *digits {
+            [a @ b'0'..=b'3', b @ b'0'..=b'7', c @ b'0'..=b'7'] => {
+                Some((a - b'0') * 64 + (b - b'0') * 8 + (c - b'0'))
+            }
+            _ => None,
+        }
+    }
+
+    let path = path.as_bytes();
+    let mut out = Vec::with_capacity(path.len());
+    let mut pos = 0;
+    while pos < path.len() {
+        if path[pos] == b'\\' {
+            if let Some(c) = path.get(pos + 1..pos + 4).and_then(parse_octal) {
+                out.push(c);
+                pos += 4;
+                continue;
+            }
+        }
+        out.push(path[pos]);
+        pos += 1;
+    }
+    match String::from_utf8(out) {
+        Ok(path) => path,
+        Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+    }
+}
+
+/// Parses the content of a `/proc/swaps` file.
+///
+/// Lines which cannot be parsed are skipped.
+pub(crate) fn parse_swaps(data: &str) -> Vec<SwapDevice> {
+    // The first line is the header.
+    data.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let path = unescape_path(fields.next()?);
+            // The sizes are in KiB and come after the type.
+            let total = fields.nth(1)?.parse::<u64>().ok()?;
+            let used = fields.next()?.parse::<u64>().ok()?;
+            let priority = fields.next()?.parse().ok()?;
+            Some(SwapDevice {
+                path: path.into(),
+                total: total.saturating_mul(1_024),
+                used: Some(used.saturating_mul(1_024)),
+                priority: Some(priority),
+            })
+        })
+        .collect()
+}
+
+/// Returns the total number of received and transmitted bytes of all the interfaces listed in a
+/// `/proc/net/dev` file, except the loopback one.
+pub(crate) fn parse_net_dev(data: &str) -> (u64, u64) {
+    // The first two lines are the headers.
+    data.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (interface, values) = line.split_once(':')?;
+            if interface.trim() == "lo" {
+                return None;
+            }
+            let mut values = values.split_whitespace();
+            let received = values.next()?.parse::<u64>().ok()?;
+            // The transmitted bytes are after the 7 other received values.
+            let transmitted = values.nth(7)?.parse::<u64>().ok()?;
+            Some((received, transmitted))
+        })
+        .fold((0, 0), |(rx, tx), (received, transmitted)| {
+            (rx.saturating_add(received), tx.saturating_add(transmitted))
+        })
+}
+
+/// Returns the inodes of the sockets bound to the local `port` listed in a `/proc/net/tcp` or
+/// `/proc/net/udp` file (or their IPv6 equivalent).
+///
+/// If `listening_only` is `true`, only the sockets in the `LISTEN` state are returned.
+pub(crate) fn parse_net_sockets(
+    data: &str,
+    port: u16,
+    listening_only: bool,
+) -> impl Iterator<Item = u64> + '_ {
+    const TCP_LISTEN: u8 = 0x0A;
+
+    // The first line is the header.
+    data.lines().skip(1).filter_map(move |line| {
+        // Fields are: "sl local_address rem_address st [...] uid timeout inode".
+        let mut fields = line.split_whitespace();
+        let (_, local_port) = fields.nth(1)?.rsplit_once(':')?;
+        if u16::from_str_radix(local_port, 16).ok()? != port {
+            return None;
+        }
+        let state = u8::from_str_radix(fields.nth(1)?, 16).ok()?;
+        if listening_only && state != TCP_LISTEN {
+            return None;
+        }
+        fields.nth(5)?.parse().ok()
+    })
+}
+
+/// Returns the inode of the socket a `/proc/[pid]/fd/[fd]` link targets (`socket:[inode]`).
+pub(crate) fn parse_socket_inode(link: &str) -> Option<u64> {
+    link.strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Parses the content of a `/proc/pressure/[resource]` file.
+///
+/// Returns `None` if there is no `some` line. The `full` values are `0` if there is no `full`
+/// line.
+pub(crate) fn parse_pressure(data: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = PressureAvg::default();
+
+    // Lines look like: "some avg10=0.00 avg60=0.00 avg300=0.00 total=0".
+    for line in data.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut avg = PressureAvg::default();
+        for (key, value) in fields.filter_map(|field| field.split_once('=')) {
+            let value = match value.parse() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            match key {
+                "avg10" => avg.avg10 = value,
+                "avg60" => avg.avg60 = value,
+                "avg300" => avg.avg300 = value,
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => some = Some(avg),
+            Some("full") => full = avg,
+            _ => {}
+        }
+    }
+    Some(Pressure { some: some?, full })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        is_kernel_thread, parse_kb_value, parse_meminfo, parse_net_dev, parse_net_sockets,
+        parse_pressure, parse_socket_inode, parse_stat_line, parse_status_kv, parse_swaps,
+        unescape_path,
+    };
+    use crate::{Pressure, PressureAvg, SwapDevice};
+
+    const STAT: &str = "1234 (my (weird) process) S 1 1234 1234 0 -1 4194560 1024 0 0 0 \
+                        27 12 0 0 20 0 1 0 4242 12345678 321 18446744073709551615 1 1 0 0 0 0 \
+                        0 4096 1260 0 0 0 17 3 0 0 0 0 0 0 0 0 0 0 0 0 0\n";
+
+    const STATUS: &str = "Name:\tbash
+Umask:\t0022
+State:\tS (sleeping)
+Tgid:\t1234
+Ngid:\t0
+Pid:\t1234
+PPid:\t1
+TracerPid:\t0
+Uid:\t1000\t1000\t1000\t1000
+Gid:\t1000\t1000\t1000\t1000
+FDSize:\t256
+Groups:\t4 24 27 1000
+VmPeak:\t   12000 kB
+VmRSS:\t    5120 kB
+Threads:\t1
+voluntary_ctxt_switches:\t150
+nonvoluntary_ctxt_switches:\t3
+";
+
+    const MEMINFO: &str = "MemTotal:       16318540 kB
+MemFree:         9048088 kB
+MemAvailable:   12641844 kB
+Buffers:          238412 kB
+Cached:          3570388 kB
+SwapTotal:       2097148 kB
+SwapFree:        2097148 kB
+HugePages_Total:       0
+Hugepagesize:       2048 kB
+";
+
+    const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:    1000      10    0    0    0     0          0         0     1000      10    0    0    0     0       0          0
+  eth0:    2500      25    0    0    0     0          0         0      700       7    0    0    0     0       0          0
+  eth1:     500       5    0    0    0     0          0         0      300       3    0    0    0     0       0          0
+";
+
+    const NET_TCP: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 4343 1 0000000000000000 20 4 30 10 -1
+   2: 0100007F:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 4444 1 0000000000000000 100 0 0 10 0
+";
+
+    #[test]
+    fn check_parse_stat_line() {
+        let parts = parse_stat_line(STAT).expect("failed to parse stat");
+        assert_eq!(parts[0], "1234");
+        assert_eq!(parts[1], "my (weird) process");
+        assert_eq!(parts[2], "S");
+        assert_eq!(parts[3], "1");
+        assert_eq!(parts[13], "27");
+        assert_eq!(parts[14], "12");
+        assert_eq!(parts[21], "4242");
+        assert_eq!(parts[22], "12345678");
+        assert_eq!(parts[23], "321");
+
+        assert_eq!(parse_stat_line("1 (a) R").map(|p| p.len()), Some(3));
+        assert!(parse_stat_line("").is_none());
+        assert!(parse_stat_line("1").is_none());
+    }
+
+    #[test]
+    fn check_is_kernel_thread() {
+        let parts = parse_stat_line(STAT).expect("failed to parse stat");
+        assert!(!is_kernel_thread(&parts));
+
+        let kthreadd = "2 (kthreadd) S 0 0 0 0 -1 2129984 0 0 0 0 0 12 0 0 20 0 1 0 3 0 0 \
+                        18446744073709551615 0 0 0 0 0 0 0 2147483647 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0";
+        let parts = parse_stat_line(kthreadd).expect("failed to parse stat");
+        assert!(is_kernel_thread(&parts));
+
+        assert!(!is_kernel_thread(&["2", "kthreadd", "S"]));
+    }
+
+    #[test]
+    fn check_parse_status_kv() {
+        let kv = parse_status_kv(STATUS).collect::<Vec<_>>();
+        assert_eq!(kv.len(), 17);
+        assert_eq!(kv[0], ("Name", "bash"));
+        assert_eq!(kv[2], ("State", "S (sleeping)"));
+        assert_eq!(kv[8], ("Uid", "1000\t1000\t1000\t1000"));
+        assert_eq!(
+            parse_status_kv(STATUS).find(|(key, _)| *key == "TracerPid"),
+            Some(("TracerPid", "0"))
+        );
+        assert_eq!(parse_status_kv("no separator\n").count(), 0);
+    }
+
+    #[test]
+    fn check_parse_kb_value() {
+        assert_eq!(parse_kb_value("  12 kB"), Some(12 * 1_024));
+        assert_eq!(parse_kb_value("12"), Some(12));
+        assert_eq!(parse_kb_value("12 MB"), None);
+        assert_eq!(parse_kb_value("a kB"), None);
+        assert_eq!(parse_kb_value(""), None);
+    }
+
+    #[test]
+    fn check_parse_meminfo() {
+        let meminfo = parse_meminfo(MEMINFO).collect::<Vec<_>>();
+        assert_eq!(meminfo.len(), 9);
+        assert_eq!(meminfo[0], ("MemTotal", 16_318_540 * 1_024));
+        assert_eq!(meminfo[2], ("MemAvailable", 12_641_844 * 1_024));
+        assert_eq!(meminfo[7], ("HugePages_Total", 0));
+        assert_eq!(meminfo[8], ("Hugepagesize", 2_048 * 1_024));
+        // Missing fields are just not returned.
+        assert!(meminfo.iter().all(|(key, _)| *key != "Shmem"));
+    }
+
+    #[test]
+    fn check_parse_net_dev() {
+        assert_eq!(parse_net_dev(NET_DEV), (3_000, 1_000));
+        assert_eq!(parse_net_dev(""), (0, 0));
+    }
+
+    #[test]
+    fn check_parse_net_sockets() {
+        assert_eq!(
+            parse_net_sockets(NET_TCP, 8080, true).collect::<Vec<_>>(),
+            vec![4242]
+        );
+        assert_eq!(
+            parse_net_sockets(NET_TCP, 8080, false).collect::<Vec<_>>(),
+            vec![4242, 4343]
+        );
+        assert_eq!(
+            parse_net_sockets(NET_TCP, 22, true).collect::<Vec<_>>(),
+            vec![4444]
+        );
+        assert_eq!(parse_net_sockets(NET_TCP, 80, false).count(), 0);
+        assert_eq!(parse_net_sockets("", 8080, false).count(), 0);
+    }
+
+    #[test]
+    fn check_parse_socket_inode() {
+        assert_eq!(parse_socket_inode("socket:[4242]"), Some(4242));
+        assert_eq!(parse_socket_inode("pipe:[4242]"), None);
+        assert_eq!(parse_socket_inode("/dev/null"), None);
+        assert_eq!(parse_socket_inode("socket:[]"), None);
+    }
+
+    #[test]
+    fn check_parse_pressure() {
+        let data = "some avg10=1.50 avg60=0.75 avg300=0.10 total=123456\n\
+                    full avg10=0.50 avg60=0.25 avg300=0.00 total=4242\n";
+        assert_eq!(
+            parse_pressure(data),
+            Some(Pressure {
+                some: PressureAvg {
+                    avg10: 1.5,
+                    avg60: 0.75,
+                    avg300: 0.1,
+                },
+                full: PressureAvg {
+                    avg10: 0.5,
+                    avg60: 0.25,
+                    avg300: 0.,
+                },
+            })
+        );
+        // Before Linux 5.13, there is no "full" line for the CPU.
+        assert_eq!(
+            parse_pressure("some avg10=2.00 avg60=0.00 avg300=0.00 total=0\n")
+                .map(|pressure| (pressure.some.avg10, pressure.full)),
+            Some((2., PressureAvg::default()))
+        );
+        assert_eq!(
+            parse_pressure("full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n"),
+            None
+        );
+        assert_eq!(parse_pressure(""), None);
+    }
+
+    #[test]
+    fn check_unescape_path() {
+        assert_eq!(unescape_path("/mnt/my\\040disk"), "/mnt/my disk");
+        assert_eq!(unescape_path("/a\\134b\\011c"), "/a\\b\tc");
+        assert_eq!(unescape_path("/swapfile"), "/swapfile");
+        // An escaped backslash followed by digits must only be decoded once.
+        assert_eq!(unescape_path("/mnt/a\\134040b"), "/mnt/a\\040b");
+        assert_eq!(unescape_path("/mnt/a\\"), "/mnt/a\\");
+    }
+
+    #[test]
+    fn check_parse_swaps() {
+        let data = "\
+Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/sda2                               partition\t8388604\t\t1024\t\t-2
+/swap\\040file                              file\t\t1048576\t\t0\t\t10
+invalid line
+";
+        assert_eq!(
+            parse_swaps(data),
+            vec![
+                SwapDevice {
+                    path: "/dev/sda2".into(),
+                    total: 8_388_604 * 1_024,
+                    used: Some(1_024 * 1_024),
+                    priority: Some(-2),
+                },
+                SwapDevice {
+                    path: "/swap file".into(),
+                    total: 1_048_576 * 1_024,
+                    used: Some(0),
+                    priority: Some(10),
+                },
+            ]
+        );
+        assert!(parse_swaps("").is_empty());
+    }
+}
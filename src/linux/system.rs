@@ -1,26 +1,33 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use crate::sys::component::{self, Component};
+use crate::sys::battery::{get_battery, get_on_ac_power, POWER_SUPPLY_ROOT};
+use crate::sys::cgroup::{get_cgroup_limits, get_cgroup_stats, CGROUP_ROOT};
+use crate::sys::component::{self, Component, HWMON_ROOT};
 use crate::sys::cpu::*;
 use crate::sys::disk;
 use crate::sys::process::*;
+use crate::sys::procfs::{parse_meminfo, parse_net_sockets, parse_pressure, parse_swaps};
 use crate::sys::utils::{get_all_data, to_u64};
+use crate::utils::{DiskListFilter, RefreshInterval, SwapActivity, UserNameCache};
 use crate::{
-    CpuRefreshKind, Disk, LoadAvg, Networks, Pid, ProcessRefreshKind, RefreshKind, SystemExt, User,
+    Battery, CgroupLimits, CgroupStats, CpuRefreshKind, Disk, Error, LoadAvg, Networks, Pid,
+    PressureStats, ProcessRefreshKind, Protocol, RefreshKind, RefreshTimes, Sensor, SwapDevice,
+    SystemExt, Uid, User, UserExt, Virtualization,
 };
 
-use libc::{self, c_char, c_int, sysconf, _SC_CLK_TCK, _SC_HOST_NAME_MAX, _SC_PAGESIZE};
+use libc::{self, sysconf, _SC_CLK_TCK, _SC_HOST_NAME_MAX, _SC_PAGESIZE};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
-use std::path::Path;
-use std::str::FromStr;
+use std::os::raw::{c_char, c_int};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 // This whole thing is to prevent having too many files open at once. It could be problematic
 // for processes using a lot of files and using sysinfo at the same time.
 #[allow(clippy::mutex_atomic)]
-pub(crate) static mut REMAINING_FILES: once_cell::sync::Lazy<Arc<Mutex<isize>>> =
+pub(crate) static REMAINING_FILES: once_cell::sync::Lazy<Arc<Mutex<isize>>> =
     once_cell::sync::Lazy::new(|| {
         unsafe {
             let mut limits = libc::rlimit {
@@ -63,8 +70,111 @@ pub(crate) fn get_max_nb_fds() -> isize {
     }
 }
 
-fn boot_time() -> u64 {
-    if let Ok(f) = File::open("/proc/stat") {
+// Returns `None` if one of the resources has no pressure file, which means that PSI is not
+// supported or disabled.
+fn get_pressure(proc_root: &Path) -> Option<PressureStats> {
+    let read = |resource: &str| {
+        get_all_data(proc_root.join("pressure").join(resource), 1_024)
+            .ok()
+            .and_then(|data| parse_pressure(&data))
+    };
+    Some(PressureStats {
+        cpu: read("cpu")?,
+        memory: read("memory")?,
+        io: read("io")?,
+    })
+}
+
+// Identifies the hypervisor from the DMI vendor and product names.
+fn virtualization_from_dmi(vendor: &str, product: &str) -> Option<Virtualization> {
+    Some(match (vendor, product) {
+        (_, "KVM") => Virtualization::Kvm,
+        ("VMware, Inc.", _) => Virtualization::VMware,
+        ("innotek GmbH", _) | (_, "VirtualBox") => Virtualization::VirtualBox,
+        ("Xen", _) | (_, "HVM domU") => Virtualization::Xen,
+        ("Microsoft Corporation", "Virtual Machine") => Virtualization::HyperV,
+        // QEMU doesn't tell if KVM is used or not.
+        ("QEMU", _) => Virtualization::Other,
+        _ => return None,
+    })
+}
+
+fn get_virtualization(sys_root: &Path) -> Option<Virtualization> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    let cpuid = crate::utils::cpuid_hypervisor();
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    let cpuid = None;
+
+    if cpuid.is_some() && cpuid != Some(Virtualization::Other) {
+        return cpuid;
+    }
+    let read = |file: &str| {
+        get_all_data(sys_root.join(file), 256)
+            .map(|s| s.trim().to_owned())
+            .unwrap_or_default()
+    };
+    virtualization_from_dmi(
+        &read("class/dmi/id/sys_vendor"),
+        &read("class/dmi/id/product_name"),
+    )
+    .or_else(|| {
+        // Xen PV guests don't have DMI information.
+        if read("hypervisor/type") == "xen" {
+            Some(Virtualization::Xen)
+        } else {
+            None
+        }
+    })
+    .or(cpuid)
+}
+
+// Returns `true` if the block device described by `device_dir` (a folder like
+// `/sys/dev/block/<major>:<minor>`) is a dm-crypt device or is stacked on top of one, like an LVM
+// volume on an encrypted partition.
+fn is_dm_crypt_device(device_dir: &Path) -> bool {
+    let is_crypt = get_all_data(device_dir.join("dm/uuid"), 256)
+        .map(|uuid| uuid.starts_with("CRYPT-"))
+        .unwrap_or(false);
+    is_crypt
+        || std::fs::read_dir(device_dir.join("slaves"))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .any(|entry| is_dm_crypt_device(&entry.path()))
+            })
+            .unwrap_or(false)
+}
+
+fn is_swap_encrypted(sys_root: &Path, devices: &[SwapDevice]) -> Option<bool> {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    if devices.is_empty() {
+        return None;
+    }
+    for device in devices {
+        let metadata = std::fs::metadata(&device.path).ok()?;
+        // For swap files, what matters is the device containing the file.
+        let dev = if metadata.file_type().is_block_device() {
+            metadata.rdev()
+        } else {
+            metadata.dev()
+        };
+        let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & 0xffff_f000);
+        let minor = (dev & 0xff) | ((dev >> 12) & 0xffff_ff00);
+        let device_dir = sys_root.join(format!("dev/block/{}:{}", major, minor));
+        // Some file systems (like btrfs) don't use a "real" block device number.
+        if !device_dir.exists() {
+            return None;
+        }
+        if !is_dm_crypt_device(&device_dir) {
+            return Some(false);
+        }
+    }
+    Some(true)
+}
+
+fn boot_time(proc_root: &Path) -> u64 {
+    if let Ok(f) = File::open(proc_root.join("stat")) {
         let buf = BufReader::new(f);
         let line = buf
             .split(b'\n')
@@ -96,18 +206,18 @@ fn boot_time() -> u64 {
 }
 
 pub(crate) struct SystemInfo {
-    pub(crate) page_size_kb: u64,
+    pub(crate) page_size: u64,
     pub(crate) clock_cycle: u64,
     pub(crate) boot_time: u64,
 }
 
 impl SystemInfo {
-    fn new() -> Self {
+    fn new(proc_root: &Path) -> Self {
         unsafe {
             Self {
-                page_size_kb: sysconf(_SC_PAGESIZE) as _,
+                page_size: sysconf(_SC_PAGESIZE) as _,
                 clock_cycle: sysconf(_SC_CLK_TCK) as _,
-                boot_time: boot_time(),
+                boot_time: boot_time(proc_root),
             }
         }
     }
@@ -152,6 +262,9 @@ declare_signals! {
 #[doc = include_str!("../../md_doc/system.md")]
 pub struct System {
     process_list: Process,
+    process_errors: HashMap<Pid, Error>,
+    process_refresh_interval: RefreshInterval,
+    refresh_times: RefreshTimes,
     mem_total: u64,
     mem_free: u64,
     mem_available: u64,
@@ -161,12 +274,17 @@ pub struct System {
     mem_slab_reclaimable: u64,
     swap_total: u64,
     swap_free: u64,
+    swap_activity: SwapActivity,
     components: Vec<Component>,
     disks: Vec<Disk>,
     networks: Networks,
     users: Vec<User>,
+    user_names: UserNameCache,
     info: SystemInfo,
     cpus: CpusWrapper,
+    pressure: Option<PressureStats>,
+    proc_root: PathBuf,
+    sys_root: PathBuf,
 }
 
 impl System {
@@ -181,8 +299,12 @@ impl System {
 
     fn clear_procs(&mut self, refresh_kind: ProcessRefreshKind) {
         let (total_time, compute_cpu, max_value) = if refresh_kind.cpu() {
-            self.cpus
-                .refresh_if_needed(true, CpuRefreshKind::new().with_cpu_usage());
+            self.cpus.refresh_if_needed(
+                &self.proc_root,
+                &self.sys_root,
+                true,
+                CpuRefreshKind::new().with_cpu_usage(),
+            );
 
             if self.cpus.is_empty() {
                 sysinfo_debug!("cannot compute processes CPU usage: no CPU found...");
@@ -213,7 +335,70 @@ impl System {
     }
 
     fn refresh_cpus(&mut self, only_update_global_cpu: bool, refresh_kind: CpuRefreshKind) {
-        self.cpus.refresh(only_update_global_cpu, refresh_kind);
+        self.cpus.refresh(
+            &self.proc_root,
+            &self.sys_root,
+            only_update_global_cpu,
+            refresh_kind,
+        );
+    }
+
+    /// Refreshes the processes corresponding to `pids` and returns how many of them were found.
+    ///
+    /// Unlike `clear_procs`, it doesn't remove the processes which weren't updated.
+    fn refresh_pids_inner(&mut self, pids: &[Pid], refresh_kind: ProcessRefreshKind) -> usize {
+        let uptime = self.uptime();
+        // Explicitly requested processes are kept even if they are kernel threads.
+        let refresh_kind = refresh_kind.with_kernel_threads();
+        let mut found = Vec::with_capacity(pids.len());
+        for pid in pids {
+            match _get_process_data(
+                &self.proc_root.join(pid.to_string()),
+                &mut self.process_list,
+                Pid(0),
+                uptime,
+                &self.info,
+                refresh_kind,
+            ) {
+                Ok((Some(p), pid)) => {
+                    self.process_list.tasks.insert(pid, p);
+                    found.push(pid);
+                }
+                Ok((None, pid)) => found.push(pid),
+                Err(err) => {
+                    self.process_errors.insert(*pid, err);
+                }
+            }
+        }
+        if found.is_empty() {
+            return 0;
+        }
+        if refresh_kind.cpu() {
+            self.refresh_cpus(true, CpuRefreshKind::new().with_cpu_usage());
+
+            if self.cpus.is_empty() {
+                sysinfo_debug!("Cannot compute process CPU usage: no cpus found...");
+                return found.len();
+            }
+            let (new, old) = self.cpus.get_global_raw_times();
+            let total_time = (if old >= new { 1 } else { new - old }) as f32;
+            let total_time = total_time / self.cpus.len() as f32;
+
+            let max_cpu_usage = self.get_max_process_cpu_usage();
+            for pid in &found {
+                if let Some(p) = self.process_list.tasks.get_mut(pid) {
+                    compute_cpu_usage(p, total_time, max_cpu_usage);
+                    p.updated = false;
+                }
+            }
+        } else {
+            for pid in &found {
+                if let Some(p) = self.process_list.tasks.get_mut(pid) {
+                    p.updated = false;
+                }
+            }
+        }
+        found.len()
     }
 }
 
@@ -223,8 +408,12 @@ impl SystemExt for System {
 
     fn new_with_specifics(refreshes: RefreshKind) -> System {
         let process_list = Process::new(Pid(0));
+        let proc_root = PathBuf::from("/proc");
         let mut s = System {
             process_list,
+            process_errors: HashMap::new(),
+            process_refresh_interval: RefreshInterval::default(),
+            refresh_times: RefreshTimes::default(),
             mem_total: 0,
             mem_free: 0,
             mem_available: 0,
@@ -234,127 +423,208 @@ impl SystemExt for System {
             mem_slab_reclaimable: 0,
             swap_total: 0,
             swap_free: 0,
+            swap_activity: SwapActivity::default(),
             cpus: CpusWrapper::new(),
+            pressure: None,
             components: Vec::new(),
             disks: Vec::with_capacity(2),
             networks: Networks::new(),
             users: Vec::new(),
-            info: SystemInfo::new(),
+            user_names: UserNameCache::default(),
+            info: SystemInfo::new(&proc_root),
+            proc_root,
+            sys_root: PathBuf::from("/sys"),
         };
         s.refresh_specifics(refreshes);
         s
     }
 
+    fn with_proc_root(mut self, proc_root: PathBuf) -> Self {
+        self.info.boot_time = boot_time(&proc_root);
+        self.proc_root = proc_root;
+        self
+    }
+
+    fn with_sys_root(mut self, sys_root: PathBuf) -> Self {
+        self.networks.set_sys_root(&sys_root);
+        self.sys_root = sys_root;
+        self
+    }
+
     fn refresh_components_list(&mut self) {
-        self.components = component::get_components();
+        self.components = component::get_components(&self.sys_root.join(HWMON_ROOT));
     }
 
     fn refresh_memory(&mut self) {
-        if let Ok(data) = get_all_data("/proc/meminfo", 16_385) {
+        self.refresh_times.memory = Some(std::time::Instant::now());
+        if let Ok(data) = get_all_data(self.proc_root.join("meminfo"), 16_385) {
             let mut mem_available_found = false;
 
-            for line in data.split('\n') {
-                let mut iter = line.split(':');
-                let field = match iter.next() {
-                    Some("MemTotal") => &mut self.mem_total,
-                    Some("MemFree") => &mut self.mem_free,
-                    Some("MemAvailable") => {
+            for (key, value) in parse_meminfo(&data) {
+                let field = match key {
+                    "MemTotal" => &mut self.mem_total,
+                    "MemFree" => &mut self.mem_free,
+                    "MemAvailable" => {
                         mem_available_found = true;
                         &mut self.mem_available
                     }
-                    Some("Buffers") => &mut self.mem_buffers,
-                    Some("Cached") => &mut self.mem_page_cache,
-                    Some("Shmem") => &mut self.mem_shmem,
-                    Some("SReclaimable") => &mut self.mem_slab_reclaimable,
-                    Some("SwapTotal") => &mut self.swap_total,
-                    Some("SwapFree") => &mut self.swap_free,
+                    "Buffers" => &mut self.mem_buffers,
+                    "Cached" => &mut self.mem_page_cache,
+                    "Shmem" => &mut self.mem_shmem,
+                    "SReclaimable" => &mut self.mem_slab_reclaimable,
+                    "SwapTotal" => &mut self.swap_total,
+                    "SwapFree" => &mut self.swap_free,
                     _ => continue,
                 };
-                if let Some(val_str) = iter.next().and_then(|s| s.trim_start().split(' ').next()) {
-                    if let Ok(value) = u64::from_str(val_str) {
-                        // /proc/meminfo reports KiB, though it says "kB". Convert it.
-                        *field = value.saturating_mul(1_024);
-                    }
-                }
+                *field = value;
             }
 
             // Linux < 3.14 may not have MemAvailable in /proc/meminfo
             // So it should fallback to the old way of estimating available memory
             // https://github.com/KittyKatt/screenFetch/issues/386#issuecomment-249312716
             if !mem_available_found {
-                self.mem_available = self.mem_free
-                    + self.mem_buffers
-                    + self.mem_page_cache
-                    + self.mem_slab_reclaimable
-                    - self.mem_shmem;
+                self.mem_available = self
+                    .mem_free
+                    .saturating_add(self.mem_buffers)
+                    .saturating_add(self.mem_page_cache)
+                    .saturating_add(self.mem_slab_reclaimable)
+                    .saturating_sub(self.mem_shmem);
+            }
+        }
+        if let Ok(data) = get_all_data(self.proc_root.join("vmstat"), 16_385) {
+            let mut pages_in = 0;
+            let mut pages_out = 0;
+            for line in data.lines() {
+                let mut iter = line.split_whitespace();
+                let field = match iter.next() {
+                    Some("pswpin") => &mut pages_in,
+                    Some("pswpout") => &mut pages_out,
+                    _ => continue,
+                };
+                *field = iter.next().and_then(|v| v.parse().ok()).unwrap_or(0);
             }
+            self.swap_activity
+                .update(pages_in, pages_out, std::time::Instant::now());
         }
     }
 
     fn refresh_cpu_specifics(&mut self, refresh_kind: CpuRefreshKind) {
+        self.refresh_times.cpu = Some(std::time::Instant::now());
         self.refresh_cpus(false, refresh_kind);
+        self.pressure = get_pressure(&self.proc_root);
+    }
+
+    fn set_cpu_usage_history_capacity(&mut self, capacity: usize) {
+        self.cpus.set_usage_history_capacity(capacity);
     }
 
     fn refresh_processes_specifics(&mut self, refresh_kind: ProcessRefreshKind) {
+        if !self
+            .process_refresh_interval
+            .should_refresh(std::time::Instant::now())
+        {
+            return;
+        }
+        self.refresh_times.processes = Some(std::time::Instant::now());
         let uptime = self.uptime();
+        self.process_errors.clear();
         refresh_procs(
             &mut self.process_list,
-            Path::new("/proc"),
+            &self.proc_root,
             Pid(0),
             uptime,
             &self.info,
             refresh_kind,
+            Some(&mut self.process_errors),
         );
         self.clear_procs(refresh_kind);
         self.cpus.set_need_cpus_update();
     }
 
-    fn refresh_process_specifics(&mut self, pid: Pid, refresh_kind: ProcessRefreshKind) -> bool {
-        let uptime = self.uptime();
-        let found = match _get_process_data(
-            &Path::new("/proc/").join(pid.to_string()),
-            &mut self.process_list,
-            Pid(0),
-            uptime,
-            &self.info,
-            refresh_kind,
-        ) {
-            Ok((Some(p), pid)) => {
-                self.process_list.tasks.insert(pid, p);
-                true
-            }
-            Ok(_) => true,
-            Err(_) => false,
-        };
-        if found {
-            if refresh_kind.cpu() {
-                self.refresh_cpus(true, CpuRefreshKind::new().with_cpu_usage());
+    fn max_pid(&self) -> Option<Pid> {
+        get_all_data(self.proc_root.join("sys/kernel/pid_max"), 32)
+            .ok()
+            .and_then(|data| data.trim().parse().ok())
+            .map(Pid)
+    }
 
-                if self.cpus.is_empty() {
-                    sysinfo_debug!("Cannot compute process CPU usage: no cpus found...");
-                    return found;
-                }
-                let (new, old) = self.cpus.get_global_raw_times();
-                let total_time = (if old >= new { 1 } else { new - old }) as f32;
+    fn read_sysctl(&self, _name: &str) -> Option<i64> {
+        None
+    }
 
-                let max_cpu_usage = self.get_max_process_cpu_usage();
-                if let Some(p) = self.process_list.tasks.get_mut(&pid) {
-                    compute_cpu_usage(p, total_time / self.cpus.len() as f32, max_cpu_usage);
-                    p.updated = false;
-                }
-            } else if let Some(p) = self.process_list.tasks.get_mut(&pid) {
-                p.updated = false;
-            }
+    fn read_proc_value(&self, path: &str) -> Option<String> {
+        let path = Path::new(path);
+        // Only allow paths which cannot escape from `/proc`: `..` components aren't allowed and
+        // neither are the per-process symlinks to the rest of the file system.
+        if path.as_os_str().is_empty()
+            || !path.components().all(|c| match c {
+                std::path::Component::Normal(name) => !matches!(
+                    name.to_str(),
+                    Some("root" | "cwd" | "exe" | "fd" | "map_files")
+                ),
+                _ => false,
+            })
+        {
+            return None;
         }
-        found
+        let mut data = get_all_data(self.proc_root.join(path), 16_384).ok()?;
+        data.truncate(data.trim_end_matches('\n').len());
+        Some(data)
+    }
+
+    fn for_each_process<F: FnMut(&Process)>(&mut self, refresh: bool, f: F) {
+        if refresh {
+            let uptime = self.uptime();
+            stream_procs(
+                &self.proc_root,
+                uptime,
+                &self.info,
+                ProcessRefreshKind::everything(),
+                f,
+            );
+        } else {
+            self.process_list.tasks.values().for_each(f);
+        }
+    }
+
+    fn set_process_refresh_interval(&mut self, interval: Duration) {
+        self.process_refresh_interval.set_interval(interval);
+    }
+
+    fn processes_refresh_skipped(&self) -> bool {
+        self.process_refresh_interval.skipped()
+    }
+
+    fn refresh_times(&self) -> RefreshTimes {
+        self.refresh_times
+    }
+
+    fn refresh_process_specifics(&mut self, pid: Pid, refresh_kind: ProcessRefreshKind) -> bool {
+        self.process_errors.clear();
+        self.refresh_pids_inner(&[pid], refresh_kind) != 0
+    }
+
+    fn refresh_pids_specifics(&mut self, pids: &[Pid], refresh_kind: ProcessRefreshKind) {
+        self.process_errors.clear();
+        self.refresh_pids_inner(pids, refresh_kind);
     }
 
     fn refresh_disks_list(&mut self) {
-        self.disks = disk::get_all_disks();
+        self.disks = disk::get_all_disks(&self.proc_root, &self.sys_root, DiskListFilter::Default);
+    }
+
+    fn refresh_disks_list_local_only(&mut self) {
+        self.disks =
+            disk::get_all_disks(&self.proc_root, &self.sys_root, DiskListFilter::LocalOnly);
+    }
+
+    fn refresh_disks_list_with_all_file_systems(&mut self) {
+        self.disks = disk::get_all_disks(&self.proc_root, &self.sys_root, DiskListFilter::All);
     }
 
     fn refresh_users_list(&mut self) {
         self.users = crate::users::get_users_list();
+        self.user_names.clear();
     }
 
     // COMMON PART
@@ -369,6 +639,31 @@ impl SystemExt for System {
         self.process_list.tasks.get(&pid)
     }
 
+    fn last_errors(&self) -> &HashMap<Pid, Error> {
+        &self.process_errors
+    }
+
+    fn process_by_port(&self, port: u16, protocol: Protocol) -> Option<&Process> {
+        let (files, listening_only) = match protocol {
+            Protocol::Tcp => (["net/tcp", "net/tcp6"], true),
+            Protocol::Udp => (["net/udp", "net/udp6"], false),
+        };
+        let mut sockets = Vec::new();
+        for file in files.iter() {
+            if let Ok(data) = get_all_data(self.proc_root.join(file), 16_385) {
+                sockets.extend(parse_net_sockets(&data, port, listening_only));
+            }
+        }
+        if sockets.is_empty() {
+            return None;
+        }
+        let mut pids = self.process_list.tasks.keys().collect::<Vec<_>>();
+        pids.sort_unstable();
+        pids.into_iter()
+            .find(|pid| has_socket(&self.proc_root.join(pid.to_string()), &sockets))
+            .and_then(|pid| self.process(*pid))
+    }
+
     fn networks(&self) -> &Networks {
         &self.networks
     }
@@ -385,8 +680,20 @@ impl SystemExt for System {
         &self.cpus.cpus
     }
 
+    fn cpu_count(&self) -> usize {
+        unsafe { sysconf(libc::_SC_NPROCESSORS_ONLN) }.max(1) as usize
+    }
+
     fn physical_core_count(&self) -> Option<usize> {
-        get_physical_core_count()
+        get_physical_core_count(&self.proc_root)
+    }
+
+    fn context_switches(&self) -> Option<u64> {
+        Some(self.cpus.context_switches.delta)
+    }
+
+    fn interrupts(&self) -> Option<u64> {
+        Some(self.cpus.interrupts.delta)
     }
 
     fn total_memory(&self) -> u64 {
@@ -402,7 +709,7 @@ impl SystemExt for System {
     }
 
     fn used_memory(&self) -> u64 {
-        self.mem_total - self.mem_available
+        self.mem_total.saturating_sub(self.mem_available)
     }
 
     fn total_swap(&self) -> u64 {
@@ -415,7 +722,37 @@ impl SystemExt for System {
 
     // need to be checked
     fn used_swap(&self) -> u64 {
-        self.swap_total - self.swap_free
+        self.swap_total.saturating_sub(self.swap_free)
+    }
+
+    fn swap_devices(&self) -> Vec<SwapDevice> {
+        get_all_data(self.proc_root.join("swaps"), 16_385)
+            .map(|data| parse_swaps(&data))
+            .unwrap_or_default()
+    }
+
+    fn is_swap_encrypted(&self) -> Option<bool> {
+        is_swap_encrypted(&self.sys_root, &self.swap_devices())
+    }
+
+    fn page_size(&self) -> u64 {
+        self.info.page_size
+    }
+
+    fn swap_in_per_second(&self) -> f64 {
+        self.swap_activity.in_per_second
+    }
+
+    fn swap_out_per_second(&self) -> f64 {
+        self.swap_activity.out_per_second
+    }
+
+    fn swap_in(&self) -> u64 {
+        self.swap_activity.swapped_in
+    }
+
+    fn swap_out(&self) -> u64 {
+        self.swap_activity.swapped_out
     }
 
     fn components(&self) -> &[Component] {
@@ -426,6 +763,20 @@ impl SystemExt for System {
         &mut self.components
     }
 
+    fn sensors_available(&self) -> bool {
+        component::sensors_available(&self.sys_root.join(HWMON_ROOT))
+    }
+
+    fn cpu_temperature(&self) -> Option<f32> {
+        component::cpu_temperature(&self.components)
+    }
+
+    fn sensors(&self) -> Vec<Sensor> {
+        let mut sensors = Sensor::temperatures(&self.components);
+        sensors.extend(component::get_sensors(&self.sys_root.join(HWMON_ROOT)));
+        sensors
+    }
+
     fn disks(&self) -> &[Disk] {
         &self.disks
     }
@@ -434,6 +785,10 @@ impl SystemExt for System {
         &mut self.disks
     }
 
+    fn file_systems(&self) -> Vec<Disk> {
+        disk::get_all_disks(&self.proc_root, &self.sys_root, DiskListFilter::All)
+    }
+
     fn sort_disks_by<F>(&mut self, compare: F)
     where
         F: FnMut(&Disk, &Disk) -> std::cmp::Ordering,
@@ -442,7 +797,7 @@ impl SystemExt for System {
     }
 
     fn uptime(&self) -> u64 {
-        let content = get_all_data("/proc/uptime", 50).unwrap_or_default();
+        let content = get_all_data(self.proc_root.join("uptime"), 50).unwrap_or_default();
         content
             .split('.')
             .next()
@@ -456,7 +811,7 @@ impl SystemExt for System {
 
     fn load_average(&self) -> LoadAvg {
         let mut s = String::new();
-        if File::open("/proc/loadavg")
+        if File::open(self.proc_root.join("loadavg"))
             .and_then(|mut f| f.read_to_string(&mut s))
             .is_err()
         {
@@ -475,10 +830,47 @@ impl SystemExt for System {
         }
     }
 
+    fn cgroup_stats(&self, path: &Path) -> Option<CgroupStats> {
+        get_cgroup_stats(&self.sys_root.join(CGROUP_ROOT), path)
+    }
+
+    fn cgroup_limits(&self) -> Option<CgroupLimits> {
+        let self_cgroup = get_all_data(self.proc_root.join("self/cgroup"), 16_384).ok()?;
+        let mut mem_total = self.mem_total;
+        if mem_total == 0 {
+            // The memory wasn't refreshed yet.
+            if let Ok(data) = get_all_data(self.proc_root.join("meminfo"), 16_385) {
+                mem_total = parse_meminfo(&data)
+                    .find(|(key, _)| *key == "MemTotal")
+                    .map(|(_, value)| value)
+                    .unwrap_or(0);
+            }
+        }
+        get_cgroup_limits(&self.sys_root.join(CGROUP_ROOT), &self_cgroup, mem_total)
+    }
+
+    fn pressure(&self) -> Option<PressureStats> {
+        self.pressure
+    }
+
+    fn battery(&self) -> Option<Battery> {
+        get_battery(&self.sys_root.join(POWER_SUPPLY_ROOT))
+    }
+
+    fn on_ac_power(&self) -> Option<bool> {
+        get_on_ac_power(&self.sys_root.join(POWER_SUPPLY_ROOT))
+    }
+
     fn users(&self) -> &[User] {
         &self.users
     }
 
+    fn get_user_name_by_id(&self, user_id: &Uid) -> Option<String> {
+        self.get_user_by_id(user_id)
+            .map(|user| user.name().to_owned())
+            .or_else(|| self.user_names.get(user_id))
+    }
+
     #[cfg(not(target_os = "android"))]
     fn name(&self) -> Option<String> {
         get_system_info_linux(
@@ -525,6 +917,10 @@ impl SystemExt for System {
         }
     }
 
+    fn virtualization(&self) -> Option<Virtualization> {
+        get_virtualization(&self.sys_root)
+    }
+
     fn kernel_version(&self) -> Option<String> {
         let mut raw = std::mem::MaybeUninit::<libc::utsname>::zeroed();
 
@@ -609,7 +1005,7 @@ fn get_system_info_linux(info: InfoType, path: &Path, fallback_path: &Path) -> O
             InfoType::DistributionID => "ID=",
         };
 
-        for line in reader.lines().flatten() {
+        for line in reader.lines().map_while(Result::ok) {
             if let Some(stripped) = line.strip_prefix(info_str) {
                 return Some(stripped.replace('"', ""));
             }
@@ -630,7 +1026,7 @@ fn get_system_info_linux(info: InfoType, path: &Path, fallback_path: &Path) -> O
             return None;
         }
     };
-    for line in reader.lines().flatten() {
+    for line in reader.lines().map_while(Result::ok) {
         if let Some(stripped) = line.strip_prefix(info_str) {
             return Some(stripped.replace('"', ""));
         }
@@ -675,6 +1071,324 @@ mod test {
     #[cfg(not(target_os = "android"))]
     use super::get_system_info_linux;
     use super::InfoType;
+    use std::path::Path;
+
+    // Writes the `stat` file of a fake process (or task) in `dir`. All the numeric fields are 0,
+    // except for the parent, process group, session and terminal process group ones, and the ones
+    // in `overrides`, which are indexed like the fields returned by `parse_stat_line`.
+    fn write_stat(dir: &Path, pid: u32, name: &str, state: char, overrides: &[(usize, u64)]) {
+        let pid = pid.to_string();
+        let mut fields = vec![
+            pid.clone(),
+            format!("({})", name),
+            state.to_string(),
+            "1".to_owned(),
+            pid.clone(),
+            pid,
+            "0".to_owned(),
+            "-1".to_owned(),
+        ];
+        fields.resize(51, "0".to_owned());
+        for &(index, value) in overrides {
+            fields[index] = value.to_string();
+        }
+        std::fs::create_dir_all(dir).expect("failed to create process directory");
+        std::fs::write(dir.join("stat"), fields.join(" ")).expect("failed to write stat");
+    }
+
+    #[test]
+    fn check_virtualization_from_dmi() {
+        use super::virtualization_from_dmi;
+        use crate::Virtualization;
+
+        assert_eq!(
+            virtualization_from_dmi("QEMU", "KVM"),
+            Some(Virtualization::Kvm)
+        );
+        assert_eq!(
+            virtualization_from_dmi("QEMU", "Standard PC (Q35 + ICH9, 2009)"),
+            Some(Virtualization::Other)
+        );
+        assert_eq!(
+            virtualization_from_dmi("VMware, Inc.", "VMware Virtual Platform"),
+            Some(Virtualization::VMware)
+        );
+        assert_eq!(
+            virtualization_from_dmi("innotek GmbH", "VirtualBox"),
+            Some(Virtualization::VirtualBox)
+        );
+        assert_eq!(
+            virtualization_from_dmi("Xen", "HVM domU"),
+            Some(Virtualization::Xen)
+        );
+        assert_eq!(
+            virtualization_from_dmi("Microsoft Corporation", "Virtual Machine"),
+            Some(Virtualization::HyperV)
+        );
+        assert_eq!(
+            virtualization_from_dmi("Microsoft Corporation", "Surface Laptop 4"),
+            None
+        );
+        assert_eq!(virtualization_from_dmi("Dell Inc.", "XPS 13 9310"), None);
+        assert_eq!(virtualization_from_dmi("", ""), None);
+    }
+
+    #[test]
+    fn check_inconsistent_memory_values() {
+        use super::System;
+        use crate::SystemExt;
+
+        let mut s = System::new();
+        // The kernel could report more free memory/swap than the total.
+        s.mem_total = 10;
+        s.mem_available = 20;
+        s.swap_total = 10;
+        s.swap_free = 20;
+        assert_eq!(s.used_memory(), 0);
+        assert_eq!(s.used_swap(), 0);
+    }
+
+    #[test]
+    fn check_custom_roots() {
+        use super::System;
+        use crate::{NetworkExt, NetworksExt, Pid, PidExt, SystemExt};
+        use std::fs;
+
+        let dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let proc_root = dir.path().join("proc");
+        let sys_root = dir.path().join("sys");
+        let statistics = sys_root.join("class/net/eth0/statistics");
+        fs::create_dir_all(&proc_root).expect("failed to create proc directory");
+        fs::create_dir_all(&statistics).expect("failed to create sys directory");
+
+        fs::write(
+            proc_root.join("meminfo"),
+            "MemTotal: 2000 kB\nMemFree: 500 kB\nMemAvailable: 1500 kB\nSwapTotal: 100 kB\n\
+             SwapFree: 40 kB\n",
+        )
+        .expect("failed to write meminfo");
+        fs::write(
+            proc_root.join("stat"),
+            "cpu  1 2 3 4 5 6 7 8 9 10\nbtime 42\n",
+        )
+        .expect("failed to write stat");
+        fs::write(proc_root.join("uptime"), "1234.56 789.01\n").expect("failed to write uptime");
+        fs::write(proc_root.join("loadavg"), "0.50 0.25 0.10 1/100 4242\n")
+            .expect("failed to write loadavg");
+        fs::create_dir_all(proc_root.join("sys/kernel")).expect("failed to create sys directory");
+        fs::write(proc_root.join("sys/kernel/pid_max"), "32768\n")
+            .expect("failed to write pid_max");
+        fs::write(statistics.join("rx_bytes"), "12\n").expect("failed to write rx_bytes");
+        let pressure = proc_root.join("pressure");
+        fs::create_dir_all(&pressure).expect("failed to create pressure directory");
+        for resource in &["cpu", "memory", "io"] {
+            fs::write(
+                pressure.join(resource),
+                "some avg10=1.00 avg60=0.50 avg300=0.25 total=42\n",
+            )
+            .expect("failed to write pressure");
+        }
+
+        let mut s = System::new()
+            .with_proc_root(proc_root)
+            .with_sys_root(sys_root);
+        assert_eq!(s.pressure(), None);
+        s.refresh_memory();
+        s.refresh_cpu();
+        s.refresh_networks_list();
+
+        assert_eq!(s.total_memory(), 2_000 * 1_024);
+        assert_eq!(s.available_memory(), 1_500 * 1_024);
+        assert_eq!(s.used_swap(), 60 * 1_024);
+        assert_eq!(s.boot_time(), 42);
+        assert_eq!(s.uptime(), 1234);
+        assert_eq!(s.load_average().five, 0.25);
+        assert_eq!(s.max_pid(), Some(Pid::from_u32(32_768)));
+        assert_eq!(
+            s.read_proc_value("sys/kernel/pid_max").as_deref(),
+            Some("32768")
+        );
+        assert_eq!(s.read_proc_value("../pid_max"), None);
+        assert_eq!(s.read_proc_value("/etc/hostname"), None);
+        assert_eq!(s.read_proc_value(""), None);
+        assert_eq!(s.read_proc_value("self/root/etc/hostname"), None);
+        assert_eq!(s.read_proc_value("1/task/1/cwd/foo"), None);
+        assert_eq!(s.pressure().map(|p| p.memory.some.avg60), Some(0.5));
+        assert_eq!(s.networks().iter().count(), 1);
+        assert_eq!(
+            s.networks().iter().next().map(|(_, n)| n.total_received()),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn check_is_dm_crypt_device() {
+        use super::is_dm_crypt_device;
+        use std::fs;
+
+        let dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let partition = dir.path().join("8:2");
+        fs::create_dir_all(&partition).expect("failed to create partition directory");
+        let crypt = dir.path().join("253:0");
+        fs::create_dir_all(crypt.join("dm")).expect("failed to create crypt directory");
+        fs::write(crypt.join("dm/uuid"), "CRYPT-LUKS2-0123-swap\n").expect("failed to write uuid");
+        let lvm = dir.path().join("253:1");
+        fs::create_dir_all(lvm.join("dm")).expect("failed to create lvm directory");
+        fs::write(lvm.join("dm/uuid"), "LVM-0123\n").expect("failed to write uuid");
+        fs::create_dir_all(lvm.join("slaves/dm-0/dm")).expect("failed to create slaves directory");
+        fs::write(lvm.join("slaves/dm-0/dm/uuid"), "CRYPT-LUKS2-0123-swap\n")
+            .expect("failed to write uuid");
+
+        assert!(!is_dm_crypt_device(&partition));
+        assert!(is_dm_crypt_device(&crypt));
+        assert!(is_dm_crypt_device(&lvm));
+    }
+
+    #[test]
+    fn check_process_exiting_during_refresh() {
+        use super::System;
+        use crate::{Error, Pid, PidExt, ProcessExt, SystemExt};
+        use std::fs;
+
+        let dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let proc_root = dir.path().join("proc");
+        let process_dir = proc_root.join("42");
+        write_stat(&process_dir, 42, "fake", 'S', &[]);
+
+        let pid = Pid::from_u32(42);
+        let mut s = System::new().with_proc_root(proc_root);
+        s.refresh_processes();
+        assert_eq!(s.process(pid).map(|p| p.name()), Some("fake"));
+
+        // The process is still listed in `/proc` but it exited before its `stat` file is read.
+        fs::remove_file(process_dir.join("stat")).expect("failed to remove stat");
+        s.refresh_processes();
+        assert!(s.process(pid).is_none());
+        assert_eq!(s.last_errors().get(&pid), Some(&Error::NotFound));
+    }
+
+    #[test]
+    fn check_for_each_process() {
+        use super::System;
+        use crate::{ProcessExt, SystemExt};
+
+        let dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let proc_root = dir.path().join("proc");
+        let process_dir = proc_root.join("42");
+        write_stat(&process_dir, 42, "fake", 'S', &[]);
+
+        let mut s = System::new().with_proc_root(proc_root);
+        let mut names = Vec::new();
+        s.for_each_process(true, |p| names.push(p.name().to_owned()));
+        assert_eq!(names, ["fake"]);
+        // The streamed processes are not stored.
+        assert!(s.processes().is_empty());
+        s.for_each_process(false, |_| panic!("no process should be stored"));
+
+        s.refresh_processes();
+        let mut count = 0;
+        s.for_each_process(false, |_| count += 1);
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn check_process_iowait_time() {
+        use super::System;
+        use crate::{Pid, PidExt, ProcessExt, SystemExt};
+
+        let dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let proc_root = dir.path().join("proc");
+        let mut s = System::new().with_proc_root(proc_root.clone());
+        // `delayacct_blkio_ticks` is the 42nd field.
+        write_stat(
+            &proc_root.join("42"),
+            42,
+            "fake",
+            'S',
+            &[(41, s.info.clock_cycle * 3)],
+        );
+
+        s.refresh_processes();
+        let process = s.process(Pid::from_u32(42)).expect("process not found");
+        assert_eq!(process.iowait_time(), Some(3_000));
+    }
+
+    #[test]
+    fn check_sys_and_proc_roots() {
+        use super::System;
+        use crate::{ComponentExt, SystemExt};
+        use std::fs;
+
+        let dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let sys_root = dir.path().join("sys");
+        let hwmon = sys_root.join("class/hwmon/hwmon0");
+        fs::create_dir_all(&hwmon).expect("failed to create hwmon directory");
+        fs::write(hwmon.join("name"), "fake\n").expect("failed to write name");
+        fs::write(hwmon.join("temp1_input"), "42000\n").expect("failed to write temp1_input");
+        let proc_root = dir.path().join("proc");
+        fs::create_dir_all(&proc_root).expect("failed to create proc directory");
+        fs::write(
+            proc_root.join("cpuinfo"),
+            "processor\t: 0\ncore id\t\t: 0\nphysical id\t: 0\n\n\
+             processor\t: 1\ncore id\t\t: 0\nphysical id\t: 0\n",
+        )
+        .expect("failed to write cpuinfo");
+        fs::write(proc_root.join("mounts"), "").expect("failed to write mounts");
+
+        let mut s = System::new()
+            .with_proc_root(proc_root)
+            .with_sys_root(sys_root);
+        assert!(s.sensors_available());
+        s.refresh_components_list();
+        assert_eq!(
+            s.components()
+                .iter()
+                .map(|c| c.temperature())
+                .collect::<Vec<_>>(),
+            [42.]
+        );
+        assert_eq!(s.physical_core_count(), Some(1));
+        s.refresh_disks_list();
+        assert!(s.disks().is_empty());
+    }
+
+    #[test]
+    fn check_process_threads() {
+        use super::System;
+        use crate::{Pid, PidExt, ProcessExt, ProcessRefreshKind, ProcessStatus, SystemExt};
+
+        let dir = tempfile::tempdir().expect("failed to create temporary directory");
+        let proc_root = dir.path().join("proc");
+        let process_dir = proc_root.join("42");
+        write_stat(&process_dir, 42, "fake", 'S', &[]);
+        write_stat(&process_dir.join("task/42"), 42, "fake", 'S', &[]);
+        write_stat(&process_dir.join("task/43"), 43, "worker", 'R', &[]);
+
+        let mut s = System::new().with_proc_root(proc_root.clone());
+        s.refresh_processes();
+        let process = s.process(Pid::from_u32(42)).expect("process not found");
+        // Tasks keep the name of their process, only `threads` returns the name of the thread.
+        assert_eq!(process.tasks[&Pid::from_u32(43)].name(), "fake");
+        let threads = process
+            .threads()
+            .into_iter()
+            .map(|thread| (thread.tid(), thread.name().to_owned(), thread.status()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            threads,
+            [
+                (42, "fake".to_owned(), ProcessStatus::Sleep),
+                (43, "worker".to_owned(), ProcessStatus::Run),
+            ]
+        );
+
+        // Threads are only retrieved when asked, but tasks are always retrieved.
+        let mut s = System::new().with_proc_root(proc_root);
+        s.refresh_processes_specifics(ProcessRefreshKind::new());
+        let process = s.process(Pid::from_u32(42)).expect("process not found");
+        assert_eq!(process.tasks.len(), 1);
+        assert!(process.threads().is_empty());
+    }
 
     #[test]
     #[cfg(target_os = "android")]
@@ -687,8 +1401,6 @@ mod test {
     #[test]
     #[cfg(not(target_os = "android"))]
     fn lsb_release_fallback_not_android() {
-        use std::path::Path;
-
         let dir = tempfile::tempdir().expect("failed to create temporary directory");
         let tmp1 = dir.path().join("tmp1");
         let tmp2 = dir.path().join("tmp2");
@@ -34,14 +34,12 @@ pub(crate) struct FileCounter(File);
 
 impl FileCounter {
     pub(crate) fn new(f: File) -> Option<Self> {
-        unsafe {
-            if let Ok(ref mut x) = REMAINING_FILES.lock() {
-                if **x > 0 {
-                    **x -= 1;
-                    return Some(Self(f));
-                }
-                // All file descriptors we were allowed are being used.
+        if let Ok(ref mut x) = REMAINING_FILES.lock() {
+            if **x > 0 {
+                **x -= 1;
+                return Some(Self(f));
             }
+            // All file descriptors we were allowed are being used.
         }
         None
     }
@@ -62,10 +60,8 @@ impl std::ops::DerefMut for FileCounter {
 
 impl Drop for FileCounter {
     fn drop(&mut self) {
-        unsafe {
-            if let Ok(ref mut x) = crate::sys::system::REMAINING_FILES.lock() {
-                **x += 1;
-            }
+        if let Ok(ref mut x) = crate::sys::system::REMAINING_FILES.lock() {
+            **x += 1;
         }
     }
 }
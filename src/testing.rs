@@ -0,0 +1,809 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+//! Types allowing to test code using `sysinfo` without depending on the current system.
+//!
+//! This module is only available when the `testing` feature is enabled.
+//!
+//! ```
+//! use sysinfo::testing::MockSystem;
+//! use sysinfo::{Pid, ProcessExt, ProcessSnapshot, ProcessStatus, SystemExt, DiskUsage};
+//! use std::path::PathBuf;
+//!
+//! let s = MockSystem::builder()
+//!     .total_memory(8_000_000)
+//!     .available_memory(2_000_000)
+//!     .process(ProcessSnapshot {
+//!         pid: Pid::from(42),
+//!         parent: None,
+//!         name: "fake".to_owned(),
+//!         cmd: vec!["fake".to_owned()],
+//!         exe: PathBuf::from("/usr/bin/fake"),
+//!         memory: 1_000,
+//!         virtual_memory: 2_000,
+//!         cpu_usage: 12.,
+//!         status: ProcessStatus::Run,
+//!         start_time: 0,
+//!         run_time: 0,
+//!         disk_usage: DiskUsage::default(),
+//!         user_id: None,
+//!     })
+//!     .build();
+//! assert_eq!(s.used_memory(), 6_000_000);
+//! assert_eq!(s.process(Pid::from(42)).map(|p| p.pid()), Some(Pid::from(42)));
+//! ```
+//!
+//! ⚠️ The processes returned by [`MockSystem`] are the same [`Process`] type as the one returned
+//! by [`System`][crate::System], so methods acting on the system (like [`ProcessExt::kill`] or
+//! [`ProcessExt::wait`]) will act on the real process with the same PID, if any.
+
+use crate::{
+    Battery, CgroupLimits, CgroupStats, Component, Cpu, CpuRefreshKind, CpuSnapshot, Disk, Error,
+    LoadAvg, Networks, Pid, PressureStats, Process, ProcessRefreshKind, ProcessSnapshot, Protocol,
+    RefreshKind, RefreshTimes, Signal, SwapDevice, SystemExt, User, Virtualization,
+};
+
+#[cfg(doc)]
+use crate::ProcessExt;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+/// A [`SystemExt`] implementation returning fixed values instead of the ones of the current
+/// system. The "refresh" methods don't do anything.
+///
+/// Use [`MockSystem::builder`] to create it.
+///
+/// ```
+/// use sysinfo::testing::MockSystem;
+/// use sysinfo::SystemExt;
+///
+/// fn memory_usage<S: SystemExt>(s: &S) -> u64 {
+///     s.used_memory() * 100 / s.total_memory()
+/// }
+///
+/// let s = MockSystem::builder()
+///     .total_memory(100)
+///     .available_memory(25)
+///     .build();
+/// assert_eq!(memory_usage(&s), 75);
+/// ```
+pub struct MockSystem {
+    processes: HashMap<Pid, Process>,
+    global_cpu: Cpu,
+    process_errors: HashMap<Pid, Error>,
+    cpus: Vec<Cpu>,
+    physical_core_count: Option<usize>,
+    total_memory: u64,
+    free_memory: u64,
+    available_memory: u64,
+    total_swap: u64,
+    free_swap: u64,
+    page_size: u64,
+    components: Vec<Component>,
+    disks: Vec<Disk>,
+    users: Vec<User>,
+    networks: Networks,
+    uptime: u64,
+    boot_time: u64,
+    load_average: LoadAvg,
+    name: Option<String>,
+    kernel_version: Option<String>,
+    os_version: Option<String>,
+    host_name: Option<String>,
+}
+
+impl MockSystem {
+    /// Creates a new [`MockSystemBuilder`].
+    ///
+    /// ```
+    /// use sysinfo::testing::MockSystem;
+    /// use sysinfo::SystemExt;
+    ///
+    /// let s = MockSystem::builder().total_memory(8_000_000).build();
+    /// assert_eq!(s.total_memory(), 8_000_000);
+    /// ```
+    pub fn builder() -> MockSystemBuilder {
+        MockSystemBuilder::default()
+    }
+}
+
+impl Default for MockSystem {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl fmt::Debug for MockSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockSystem")
+            .field("total memory", &self.total_memory)
+            .field("available memory", &self.available_memory)
+            .field("total swap", &self.total_swap)
+            .field("free swap", &self.free_swap)
+            .field("nb CPUs", &self.cpus.len())
+            .field("nb processes", &self.processes.len())
+            .finish()
+    }
+}
+
+/// Builder for [`MockSystem`].
+///
+/// Values which aren't set are `0`, empty or `None`.
+///
+/// ```
+/// use sysinfo::testing::MockSystem;
+/// use sysinfo::{CpuExt, CpuSnapshot, SystemExt};
+///
+/// let s = MockSystem::builder()
+///     .cpu(CpuSnapshot {
+///         name: "cpu0".to_owned(),
+///         cpu_usage: 50.,
+///         frequency: 2_000,
+///         vendor_id: "GenuineIntel".to_owned(),
+///         brand: String::new(),
+///     })
+///     .global_cpu_usage(50.)
+///     .build();
+/// assert_eq!(s.cpus().len(), 1);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MockSystemBuilder {
+    processes: Vec<ProcessSnapshot>,
+    process_errors: HashMap<Pid, Error>,
+    global_cpu_usage: f32,
+    cpus: Vec<CpuSnapshot>,
+    physical_core_count: Option<usize>,
+    total_memory: u64,
+    free_memory: u64,
+    available_memory: u64,
+    total_swap: u64,
+    free_swap: u64,
+    page_size: u64,
+    uptime: u64,
+    boot_time: u64,
+    load_average: LoadAvg,
+    name: Option<String>,
+    kernel_version: Option<String>,
+    os_version: Option<String>,
+    host_name: Option<String>,
+}
+
+impl MockSystemBuilder {
+    /// Adds a process. If a process with the same PID was already added, it is replaced.
+    pub fn process(mut self, process: ProcessSnapshot) -> Self {
+        self.processes.push(process);
+        self
+    }
+
+    /// Adds an error returned by [`SystemExt::last_errors`].
+    pub fn process_error(mut self, pid: Pid, error: Error) -> Self {
+        self.process_errors.insert(pid, error);
+        self
+    }
+
+    /// Adds a CPU.
+    pub fn cpu(mut self, cpu: CpuSnapshot) -> Self {
+        self.cpus.push(cpu);
+        self
+    }
+
+    /// Sets the usage of the global CPU returned by [`SystemExt::global_cpu_info`].
+    pub fn global_cpu_usage(mut self, cpu_usage: f32) -> Self {
+        self.global_cpu_usage = cpu_usage;
+        self
+    }
+
+    /// Sets the value returned by [`SystemExt::physical_core_count`].
+    pub fn physical_core_count(mut self, count: usize) -> Self {
+        self.physical_core_count = Some(count);
+        self
+    }
+
+    /// Sets the total memory, in bytes.
+    pub fn total_memory(mut self, total_memory: u64) -> Self {
+        self.total_memory = total_memory;
+        self
+    }
+
+    /// Sets the free memory, in bytes.
+    pub fn free_memory(mut self, free_memory: u64) -> Self {
+        self.free_memory = free_memory;
+        self
+    }
+
+    /// Sets the available memory, in bytes. [`SystemExt::used_memory`] is computed from it.
+    pub fn available_memory(mut self, available_memory: u64) -> Self {
+        self.available_memory = available_memory;
+        self
+    }
+
+    /// Sets the total swap, in bytes.
+    pub fn total_swap(mut self, total_swap: u64) -> Self {
+        self.total_swap = total_swap;
+        self
+    }
+
+    /// Sets the free swap, in bytes.
+    pub fn free_swap(mut self, free_swap: u64) -> Self {
+        self.free_swap = free_swap;
+        self
+    }
+
+    /// Sets the size of a memory page, in bytes.
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Sets the uptime, in seconds.
+    pub fn uptime(mut self, uptime: u64) -> Self {
+        self.uptime = uptime;
+        self
+    }
+
+    /// Sets the boot time, in seconds since UNIX epoch.
+    pub fn boot_time(mut self, boot_time: u64) -> Self {
+        self.boot_time = boot_time;
+        self
+    }
+
+    /// Sets the load average.
+    pub fn load_average(mut self, load_average: LoadAvg) -> Self {
+        self.load_average = load_average;
+        self
+    }
+
+    /// Sets the system name.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Sets the kernel version.
+    pub fn kernel_version(mut self, kernel_version: &str) -> Self {
+        self.kernel_version = Some(kernel_version.to_owned());
+        self
+    }
+
+    /// Sets the OS version.
+    pub fn os_version(mut self, os_version: &str) -> Self {
+        self.os_version = Some(os_version.to_owned());
+        self
+    }
+
+    /// Sets the host name.
+    pub fn host_name(mut self, host_name: &str) -> Self {
+        self.host_name = Some(host_name.to_owned());
+        self
+    }
+
+    /// Creates the [`MockSystem`].
+    pub fn build(self) -> MockSystem {
+        MockSystem {
+            processes: self
+                .processes
+                .iter()
+                .map(|p| (p.pid, Process::from_snapshot(p)))
+                .collect(),
+            process_errors: self.process_errors,
+            global_cpu: Cpu::from_snapshot(&CpuSnapshot {
+                name: String::new(),
+                cpu_usage: self.global_cpu_usage,
+                frequency: 0,
+                vendor_id: String::new(),
+                brand: String::new(),
+            }),
+            cpus: self.cpus.iter().map(Cpu::from_snapshot).collect(),
+            physical_core_count: self.physical_core_count,
+            total_memory: self.total_memory,
+            free_memory: self.free_memory,
+            available_memory: self.available_memory,
+            total_swap: self.total_swap,
+            free_swap: self.free_swap,
+            page_size: self.page_size,
+            components: Vec::new(),
+            disks: Vec::new(),
+            users: Vec::new(),
+            networks: Networks::new(),
+            uptime: self.uptime,
+            boot_time: self.boot_time,
+            load_average: self.load_average,
+            name: self.name,
+            kernel_version: self.kernel_version,
+            os_version: self.os_version,
+            host_name: self.host_name,
+        }
+    }
+}
+
+impl SystemExt for MockSystem {
+    const IS_SUPPORTED: bool = true;
+    const SUPPORTED_SIGNALS: &'static [Signal] = &[];
+
+    fn new_with_specifics(_refreshes: RefreshKind) -> MockSystem {
+        Self::default()
+    }
+
+    fn refresh_memory(&mut self) {}
+
+    fn refresh_cpu_specifics(&mut self, _refresh_kind: CpuRefreshKind) {}
+
+    fn set_cpu_usage_history_capacity(&mut self, _capacity: usize) {}
+
+    fn refresh_components_list(&mut self) {}
+
+    fn refresh_processes_specifics(&mut self, _refresh_kind: ProcessRefreshKind) {}
+
+    fn set_process_refresh_interval(&mut self, _interval: Duration) {}
+
+    fn processes_refresh_skipped(&self) -> bool {
+        false
+    }
+
+    fn refresh_times(&self) -> RefreshTimes {
+        RefreshTimes::default()
+    }
+
+    fn refresh_process_specifics(&mut self, pid: Pid, _refresh_kind: ProcessRefreshKind) -> bool {
+        self.processes.contains_key(&pid)
+    }
+
+    fn refresh_pids_specifics(&mut self, _pids: &[Pid], _refresh_kind: ProcessRefreshKind) {}
+
+    fn refresh_disks_list(&mut self) {}
+
+    fn refresh_disks_list_local_only(&mut self) {}
+
+    fn refresh_disks_list_with_all_file_systems(&mut self) {}
+
+    fn refresh_users_list(&mut self) {}
+
+    fn processes(&self) -> &HashMap<Pid, Process> {
+        &self.processes
+    }
+
+    fn process(&self, pid: Pid) -> Option<&Process> {
+        self.processes.get(&pid)
+    }
+
+    fn last_errors(&self) -> &HashMap<Pid, Error> {
+        &self.process_errors
+    }
+
+    fn process_by_port(&self, _port: u16, _protocol: Protocol) -> Option<&Process> {
+        None
+    }
+
+    fn global_cpu_info(&self) -> &Cpu {
+        &self.global_cpu
+    }
+
+    fn cpus(&self) -> &[Cpu] {
+        &self.cpus
+    }
+
+    fn cpu_count(&self) -> usize {
+        self.cpus.len()
+    }
+
+    fn physical_core_count(&self) -> Option<usize> {
+        self.physical_core_count
+    }
+
+    fn max_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    fn read_sysctl(&self, _name: &str) -> Option<i64> {
+        None
+    }
+
+    fn read_proc_value(&self, _path: &str) -> Option<String> {
+        None
+    }
+
+    fn context_switches(&self) -> Option<u64> {
+        None
+    }
+
+    fn interrupts(&self) -> Option<u64> {
+        None
+    }
+
+    fn total_memory(&self) -> u64 {
+        self.total_memory
+    }
+
+    fn free_memory(&self) -> u64 {
+        self.free_memory
+    }
+
+    fn available_memory(&self) -> u64 {
+        self.available_memory
+    }
+
+    fn used_memory(&self) -> u64 {
+        self.total_memory.saturating_sub(self.available_memory)
+    }
+
+    fn total_swap(&self) -> u64 {
+        self.total_swap
+    }
+
+    fn free_swap(&self) -> u64 {
+        self.free_swap
+    }
+
+    fn used_swap(&self) -> u64 {
+        self.total_swap.saturating_sub(self.free_swap)
+    }
+
+    fn swap_devices(&self) -> Vec<SwapDevice> {
+        Vec::new()
+    }
+
+    fn is_swap_encrypted(&self) -> Option<bool> {
+        None
+    }
+
+    fn page_size(&self) -> u64 {
+        self.page_size
+    }
+
+    fn swap_in_per_second(&self) -> f64 {
+        0.
+    }
+
+    fn swap_out_per_second(&self) -> f64 {
+        0.
+    }
+
+    fn swap_in(&self) -> u64 {
+        0
+    }
+
+    fn swap_out(&self) -> u64 {
+        0
+    }
+
+    fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    fn components_mut(&mut self) -> &mut [Component] {
+        &mut self.components
+    }
+
+    fn sensors_available(&self) -> bool {
+        false
+    }
+
+    fn cpu_temperature(&self) -> Option<f32> {
+        None
+    }
+
+    fn users(&self) -> &[User] {
+        &self.users
+    }
+
+    fn disks(&self) -> &[Disk] {
+        &self.disks
+    }
+
+    fn disks_mut(&mut self) -> &mut [Disk] {
+        &mut self.disks
+    }
+
+    fn file_systems(&self) -> Vec<Disk> {
+        Vec::new()
+    }
+
+    fn sort_disks_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&Disk, &Disk) -> std::cmp::Ordering,
+    {
+        self.disks.sort_unstable_by(compare);
+    }
+
+    fn networks(&self) -> &Networks {
+        &self.networks
+    }
+
+    fn networks_mut(&mut self) -> &mut Networks {
+        &mut self.networks
+    }
+
+    fn uptime(&self) -> u64 {
+        self.uptime
+    }
+
+    fn boot_time(&self) -> u64 {
+        self.boot_time
+    }
+
+    fn load_average(&self) -> LoadAvg {
+        self.load_average.clone()
+    }
+
+    fn cgroup_stats(&self, _path: &Path) -> Option<CgroupStats> {
+        None
+    }
+
+    fn cgroup_limits(&self) -> Option<CgroupLimits> {
+        None
+    }
+
+    fn pressure(&self) -> Option<PressureStats> {
+        None
+    }
+
+    fn battery(&self) -> Option<Battery> {
+        None
+    }
+
+    fn on_ac_power(&self) -> Option<bool> {
+        None
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn kernel_version(&self) -> Option<String> {
+        self.kernel_version.clone()
+    }
+
+    fn os_version(&self) -> Option<String> {
+        self.os_version.clone()
+    }
+
+    fn long_os_version(&self) -> Option<String> {
+        match (&self.name, &self.os_version) {
+            (Some(name), Some(version)) => Some(format!("{} {}", name, version)),
+            (name, _) => name.clone(),
+        }
+    }
+
+    fn distribution_id(&self) -> String {
+        std::env::consts::OS.to_owned()
+    }
+
+    fn host_name(&self) -> Option<String> {
+        self.host_name.clone()
+    }
+
+    fn virtualization(&self) -> Option<Virtualization> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MockSystem;
+    use crate::{
+        CpuExt, CpuSnapshot, DiskUsage, Error, Pid, PidExt, ProcessExt, ProcessSnapshot,
+        ProcessStatus, SystemExt,
+    };
+    use std::path::PathBuf;
+
+    fn snapshot(pid: u32, name: &str) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid: Pid::from_u32(pid),
+            parent: None,
+            name: name.to_owned(),
+            cmd: Vec::new(),
+            exe: PathBuf::new(),
+            memory: 10,
+            virtual_memory: 20,
+            cpu_usage: 1.,
+            status: ProcessStatus::Run,
+            start_time: 0,
+            run_time: 0,
+            disk_usage: DiskUsage {
+                total_written_bytes: 30,
+                written_bytes: 10,
+                total_read_bytes: 40,
+                read_bytes: 5,
+            },
+            user_id: None,
+        }
+    }
+
+    #[test]
+    fn check_mock_system() {
+        let mut s = MockSystem::builder()
+            .total_memory(8_000)
+            .available_memory(3_000)
+            .total_swap(100)
+            .free_swap(40)
+            .page_size(4_096)
+            .global_cpu_usage(25.)
+            .cpu(CpuSnapshot {
+                name: "cpu0".to_owned(),
+                cpu_usage: 25.,
+                frequency: 1_000,
+                vendor_id: "vendor".to_owned(),
+                brand: "brand".to_owned(),
+            })
+            .process(snapshot(1, "a"))
+            .process(snapshot(2, "b"))
+            .process(snapshot(1, "c"))
+            .process_error(Pid::from_u32(4), Error::PermissionDenied)
+            .name("mock")
+            .build();
+        s.refresh_all();
+
+        assert_eq!(s.used_memory(), 5_000);
+        assert_eq!(s.used_swap(), 60);
+        assert_eq!(s.page_size(), 4_096);
+        assert_eq!(s.name().as_deref(), Some("mock"));
+        assert_eq!(s.processes().len(), 2);
+        assert_eq!(
+            s.last_errors().get(&Pid::from_u32(4)),
+            Some(&Error::PermissionDenied)
+        );
+        assert!(s.refresh_process(Pid::from_u32(2)));
+        assert!(!s.refresh_process(Pid::from_u32(3)));
+        assert_eq!(s.processes_by_exact_name("a").count(), 0);
+
+        #[cfg(not(any(feature = "unknown-ci", feature = "apple-sandbox")))]
+        {
+            let p = s.process(Pid::from_u32(1)).expect("process 1 not found");
+            assert_eq!(p.name(), "c");
+            assert_eq!(p.memory(), 10);
+            assert_eq!(p.disk_usage().written_bytes, 10);
+            assert_eq!(p.disk_usage().total_read_bytes, 40);
+            assert_eq!(s.global_cpu_info().cpu_usage(), 25.);
+            assert_eq!(s.cpus()[0].frequency(), 1_000);
+            assert_eq!(s.cpus()[0].brand(), "brand");
+        }
+    }
+
+    #[cfg(not(any(feature = "unknown-ci", feature = "apple-sandbox")))]
+    #[test]
+    fn check_mock_system_diff_processes() {
+        let with_start_time = |pid, start_time| ProcessSnapshot {
+            start_time,
+            ..snapshot(pid, "p")
+        };
+        let before = MockSystem::builder()
+            .process(with_start_time(1, 10))
+            .process(with_start_time(2, 20))
+            .process(with_start_time(3, 30))
+            .build()
+            .snapshot();
+        // Process 2 ended and its PID was reused, process 3 ended and process 4 started.
+        let s = MockSystem::builder()
+            .process(with_start_time(1, 10))
+            .process(with_start_time(2, 25))
+            .process(with_start_time(4, 40))
+            .build();
+
+        let diff = s.diff_processes(&before);
+        assert_eq!(diff.started, vec![Pid::from_u32(2), Pid::from_u32(4)]);
+        assert_eq!(diff.ended, vec![Pid::from_u32(2), Pid::from_u32(3)]);
+        assert_eq!(s.diff_processes(&s.snapshot()), Default::default());
+    }
+
+    #[cfg(not(any(feature = "unknown-ci", feature = "apple-sandbox")))]
+    #[test]
+    fn check_mock_system_process_tree_memory() {
+        let child = |pid, parent, start_time| ProcessSnapshot {
+            parent: Some(Pid::from_u32(parent)),
+            start_time,
+            ..snapshot(pid, "p")
+        };
+        let s = MockSystem::builder()
+            .process(child(1, 0, 10))
+            .process(child(2, 1, 20))
+            .process(child(3, 2, 30))
+            .process(child(4, 1, 40))
+            // Started before process 1, so its parent PID was reused.
+            .process(child(5, 1, 5))
+            // Parents of each other.
+            .process(child(6, 7, 60))
+            .process(child(7, 6, 60))
+            .build();
+
+        assert_eq!(s.process_tree_memory(Pid::from_u32(1)), 40);
+        assert_eq!(s.process_tree_memory(Pid::from_u32(2)), 20);
+        assert_eq!(s.process_tree_memory(Pid::from_u32(6)), 20);
+        assert_eq!(s.process_tree_memory(Pid::from_u32(8)), 0);
+    }
+
+    #[cfg(not(any(feature = "unknown-ci", feature = "apple-sandbox")))]
+    #[test]
+    fn check_mock_system_sorted_processes() {
+        let process = |pid, memory, cpu_usage| ProcessSnapshot {
+            memory,
+            cpu_usage,
+            ..snapshot(pid, "p")
+        };
+        let s = MockSystem::builder()
+            .process(process(1, 30, 5.))
+            .process(process(2, 10, 50.))
+            .process(process(3, 20, 0.5))
+            .build();
+        let pids = |processes: Vec<&crate::Process>| {
+            processes
+                .iter()
+                .map(|p| p.pid().as_u32())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(pids(s.processes_by_cpu()), vec![2, 1, 3]);
+        assert_eq!(pids(s.processes_by_memory()), vec![1, 3, 2]);
+        assert_eq!(
+            pids(s.processes_sorted_by(|a, b| a.pid().cmp(&b.pid()))),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn check_mock_system_usage_percent() {
+        let s = MockSystem::builder()
+            .total_memory(1_000)
+            .available_memory(750)
+            .total_swap(200)
+            .free_swap(50)
+            .build();
+        assert_eq!(s.memory_usage_percent(), 25.);
+        assert_eq!(s.swap_usage_percent(), 75.);
+
+        let s = MockSystem::builder().build();
+        assert_eq!(s.memory_usage_percent(), 0.);
+        assert_eq!(s.swap_usage_percent(), 0.);
+    }
+
+    #[cfg(not(any(feature = "unknown-ci", feature = "apple-sandbox")))]
+    #[test]
+    fn check_mock_system_process_count() {
+        let s = MockSystem::builder()
+            .process(snapshot(1, "a"))
+            .process(ProcessSnapshot {
+                status: ProcessStatus::Sleep,
+                ..snapshot(2, "b")
+            })
+            .process(snapshot(3, "c"))
+            .build();
+
+        assert_eq!(s.process_count(), 3);
+        assert_eq!(s.running_process_count(), 2);
+        assert_eq!(MockSystem::builder().build().process_count(), 0);
+    }
+
+    #[cfg(all(unix, not(any(feature = "unknown-ci", feature = "apple-sandbox"))))]
+    #[test]
+    fn check_mock_system_process_user_name() {
+        use crate::{Gid, Uid, User};
+
+        let mut s = MockSystem::builder()
+            .process(ProcessSnapshot {
+                user_id: Some(Uid(1_000)),
+                ..snapshot(1, "a")
+            })
+            .process(ProcessSnapshot {
+                user_id: Some(Uid(1_001)),
+                ..snapshot(2, "b")
+            })
+            .process(snapshot(3, "c"))
+            .build();
+        s.users.push(User {
+            uid: Uid(1_000),
+            gid: Gid(1_000),
+            name: "alice".to_owned(),
+            groups: Vec::new(),
+        });
+        let user_name = |pid| {
+            s.process(Pid::from_u32(pid))
+                .and_then(|process| process.user_name(&s))
+        };
+
+        assert_eq!(user_name(1), Some("alice"));
+        // Not in the users list.
+        assert_eq!(user_name(2), None);
+        assert_eq!(user_name(3), None);
+    }
+}
@@ -5,14 +5,17 @@ use crate::{
     sys::{Component, Cpu, Disk, Networks, Process},
 };
 use crate::{
-    CpuRefreshKind, DiskType, DiskUsage, LoadAvg, NetworksIter, Pid, ProcessRefreshKind,
-    ProcessStatus, RefreshKind, Signal, User,
+    Battery, CgroupLimits, CgroupStats, CpuRefreshKind, DiskType, DiskUsage, Error, LoadAvg,
+    NetworksIter, Pid, PressureStats, ProcessDiff, ProcessRefreshKind, ProcessStatus, Protocol,
+    RefreshKind, RefreshTimes, Sensor, Signal, SwapDevice, SystemSnapshot, ThreadInfo, User,
+    Virtualization,
 };
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fmt::Debug;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Contains all the methods of the [`Disk`][crate::Disk] struct.
 ///
@@ -87,6 +90,10 @@ pub trait DiskExt: Debug {
 
     /// Returns the available disk size, in bytes.
     ///
+    /// It is the space usable by non-privileged users (`f_bavail`), which is what `df` displays
+    /// in its "Avail" column. It doesn't include the space reserved for the `root` user, take a
+    /// look at [`DiskExt::free_space`] if you want it.
+    ///
     /// ```no_run
     /// use sysinfo::{DiskExt, System, SystemExt};
     ///
@@ -97,6 +104,54 @@ pub trait DiskExt: Debug {
     /// ```
     fn available_space(&self) -> u64;
 
+    /// Returns the free disk size, in bytes.
+    ///
+    /// Unlike [`DiskExt::available_space`], it includes the space reserved for the `root` user
+    /// (`f_bfree`), so it is greater than or equal to it.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for disk in s.disks() {
+    ///     println!("{}", disk.free_space());
+    /// }
+    /// ```
+    fn free_space(&self) -> u64;
+
+    /// Returns the total number of inodes (`f_files`) of the disk.
+    ///
+    /// A file system can run out of inodes (if it contains a lot of small files for example)
+    /// before running out of space. It is `0` for file systems which don't have a fixed number
+    /// of inodes, like some FUSE ones.
+    ///
+    /// ⚠️ It always returns `0` on Windows.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for disk in s.disks() {
+    ///     println!("{}", disk.total_inodes());
+    /// }
+    /// ```
+    fn total_inodes(&self) -> u64;
+
+    /// Returns the number of inodes available to non-privileged users (`f_favail`).
+    ///
+    /// ⚠️ On macOS, it also includes the inodes reserved for the `root` user (`f_ffree`). It
+    /// always returns `0` on Windows.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for disk in s.disks() {
+    ///     println!("{}", disk.available_inodes());
+    /// }
+    /// ```
+    fn available_inodes(&self) -> u64;
+
     /// Returns `true` if the disk is removable.
     ///
     /// ```no_run
@@ -109,8 +164,75 @@ pub trait DiskExt: Debug {
     /// ```
     fn is_removable(&self) -> bool;
 
+    /// Returns the options the disk is mounted with (`ro`, `noatime`, `nodev`, etc...).
+    ///
+    /// ⚠️ It always returns an empty slice on Windows.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for disk in s.disks() {
+    ///     println!("{:?}", disk.mount_options());
+    /// }
+    /// ```
+    fn mount_options(&self) -> &[String];
+
+    /// Returns the model of the physical device the disk is on, or an empty string if it
+    /// couldn't be retrieved.
+    ///
+    /// ⚠️ It always returns an empty string on FreeBSD and iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for disk in s.disks() {
+    ///     println!("{}", disk.model());
+    /// }
+    /// ```
+    fn model(&self) -> &str;
+
+    /// Returns the serial number of the physical device the disk is on, or an empty string if
+    /// it couldn't be retrieved.
+    ///
+    /// ⚠️ It always returns an empty string on FreeBSD and iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for disk in s.disks() {
+    ///     println!("{}", disk.serial());
+    /// }
+    /// ```
+    fn serial(&self) -> &str;
+
+    /// Returns `true` if the last retrieval of the disk information failed (when the disk was
+    /// listed or with [`DiskExt::refresh`]), meaning that it is outdated.
+    ///
+    /// ⚠️ On Linux, network file systems (like NFS or CIFS) whose server doesn't answer within
+    /// one second are considered as failed instead of blocking the refresh. As long as the
+    /// server doesn't answer, they are not queried again.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_disks();
+    /// for disk in s.disks() {
+    ///     if disk.last_refresh_failed() {
+    ///         println!("{:?} information is outdated", disk.mount_point());
+    ///     }
+    /// }
+    /// ```
+    fn last_refresh_failed(&self) -> bool;
+
     /// Updates the disk' information.
     ///
+    /// Returns `false` if it failed, take a look at [`DiskExt::last_refresh_failed`] for more
+    /// information.
+    ///
     /// ```no_run
     /// use sysinfo::{DiskExt, System, SystemExt};
     ///
@@ -173,6 +295,9 @@ pub trait ProcessExt: Debug {
     ///  1. It is limited to 15 characters.
     ///  2. It is not always the exe name.
     ///
+    /// On **macOS**, it is the name of the executable file. Only when its path cannot be
+    /// retrieved, the name provided by the kernel (limited to 32 characters) is used instead.
+    ///
     /// If you are looking for a specific process, unless you know what you are doing, in most
     /// cases it's better to use [`ProcessExt::exe`] instead (which can be empty sometimes!).
     ///
@@ -188,6 +313,8 @@ pub trait ProcessExt: Debug {
 
     /// Returns the command line.
     ///
+    /// It is only retrieved when [`ProcessRefreshKind::cmd`] is enabled.
+    ///
     /// ```no_run
     /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
@@ -198,6 +325,39 @@ pub trait ProcessExt: Debug {
     /// ```
     fn cmd(&self) -> &[String];
 
+    /// Returns `true` if the command line was too long to be entirely retrieved, in which case
+    /// [`ProcessExt::cmd`] only contains its first arguments.
+    ///
+    /// ⚠️ Only macOS limits the size of the retrieved command line (to `kern.argmax`), so it
+    /// always returns `false` on other platforms.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?} (truncated: {})", process.cmd(), process.is_cmd_truncated());
+    /// }
+    /// ```
+    fn is_cmd_truncated(&self) -> bool;
+
+    /// Returns the command line as a single string, arguments being separated by spaces.
+    ///
+    /// Arguments which are empty or contain whitespace or double quotes are surrounded with double
+    /// quotes. Use [`ProcessExt::cmd`] if you need to work with the arguments themselves.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.cmd_line());
+    /// }
+    /// ```
+    fn cmd_line(&self) -> String {
+        crate::utils::join_cmd(self.cmd())
+    }
+
     /// Returns the path to the process.
     ///
     /// ```no_run
@@ -236,6 +396,8 @@ pub trait ProcessExt: Debug {
 
     /// Returns the environment variables of the process.
     ///
+    /// It is only retrieved when [`ProcessRefreshKind::cmd`] is enabled.
+    ///
     /// ```no_run
     /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
@@ -318,6 +480,24 @@ pub trait ProcessExt: Debug {
     /// ```
     fn status(&self) -> ProcessStatus;
 
+    /// Returns the name of the kernel function (or event) in which the process is sleeping,
+    /// or `None` if it isn't sleeping. It is useful to find what a stuck process is waiting for.
+    ///
+    /// It is only retrieved when [`ProcessRefreshKind::wait_channel`] is enabled.
+    ///
+    /// ⚠️ Only available on Linux and FreeBSD. On Linux, the kernel might hide it (if
+    /// `kptr_restrict` is set for example).
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.wait_channel());
+    /// }
+    /// ```
+    fn wait_channel(&self) -> Option<&str>;
+
     /// Returns the time where the process was started (in seconds) from epoch.
     ///
     /// ```no_run
@@ -361,6 +541,85 @@ pub trait ProcessExt: Debug {
     /// ```
     fn cpu_usage(&self) -> f32;
 
+    /// Returns the total time (user and system) spent on the CPU by the process since it
+    /// started, in milliseconds.
+    ///
+    /// ⚠️ On Windows, it is only updated when the CPU usage is refreshed. It always returns `0`
+    /// on iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} ms", process.cpu_time());
+    /// }
+    /// ```
+    fn cpu_time(&self) -> u64;
+
+    /// Returns the total time (in milliseconds) the process spent waiting for block I/O to
+    /// complete since it started.
+    ///
+    /// Comparing it with [`ProcessExt::cpu_time`] helps telling apart CPU-bound and I/O-bound
+    /// processes.
+    ///
+    /// ⚠️ On Linux, it requires the kernel to account the delays (`delayacct` kernel parameter
+    /// or `kernel.task_delayacct` sysctl), otherwise it always returns `Some(0)`. It always
+    /// returns `None` on other systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?} ms", process.iowait_time());
+    /// }
+    /// ```
+    fn iowait_time(&self) -> Option<u64>;
+
+    /// Returns the average CPU usage of the process since it started, computed from
+    /// [`ProcessExt::cpu_time`] and [`ProcessExt::run_time`].
+    ///
+    /// Unlike [`ProcessExt::cpu_usage`], it doesn't depend on the time between two refreshes.
+    /// Just like it, it can be greater than 100% on a multicore machine.
+    ///
+    /// It returns `0` if the process has been running for less than a second.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}% on average", process.average_cpu_usage());
+    /// }
+    /// ```
+    fn average_cpu_usage(&self) -> f32 {
+        match self.run_time() {
+            0 => 0.,
+            // `cpu_time` is in milliseconds whereas `run_time` is in seconds.
+            run_time => self.cpu_time() as f32 / (run_time as f32 * 10.),
+        }
+    }
+
+    /// Returns the GPU usage of the process (in %) between the last two refreshes of the
+    /// processes, or `None` if it isn't available.
+    ///
+    /// Just like [`ProcessExt::cpu_usage`], at least two refreshes are needed to get a value. It
+    /// is only retrieved when [`ProcessRefreshKind::gpu`] is enabled.
+    ///
+    /// ⚠️ It is only supported on macOS, for the GPUs providing per-process statistics (Apple
+    /// Silicon ones for example). It always returns `None` on other platforms.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("GPU: {:?}%", process.gpu_usage());
+    /// }
+    /// ```
+    fn gpu_usage(&self) -> Option<f32>;
+
     /// Returns number of bytes read and written to disk.
     ///
     /// ⚠️ On Windows and FreeBSD, this method actually returns **ALL** I/O read and written bytes.
@@ -387,6 +646,9 @@ pub trait ProcessExt: Debug {
     /// be retrieved. If you want to get the [`User`] from it, take a look at
     /// [`SystemExt::get_user_by_id`].
     ///
+    /// On Linux and macOS, it is the effective user ID while it is the real one on FreeBSD. For
+    /// the real user ID, take a look at [`ProcessExt::real_user_id`].
+    ///
     /// ```no_run
     /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
@@ -398,7 +660,9 @@ pub trait ProcessExt: Debug {
     /// ```
     fn user_id(&self) -> Option<&Uid>;
 
-    /// Returns the process group ID of the process.
+    /// Returns the real user ID of this process or `None` if this information couldn't be
+    /// retrieved. If you want to get the [`User`] from it, take a look at
+    /// [`SystemExt::get_user_by_id`].
     ///
     /// ⚠️ It always returns `None` on Windows.
     ///
@@ -408,107 +672,552 @@ pub trait ProcessExt: Debug {
     /// let mut s = System::new_all();
     ///
     /// if let Some(process) = s.process(Pid::from(1337)) {
-    ///     eprintln!("Group id for process 1337: {:?}", process.group_id());
+    ///     eprintln!("Real user id for process 1337: {:?}", process.real_user_id());
     /// }
     /// ```
-    fn group_id(&self) -> Option<Gid>;
+    fn real_user_id(&self) -> Option<&Uid>;
 
-    /// Wait for process termination.
+    /// Returns the name of the owner user of this process (the one returned by
+    /// [`ProcessExt::user_id`]) or `None` if it couldn't be found.
+    ///
+    /// The name is resolved when this method is called, from the users list of `system`. It is
+    /// only filled by [`SystemExt::refresh_users_list`] so don't forget to call it first (it
+    /// doesn't need to be called before each processes refresh). For the users which aren't
+    /// listed, take a look at [`SystemExt::get_user_name_by_id`].
     ///
     /// ```no_run
     /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
-    /// let mut s = System::new_all();
-    ///
+    /// let mut s = System::new();
+    /// s.refresh_processes();
+    /// s.refresh_users_list();
     /// if let Some(process) = s.process(Pid::from(1337)) {
-    ///     eprintln!("Waiting for pid 1337");
-    ///     process.wait();
-    ///     eprintln!("Pid 1337 exited");
+    ///     println!("{:?}", process.user_name(&s));
     /// }
     /// ```
-    fn wait(&self);
-}
+    fn user_name<'a, S: SystemExt>(&self, system: &'a S) -> Option<&'a str>
+    where
+        Self: Sized,
+    {
+        self.user_id()
+            .and_then(|user_id| system.get_user_by_id(user_id))
+            .map(|user| user.name())
+    }
 
-/// Contains all the methods of the [`Cpu`][crate::Cpu] struct.
-pub trait CpuExt: Debug {
-    /// Returns this CPU's usage.
+    /// Returns the name of the real user of this process (the one returned by
+    /// [`ProcessExt::real_user_id`]) or `None` if it couldn't be found.
     ///
-    /// Note: You'll need to refresh it at least twice (diff between the first and the second is
-    /// how CPU usage is computed) at first if you want to have a non-zero value.
+    /// Just like [`ProcessExt::user_name`], it is resolved from the users list of `system`
+    /// when this method is called.
+    ///
+    /// ⚠️ It always returns `None` on Windows.
     ///
     /// ```no_run
-    /// use sysinfo::{CpuExt, System, SystemExt};
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
-    /// let s = System::new();
-    /// for cpu in s.cpus() {
-    ///     println!("{}%", cpu.cpu_usage());
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.real_user_name(&s));
     /// }
     /// ```
-    fn cpu_usage(&self) -> f32;
+    fn real_user_name<'a, S: SystemExt>(&self, system: &'a S) -> Option<&'a str>
+    where
+        Self: Sized,
+    {
+        self.real_user_id()
+            .and_then(|user_id| system.get_user_by_id(user_id))
+            .map(|user| user.name())
+    }
 
-    /// Returns this CPU's name.
+    /// Returns the process group ID of the process.
+    ///
+    /// On Linux and macOS, it is the effective group ID while it is the real one on FreeBSD. For
+    /// the real group ID, take a look at [`ProcessExt::real_group_id`].
+    ///
+    /// ⚠️ It always returns `None` on Windows.
     ///
     /// ```no_run
-    /// use sysinfo::{CpuExt, System, SystemExt};
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
-    /// let s = System::new();
-    /// for cpu in s.cpus() {
-    ///     println!("{}", cpu.name());
+    /// let mut s = System::new_all();
+    ///
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     eprintln!("Group id for process 1337: {:?}", process.group_id());
     /// }
     /// ```
-    fn name(&self) -> &str;
+    fn group_id(&self) -> Option<Gid>;
 
-    /// Returns the CPU's vendor id.
+    /// Returns the real group ID of the process.
+    ///
+    /// ⚠️ It always returns `None` on Windows.
     ///
     /// ```no_run
-    /// use sysinfo::{CpuExt, System, SystemExt};
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
-    /// let s = System::new();
-    /// for cpu in s.cpus() {
-    ///     println!("{}", cpu.vendor_id());
+    /// let mut s = System::new_all();
+    ///
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     eprintln!("Real group id for process 1337: {:?}", process.real_group_id());
     /// }
     /// ```
-    fn vendor_id(&self) -> &str;
+    fn real_group_id(&self) -> Option<Gid>;
 
-    /// Returns the CPU's brand.
+    /// Returns `true` if the process is currently being traced (by a debugger for example) or
+    /// `None` if this information couldn't be retrieved.
+    ///
+    /// On Linux, it is only retrieved when [`ProcessRefreshKind::tracer`] is enabled.
+    ///
+    /// ⚠️ It always returns `None` on Windows.
     ///
     /// ```no_run
-    /// use sysinfo::{CpuExt, System, SystemExt};
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
-    /// let s = System::new();
-    /// for cpu in s.cpus() {
-    ///     println!("{}", cpu.brand());
+    /// let mut s = System::new_all();
+    ///
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     eprintln!("Is process 1337 traced? {:?}", process.is_being_traced());
     /// }
     /// ```
-    fn brand(&self) -> &str;
+    fn is_being_traced(&self) -> Option<bool>;
 
-    /// Returns the CPU's frequency.
+    /// Returns the PID of the process tracing this one or `None` if it isn't traced or if this
+    /// information couldn't be retrieved.
+    ///
+    /// On Linux, it is only retrieved when [`ProcessRefreshKind::tracer`] is enabled.
+    ///
+    /// ⚠️ It is only supported on Linux and always returns `None` on other platforms.
     ///
     /// ```no_run
-    /// use sysinfo::{CpuExt, System, SystemExt};
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
-    /// let s = System::new();
-    /// for cpu in s.cpus() {
-    ///     println!("{}", cpu.frequency());
+    /// let mut s = System::new_all();
+    ///
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     eprintln!("Tracer of process 1337: {:?}", process.tracer_pid());
     /// }
     /// ```
-    fn frequency(&self) -> u64;
-}
+    fn tracer_pid(&self) -> Option<Pid>;
 
-/// Contains all the methods of the [`System`][crate::System] type.
-pub trait SystemExt: Sized + Debug + Default + Send + Sync {
-    /// Returns `true` if this OS is supported. Please refer to the
-    /// [crate-level documentation](index.html) to get the list of supported OSes.
+    /// Returns the number of files opened by the process or `None` if this information couldn't
+    /// be retrieved.
     ///
-    /// ```
-    /// use sysinfo::{System, SystemExt};
+    /// It is only retrieved when [`ProcessRefreshKind::open_files`] is enabled, which isn't the
+    /// case by default.
     ///
-    /// if System::IS_SUPPORTED {
-    ///     println!("This OS is supported!");
-    /// } else {
-    ///     println!("This OS isn't supported (yet?).");
-    /// }
-    /// ```
+    /// ⚠️ On Windows, it returns the number of handles opened by the process. On FreeBSD, it
+    /// always returns `None`.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_processes_specifics(ProcessRefreshKind::everything().with_open_files());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.open_files());
+    /// }
+    /// ```
+    fn open_files(&self) -> Option<usize>;
+
+    /// Returns the total number of bytes received and transmitted (in this order) over the network
+    /// interfaces (except the loopback one) of the process' network namespace.
+    ///
+    /// It is only retrieved when [`ProcessRefreshKind::network_usage`] is enabled, which isn't the
+    /// case by default.
+    ///
+    /// ⚠️ It is only available on Linux and only if the process has its own network namespace (like
+    /// processes running in most containers): the counters are the ones of the namespace, so they
+    /// include the traffic of all the processes in this namespace. It returns `None` if the process
+    /// is in the same network namespace as the current process (since the counters would be the
+    /// ones of the whole system), if the namespace couldn't be read (which requires the same
+    /// permissions as [`ProcessExt::environ`]) or on other systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_processes_specifics(ProcessRefreshKind::everything().with_network_usage());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some((received, transmitted)) = process.network_usage() {
+    ///         println!("received: {received} B, transmitted: {transmitted} B");
+    ///     }
+    /// }
+    /// ```
+    fn network_usage(&self) -> Option<(u64, u64)>;
+
+    /// Returns `true` if the process is a 64-bit one, `false` if it's a 32-bit one.
+    ///
+    /// It returns `None` if the information couldn't be retrieved (for example if the process'
+    /// executable cannot be read, like for kernel threads on Linux).
+    ///
+    /// On Linux, it is only retrieved when [`ProcessRefreshKind::is_64bit`] is enabled.
+    ///
+    /// ⚠️ Always `None` on iOS and unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if process.is_64bit() == Some(false) {
+    ///         println!("{} is a 32-bit process", process.name());
+    ///     }
+    /// }
+    /// ```
+    fn is_64bit(&self) -> Option<bool>;
+
+    /// Returns the number of minor page faults (which didn't require loading a page from the
+    /// disk) of the process since it started.
+    ///
+    /// ⚠️ On Windows, minor and major faults cannot be distinguished so all of them are counted
+    /// here. Always `0` on iOS and unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} minor faults", process.minor_faults());
+    /// }
+    /// ```
+    fn minor_faults(&self) -> u64;
+
+    /// Returns the number of major page faults (which required loading a page from the disk) of
+    /// the process since it started.
+    ///
+    /// A high rate of major faults usually means that the process is thrashing against the swap.
+    ///
+    /// ⚠️ Always `0` on Windows (see [`ProcessExt::minor_faults`]), iOS and unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} major faults", process.major_faults());
+    /// }
+    /// ```
+    fn major_faults(&self) -> u64;
+
+    /// Returns the number of threads of the process or `0` if this information couldn't be
+    /// retrieved.
+    ///
+    /// ⚠️ It always returns `0` on iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.thread_count());
+    /// }
+    /// ```
+    fn thread_count(&self) -> usize;
+
+    /// Returns the information of each thread of the process.
+    ///
+    /// It is only retrieved when [`ProcessRefreshKind::threads`] is enabled (the CPU usage of the
+    /// threads requires [`ProcessRefreshKind::cpu`] too).
+    ///
+    /// ⚠️ Only supported on Linux, Android and macOS, it always returns an empty list on other
+    /// systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu().with_threads());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for thread in process.threads() {
+    ///         println!("{}: {:?}", thread.tid(), thread.status());
+    ///     }
+    /// }
+    /// ```
+    fn threads(&self) -> Vec<ThreadInfo>;
+
+    /// Returns the nice value of the process (from `-20`, highest priority, to `19`, lowest
+    /// priority).
+    ///
+    /// ⚠️ It always returns `0` on Windows and iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.nice());
+    /// }
+    /// ```
+    fn nice(&self) -> i32;
+
+    /// Returns the scheduling priority of the process as reported by the OS. Its range and
+    /// meaning depend on the platform (on Windows, it is the base priority of the process).
+    ///
+    /// ⚠️ It always returns `0` on iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.priority());
+    /// }
+    /// ```
+    fn priority(&self) -> i32;
+
+    /// Sets the nice value of the process. `value` is clamped to the `-20..=19` range. Returns
+    /// `true` if it succeeded.
+    ///
+    /// It fails if you don't have enough rights, for example to lower the nice value of a
+    /// process or to change the one of a process owned by another user.
+    ///
+    /// ⚠️ It is not supported on Windows and iOS and always returns `false`.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if !process.set_nice(10) {
+    ///         eprintln!("failed to change the nice value");
+    ///     }
+    /// }
+    /// ```
+    fn set_nice(&self, value: i32) -> bool;
+
+    /// Returns the name of the controlling terminal of the process (like `pts/0` or `ttys001`)
+    /// or `None` if it doesn't have one (daemons for example).
+    ///
+    /// ⚠️ It always returns `None` on Windows and iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.tty());
+    /// }
+    /// ```
+    fn tty(&self) -> Option<&str>;
+
+    /// Returns the ID of the process group of the process.
+    ///
+    /// It can be used to signal all the processes of a job at once.
+    ///
+    /// ⚠️ It always returns `None` on Windows and iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.process_group_id());
+    /// }
+    /// ```
+    fn process_group_id(&self) -> Option<Pid>;
+
+    /// Returns the ID of the session of the process (the process ID of the session leader).
+    ///
+    /// ⚠️ It always returns `None` on Windows and iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.session_id());
+    /// }
+    /// ```
+    fn session_id(&self) -> Option<Pid>;
+
+    /// Wait for process termination.
+    ///
+    /// If you need the exit code of the process, use [`ProcessExt::wait_status`] instead.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    ///
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     eprintln!("Waiting for pid 1337");
+    ///     process.wait();
+    ///     eprintln!("Pid 1337 exited");
+    /// }
+    /// ```
+    fn wait(&self) {
+        self.wait_status();
+    }
+
+    /// Wait for process termination and returns its exit code if it could be retrieved.
+    ///
+    /// On Unix systems, only the exit code of a child process of the current process can be
+    /// retrieved: for other processes, it returns `None` once the process exited. It also returns
+    /// `None` if the process was killed by a signal.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    ///
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     eprintln!("Waiting for pid 1337");
+    ///     let exit_code = process.wait_status();
+    ///     eprintln!("Pid 1337 exited with {:?}", exit_code);
+    /// }
+    /// ```
+    fn wait_status(&self) -> Option<i32>;
+}
+
+/// Contains all the methods of the [`Cpu`][crate::Cpu] struct.
+pub trait CpuExt: Debug {
+    /// Returns this CPU's usage.
+    ///
+    /// Note: You'll need to refresh it at least twice (diff between the first and the second is
+    /// how CPU usage is computed) at first if you want to have a non-zero value.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}%", cpu.cpu_usage());
+    /// }
+    /// ```
+    fn cpu_usage(&self) -> f32;
+
+    /// Returns the share of this CPU's time spent running user space code (in %).
+    ///
+    /// Like [`CpuExt::cpu_usage`], it needs two refreshes to be computed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}%", cpu.user_usage());
+    /// }
+    /// ```
+    fn user_usage(&self) -> f32;
+
+    /// Returns the share of this CPU's time spent running kernel code (in %).
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}%", cpu.system_usage());
+    /// }
+    /// ```
+    fn system_usage(&self) -> f32;
+
+    /// Returns the share of this CPU's time spent running user space code with a positive nice
+    /// value (in %).
+    ///
+    /// ⚠️ Always `0` on Windows.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}%", cpu.nice_usage());
+    /// }
+    /// ```
+    fn nice_usage(&self) -> f32;
+
+    /// Returns the share of this CPU's time spent idle (in %).
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}%", cpu.idle_usage());
+    /// }
+    /// ```
+    fn idle_usage(&self) -> f32;
+
+    /// Returns the last CPU usage values (in %) of this CPU, the oldest first.
+    ///
+    /// The history is disabled by default, use [`SystemExt::set_cpu_usage_history_capacity`]
+    /// to enable it.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.set_cpu_usage_history_capacity(60);
+    /// s.refresh_cpu();
+    /// for cpu in s.cpus() {
+    ///     println!("{:?}", cpu.usage_history());
+    /// }
+    /// ```
+    fn usage_history(&self) -> &[f32];
+
+    /// Returns this CPU's name.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}", cpu.name());
+    /// }
+    /// ```
+    fn name(&self) -> &str;
+
+    /// Returns the CPU's vendor id.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}", cpu.vendor_id());
+    /// }
+    /// ```
+    fn vendor_id(&self) -> &str;
+
+    /// Returns the CPU's brand.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}", cpu.brand());
+    /// }
+    /// ```
+    fn brand(&self) -> &str;
+
+    /// Returns the CPU's frequency.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for cpu in s.cpus() {
+    ///     println!("{}", cpu.frequency());
+    /// }
+    /// ```
+    fn frequency(&self) -> u64;
+}
+
+/// Contains all the methods of the [`System`][crate::System] type.
+pub trait SystemExt: Sized + Debug + Default + Send + Sync {
+    /// Returns `true` if this OS is supported. Please refer to the
+    /// [crate-level documentation](index.html) to get the list of supported OSes.
+    ///
+    /// ```
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// if System::IS_SUPPORTED {
+    ///     println!("This OS is supported!");
+    /// } else {
+    ///     println!("This OS isn't supported (yet?).");
+    /// }
+    /// ```
     const IS_SUPPORTED: bool;
 
     /// Returns the list of the supported signals on this system (used by
@@ -578,6 +1287,89 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn new_with_specifics(refreshes: RefreshKind) -> Self;
 
+    /// Creates a new [`System`] instance in a background thread, refreshes the data
+    /// corresponding to the given [`RefreshKind`] and sends a [`SystemSnapshot`] of it through
+    /// the returned channel.
+    ///
+    /// It allows to keep the (potentially slow) refresh out of latency-sensitive threads, like
+    /// a UI thread. If CPU usage is requested, the data is refreshed a second time 200ms later to
+    /// get meaningful values.
+    ///
+    /// It is only available with the `background-refresh` feature.
+    ///
+    /// [`System`]: crate::System
+    ///
+    /// ```no_run
+    /// use sysinfo::{RefreshKind, System, SystemExt};
+    ///
+    /// let receiver = System::refresh_in_background(RefreshKind::everything());
+    /// // Do some other work in the meantime...
+    /// if let Ok(snapshot) = receiver.recv() {
+    ///     println!("{} processes", snapshot.processes.len());
+    /// }
+    /// ```
+    #[cfg(feature = "background-refresh")]
+    fn refresh_in_background(refreshes: RefreshKind) -> std::sync::mpsc::Receiver<SystemSnapshot>
+    where
+        Self: 'static,
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut s = Self::new_with_specifics(refreshes);
+            let mut cpu_refreshes = RefreshKind::new();
+            if let Some(kind) = refreshes.cpu().filter(|kind| kind.cpu_usage()) {
+                cpu_refreshes = cpu_refreshes.with_cpu(kind);
+            }
+            if let Some(kind) = refreshes.processes().filter(|kind| kind.cpu()) {
+                cpu_refreshes = cpu_refreshes.with_processes(kind);
+            }
+            if cpu_refreshes != RefreshKind::new() {
+                // CPU usage is computed from the difference between two refreshes.
+                std::thread::sleep(Duration::from_millis(200));
+                s.refresh_specifics(cpu_refreshes);
+            }
+            // The receiver might have been dropped in the meantime, nothing to do in this case.
+            let _ = sender.send(s.snapshot());
+        });
+        receiver
+    }
+
+    /// Uses `proc_root` instead of `/proc` to retrieve the processes, memory, CPU, mounted disks,
+    /// uptime, boot time and load average information. It allows to read them from a fixture directory or
+    /// from a relocated procfs (in a chroot for example).
+    ///
+    /// It should be called before any refresh.
+    ///
+    /// ⚠️ Only used on Linux and Android, ignored on other systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new().with_proc_root("/host/proc".into());
+    /// s.refresh_memory();
+    /// ```
+    fn with_proc_root(self, _proc_root: PathBuf) -> Self {
+        self
+    }
+
+    /// Uses `sys_root` instead of `/sys` to retrieve the network interfaces, components, sensors,
+    /// CPU frequency, disk type, battery and cgroup information. It allows to read them from a fixture directory or from a relocated sysfs
+    /// (in a chroot for example).
+    ///
+    /// It should be called before any refresh.
+    ///
+    /// ⚠️ Only used on Linux and Android, ignored on other systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new().with_sys_root("/host/sys".into());
+    /// s.refresh_networks_list();
+    /// ```
+    fn with_sys_root(self, _sys_root: PathBuf) -> Self {
+        self
+    }
+
     /// Refreshes according to the given [`RefreshKind`]. It calls the corresponding
     /// "refresh_" methods.
     ///
@@ -689,6 +1481,27 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
         self.refresh_cpu_specifics(CpuRefreshKind::new().with_cpu_usage())
     }
 
+    /// Refreshes CPUs information twice, `window` apart, so the CPU usage (of the CPUs and of
+    /// [`SystemExt::global_cpu_info`]) is computed over exactly this duration. It is useful to get
+    /// reproducible measurements, whatever the time since the previous refresh.
+    ///
+    /// ⚠️ This method blocks the current thread for `window`, so it shouldn't be called from a UI
+    /// thread. `window` should be at least 200ms to get accurate values.
+    ///
+    /// ```no_run
+    /// use sysinfo::{CpuExt, System, SystemExt};
+    /// use std::time::Duration;
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_cpu_over(Duration::from_secs(1));
+    /// println!("{}%", s.global_cpu_info().cpu_usage());
+    /// ```
+    fn refresh_cpu_over(&mut self, window: Duration) {
+        self.refresh_cpu();
+        std::thread::sleep(window);
+        self.refresh_cpu();
+    }
+
     /// Refreshes CPUs specific information.
     ///
     /// Please note that it doesn't recompute disks list, components list, network interfaces
@@ -702,6 +1515,19 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn refresh_cpu_specifics(&mut self, refresh_kind: CpuRefreshKind);
 
+    /// Sets the number of CPU usage values kept by each CPU (including the global one) on
+    /// every refresh. They can be retrieved with [`CpuExt::usage_history`].
+    ///
+    /// By default it is `0`, meaning that no history is kept.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.set_cpu_usage_history_capacity(60);
+    /// ```
+    fn set_cpu_usage_history_capacity(&mut self, capacity: usize);
+
     /// Refreshes components' temperature.
     ///
     /// ```no_run
@@ -730,6 +1556,13 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     ///
     /// It does the same as `system.refresh_processes_specifics(ProcessRefreshKind::everything())`.
     ///
+    /// It does nothing if it happens sooner than the interval set with
+    /// [`SystemExt::set_process_refresh_interval`], which can be checked with
+    /// [`SystemExt::processes_refresh_skipped`].
+    ///
+    /// Processes which exit while the refresh is running are removed from the list: information
+    /// which was only partially retrieved is never kept.
+    ///
     /// ⚠️ On Linux, `sysinfo` keeps the `stat` files open by default. You can change this behaviour
     /// by using [`set_open_files_limit`][crate::set_open_files_limit].
     ///
@@ -740,11 +1573,18 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// s.refresh_processes();
     /// ```
     fn refresh_processes(&mut self) {
-        self.refresh_processes_specifics(ProcessRefreshKind::everything());
+        self.refresh_processes_specifics(ProcessRefreshKind::everything())
     }
 
     /// Gets all processes and updates the specified information.
     ///
+    /// It does nothing if it happens sooner than the interval set with
+    /// [`SystemExt::set_process_refresh_interval`], which can be checked with
+    /// [`SystemExt::processes_refresh_skipped`].
+    ///
+    /// Processes which exit while the refresh is running are removed from the list: information
+    /// which was only partially retrieved is never kept.
+    ///
     /// ⚠️ On Linux, `sysinfo` keeps the `stat` files open by default. You can change this behaviour
     /// by using [`set_open_files_limit`][crate::set_open_files_limit].
     ///
@@ -756,6 +1596,42 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn refresh_processes_specifics(&mut self, refresh_kind: ProcessRefreshKind);
 
+    /// Sets the minimum interval between two refreshes of the processes list.
+    /// [`SystemExt::refresh_processes`] and [`SystemExt::refresh_processes_specifics`] do nothing
+    /// if they are called again sooner than this interval. It also applies to the processes
+    /// refresh done by [`SystemExt::refresh_all`] and [`SystemExt::refresh_specifics`].
+    ///
+    /// By default it is zero, meaning that the processes are always refreshed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    /// use std::time::Duration;
+    ///
+    /// let mut s = System::new();
+    /// s.set_process_refresh_interval(Duration::from_millis(500));
+    /// loop {
+    ///     // Only refreshes the processes every 500ms at most.
+    ///     s.refresh_processes();
+    /// #   break;
+    /// }
+    /// ```
+    fn set_process_refresh_interval(&mut self, interval: Duration);
+
+    /// Returns `true` if the last processes refresh was skipped because it happened sooner than
+    /// the interval set with [`SystemExt::set_process_refresh_interval`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    /// use std::time::Duration;
+    ///
+    /// let mut s = System::new();
+    /// s.set_process_refresh_interval(Duration::from_secs(1));
+    /// s.refresh_processes();
+    /// s.refresh_processes();
+    /// assert!(s.processes_refresh_skipped());
+    /// ```
+    fn processes_refresh_skipped(&self) -> bool;
+
     /// Refreshes *only* the process corresponding to `pid`. Returns `false` if the process doesn't
     /// exist (it will **NOT** be removed from the processes if it doesn't exist anymore). If it
     /// isn't listed yet, it'll be added.
@@ -785,6 +1661,60 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn refresh_process_specifics(&mut self, pid: Pid, refresh_kind: ProcessRefreshKind) -> bool;
 
+    /// Refreshes *only* the process corresponding to `pid` and returns it, or returns `None` if
+    /// the process doesn't exist (anymore).
+    ///
+    /// It is the same as calling [`SystemExt::refresh_process`] and then
+    /// [`SystemExt::process`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// if let Some(process) = s.refresh_and_get_process(Pid::from(1337)) {
+    ///     println!("{}", process.name());
+    /// }
+    /// ```
+    fn refresh_and_get_process(&mut self, pid: Pid) -> Option<&Process> {
+        if self.refresh_process(pid) {
+            self.process(pid)
+        } else {
+            None
+        }
+    }
+
+    /// Refreshes *only* the processes corresponding to `pids`, without going through the whole
+    /// processes list. Processes which don't exist anymore are **NOT** removed and the ones which
+    /// aren't listed yet are added.
+    ///
+    /// It is the same as calling
+    /// `sys.refresh_pids_specifics(pids, ProcessRefreshKind::everything())`.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_pids(&[Pid::from(1337), Pid::from(1338)]);
+    /// ```
+    fn refresh_pids(&mut self, pids: &[Pid]) {
+        self.refresh_pids_specifics(pids, ProcessRefreshKind::everything());
+    }
+
+    /// Refreshes *only* the processes corresponding to `pids`, without going through the whole
+    /// processes list. Processes which don't exist anymore are **NOT** removed and the ones which
+    /// aren't listed yet are added.
+    ///
+    /// Unlike calling [`SystemExt::refresh_process_specifics`] for each PID, the CPU usage of all
+    /// these processes is computed over the same time interval.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_pids_specifics(&[Pid::from(1337), Pid::from(1338)], ProcessRefreshKind::new());
+    /// ```
+    fn refresh_pids_specifics(&mut self, pids: &[Pid], refresh_kind: ProcessRefreshKind);
+
     /// Refreshes the listed disks' information.
     ///
     /// ```no_run
@@ -799,8 +1729,30 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
         }
     }
 
+    /// Refreshes the information of the disk mounted on `mount_point`.
+    ///
+    /// Returns `false` if no listed disk is mounted there or if the refresh failed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_disk("/".as_ref());
+    /// ```
+    fn refresh_disk(&mut self, mount_point: &Path) -> bool {
+        self.disks_mut()
+            .iter_mut()
+            .find(|disk| disk.mount_point() == mount_point)
+            .map(|disk| disk.refresh())
+            .unwrap_or(false)
+    }
+
     /// The disk list will be emptied then completely recomputed.
     ///
+    /// To not change the list returned by previous versions, pseudo and network file systems
+    /// (like `autofs`, `devfs` or SMB/NFS shares) aren't excluded: use
+    /// [`SystemExt::refresh_disks_list_local_only`] to exclude them.
+    ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
@@ -809,72 +1761,228 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn refresh_disks_list(&mut self);
 
-    /// Refreshes users list.
+    /// Same as [`SystemExt::refresh_disks_list`] but also excludes pseudo and network file systems
+    /// (like `autofs`, `devfs` or SMB/NFS shares).
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_disks_list_local_only();
+    /// ```
+    fn refresh_disks_list_local_only(&mut self);
+
+    /// Same as [`SystemExt::refresh_disks_list`] but doesn't exclude any file system (not even
+    /// the pseudo ones like `proc` or `tmpfs`).
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_disks_list_with_all_file_systems();
+    /// ```
+    fn refresh_disks_list_with_all_file_systems(&mut self);
+
+    /// Refreshes users list.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_users_list();
+    /// ```
+    fn refresh_users_list(&mut self);
+
+    /// Refreshes networks data.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_networks();
+    /// ```
+    ///
+    /// It is a shortcut for:
+    ///
+    /// ```no_run
+    /// use sysinfo::{NetworksExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// let networks = s.networks_mut();
+    /// networks.refresh();
+    /// ```
+    fn refresh_networks(&mut self) {
+        self.networks_mut().refresh();
+    }
+
+    /// The network list will be updated: removing not existing anymore interfaces and adding new
+    /// ones.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_networks_list();
+    /// ```
+    ///
+    /// This is a shortcut for:
+    ///
+    /// ```no_run
+    /// use sysinfo::{NetworksExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// let networks = s.networks_mut();
+    /// networks.refresh_networks_list();
+    /// ```
+    fn refresh_networks_list(&mut self) {
+        self.networks_mut().refresh_networks_list();
+    }
+
+    /// Returns when the memory, CPU and processes information were last refreshed.
+    ///
+    /// It allows to know how stale each of them is, since they can be refreshed separately.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{:?}", s.refresh_times().processes());
+    /// ```
+    fn refresh_times(&self) -> RefreshTimes;
+
+    /// Returns when the system information was last refreshed, or `None` if it never was.
+    ///
+    /// It is the most recent of the values returned by [`SystemExt::refresh_times`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(instant) = s.last_refresh() {
+    ///     println!("refreshed {:?} ago", instant.elapsed());
+    /// }
+    /// ```
+    fn last_refresh(&self) -> Option<Instant> {
+        self.refresh_times().last()
+    }
+
+    /// Returns the process list.
+    ///
+    /// This is the raw accessor to the underlying storage. If you only want to iterate over the
+    /// processes, prefer [`SystemExt::processes_iter`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for (pid, process) in s.processes() {
+    ///     println!("{} {}", pid, process.name());
+    /// }
+    /// ```
+    fn processes(&self) -> &HashMap<Pid, Process>;
+
+    /// Returns the number of processes.
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let mut s = System::new_all();
-    /// s.refresh_users_list();
+    /// let s = System::new_all();
+    /// println!("{} processes", s.process_count());
     /// ```
-    fn refresh_users_list(&mut self);
+    fn process_count(&self) -> usize {
+        self.processes().len()
+    }
 
-    /// Refreshes networks data.
+    /// Returns the number of processes whose status is [`ProcessStatus::Run`].
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let mut s = System::new_all();
-    /// s.refresh_networks();
+    /// let s = System::new_all();
+    /// println!("{} running processes", s.running_process_count());
     /// ```
+    fn running_process_count(&self) -> usize {
+        self.processes()
+            .values()
+            .filter(|process| process.status() == ProcessStatus::Run)
+            .count()
+    }
+
+    /// Returns the value at which the PIDs wrap around: all PIDs are strictly lower than it.
+    /// Returns `None` if there is no such limit or if it cannot be retrieved.
     ///
-    /// It is a shortcut for:
+    /// Combined with [`ProcessExt::start_time`], it allows to detect that a PID was reused.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ Always `None` on Windows and unsupported systems.
     ///
     /// ```no_run
-    /// use sysinfo::{NetworksExt, System, SystemExt};
+    /// use sysinfo::{System, SystemExt};
     ///
-    /// let mut s = System::new_all();
-    /// let networks = s.networks_mut();
-    /// networks.refresh();
+    /// let s = System::new();
+    /// println!("{:?}", s.max_pid());
     /// ```
-    fn refresh_networks(&mut self) {
-        self.networks_mut().refresh();
-    }
+    fn max_pid(&self) -> Option<Pid>;
 
-    /// The network list will be updated: removing not existing anymore interfaces and adding new
-    /// ones.
+    /// Reads the integer `sysctl` value `name` (like `"kern.maxproc"`). Returns `None` if it
+    /// doesn't exist or if it isn't an integer.
+    ///
+    /// This is an escape hatch to retrieve values which aren't provided by `sysinfo`: the
+    /// meaning of the returned value depends on the OS and on its version.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ Only supported on macOS, iOS and FreeBSD, always `None` on other systems.
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let mut s = System::new_all();
-    /// s.refresh_networks_list();
+    /// let s = System::new();
+    /// println!("{:?}", s.read_sysctl("kern.maxproc"));
     /// ```
+    fn read_sysctl(&self, name: &str) -> Option<i64>;
+
+    /// Reads the content of the file at `path`, relative to the `/proc` folder (like
+    /// `"sys/kernel/threads-max"`), with trailing newlines removed. Returns `None` if the file
+    /// cannot be read or if `path` isn't a relative path inside `/proc`: `..` components and the
+    /// symlinks of the processes to the rest of the file system (`root`, `cwd`, `exe`, `fd` and
+    /// `map_files`) are refused.
     ///
-    /// This is a shortcut for:
+    /// This is an escape hatch to retrieve values which aren't provided by `sysinfo`: the
+    /// format of the returned value depends on the kernel version.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ Only supported on Linux and Android, always `None` on other systems.
     ///
     /// ```no_run
-    /// use sysinfo::{NetworksExt, System, SystemExt};
+    /// use sysinfo::{System, SystemExt};
     ///
-    /// let mut s = System::new_all();
-    /// let networks = s.networks_mut();
-    /// networks.refresh_networks_list();
+    /// let s = System::new();
+    /// println!("{:?}", s.read_proc_value("sys/kernel/threads-max"));
     /// ```
-    fn refresh_networks_list(&mut self) {
-        self.networks_mut().refresh_networks_list();
-    }
+    fn read_proc_value(&self, path: &str) -> Option<String>;
 
-    /// Returns the process list.
+    /// Returns an iterator over the processes and their [`Pid`].
     ///
     /// ```no_run
     /// use sysinfo::{ProcessExt, System, SystemExt};
     ///
     /// let s = System::new_all();
-    /// for (pid, process) in s.processes() {
+    /// for (pid, process) in s.processes_iter() {
     ///     println!("{} {}", pid, process.name());
     /// }
     /// ```
-    fn processes(&self) -> &HashMap<Pid, Process>;
+    // FIXME: replace the returned type with `impl Iterator<Item = (Pid, &Process)>` when it's
+    // supported!
+    fn processes_iter<'a>(&'a self) -> Box<dyn Iterator<Item = (Pid, &'a Process)> + 'a> {
+        Box::new(
+            self.processes()
+                .iter()
+                .map(|(pid, process)| (*pid, process)),
+        )
+    }
 
     /// Returns the process corresponding to the given pid or `None` if no such process exists.
     ///
@@ -888,6 +1996,28 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn process(&self, pid: Pid) -> Option<&Process>;
 
+    /// Returns the processes whose information couldn't be retrieved during the last processes
+    /// refresh (with [`SystemExt::refresh_processes`], [`SystemExt::refresh_process`],
+    /// [`SystemExt::refresh_pids`] or their `_specifics` variants), along with the reason.
+    ///
+    /// It allows to understand why some processes are missing from [`SystemExt::processes`].
+    ///
+    /// ⚠️ On Windows and FreeBSD, all processes are retrieved at once by
+    /// [`SystemExt::refresh_processes`] so only [`SystemExt::refresh_process`] and
+    /// [`SystemExt::refresh_pids`] can report errors. On the platforms which aren't supported,
+    /// they report [`Error::Unsupported`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_processes();
+    /// for (pid, error) in s.last_errors() {
+    ///     println!("[{pid}] {error}");
+    /// }
+    /// ```
+    fn last_errors(&self) -> &HashMap<Pid, Error>;
+
     /// Returns an iterator of process containing the given `name`.
     ///
     /// If you want only the processes with exactly the given `name`, take a look at
@@ -950,6 +2080,154 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
         )
     }
 
+    /// Returns the processes sorted with the provided callback.
+    ///
+    /// Internally, it is using the [`slice::sort_by`] function, so please refer to it for
+    /// implementation details.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for process in s.processes_sorted_by(|a, b| a.start_time().cmp(&b.start_time())) {
+    ///     println!("{} {}", process.pid(), process.name());
+    /// }
+    /// ```
+    fn processes_sorted_by<F>(&self, mut compare: F) -> Vec<&Process>
+    where
+        F: FnMut(&Process, &Process) -> std::cmp::Ordering,
+    {
+        let mut processes = self.processes().values().collect::<Vec<_>>();
+        processes.sort_by(|a, b| compare(a, b));
+        processes
+    }
+
+    /// Returns the processes sorted by [`ProcessExt::cpu_usage`], in descending order.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for process in s.processes_by_cpu().iter().take(5) {
+    ///     println!("{}: {}%", process.name(), process.cpu_usage());
+    /// }
+    /// ```
+    fn processes_by_cpu(&self) -> Vec<&Process> {
+        self.processes_sorted_by(|a, b| {
+            b.cpu_usage()
+                .partial_cmp(&a.cpu_usage())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
+
+    /// Returns the processes sorted by [`ProcessExt::memory`], in descending order.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for process in s.processes_by_memory().iter().take(5) {
+    ///     println!("{}: {} bytes", process.name(), process.memory());
+    /// }
+    /// ```
+    fn processes_by_memory(&self) -> Vec<&Process> {
+        self.processes_sorted_by(|a, b| b.memory().cmp(&a.memory()))
+    }
+
+    /// Calls `f` on each process.
+    ///
+    /// If `refresh` is `true`, the processes information is retrieved again and each process is
+    /// given to `f` as soon as it is read, without being stored: it allows to compute aggregated
+    /// values on systems with a lot of processes without keeping all of them in memory. Since no
+    /// previous value is available, the CPU usage of these processes is always `0`. The processes
+    /// stored in the `System` are not modified.
+    ///
+    /// If `refresh` is `false`, `f` is called on the processes already stored in the `System`.
+    ///
+    /// ⚠️ Only Linux supports streaming the processes. On other platforms, if `refresh` is
+    /// `true`, the stored processes are refreshed with [`SystemExt::refresh_processes`] before
+    /// calling `f` on each of them.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// let mut total_memory = 0;
+    /// s.for_each_process(true, |process| total_memory += process.memory());
+    /// println!("{} bytes", total_memory);
+    /// ```
+    fn for_each_process<F: FnMut(&Process)>(&mut self, refresh: bool, f: F) {
+        if refresh {
+            self.refresh_processes();
+        }
+        self.processes().values().for_each(f);
+    }
+
+    /// Returns the memory used by the process `root` and all its descendants, in bytes (the
+    /// sum of their [`ProcessExt::memory`]). Returns `0` if there is no process `root`.
+    ///
+    /// A process started before its parent isn't counted as one of its children since it means
+    /// that the parent PID was reused.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{} bytes", s.process_tree_memory(Pid::from(1337)));
+    /// ```
+    fn process_tree_memory(&self, root: Pid) -> u64 {
+        let root = match self.process(root) {
+            Some(root) => root,
+            None => return 0,
+        };
+        let mut children: HashMap<Pid, Vec<&Process>> = HashMap::new();
+        for process in self.processes().values() {
+            if let Some(parent) = process.parent() {
+                children.entry(parent).or_default().push(process);
+            }
+        }
+
+        let mut visited = HashSet::new();
+        let mut to_visit = vec![root];
+        let mut memory = 0u64;
+        while let Some(process) = to_visit.pop() {
+            if !visited.insert(process.pid()) {
+                continue;
+            }
+            memory = memory.saturating_add(process.memory());
+            if let Some(children) = children.get(&process.pid()) {
+                to_visit.extend(
+                    children
+                        .iter()
+                        .filter(|child| child.start_time() >= process.start_time()),
+                );
+            }
+        }
+        memory
+    }
+
+    /// Returns the process which has a socket bound to the given local `port`. For
+    /// [`Protocol::Tcp`], only listening sockets are considered.
+    ///
+    /// Only processes returned by [`SystemExt::processes`] are looked at, so you need to refresh
+    /// them first. If more than one process shares the socket (after a `fork` for example), the
+    /// one with the lowest PID is returned.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ It always returns `None` on Windows, FreeBSD and iOS. Sockets of processes you don't
+    /// have the permission to inspect are not found.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, Protocol, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process_by_port(8080, Protocol::Tcp) {
+    ///     println!("{} is listening on port 8080", process.name());
+    /// }
+    /// ```
+    fn process_by_port(&self, port: u16, protocol: Protocol) -> Option<&Process>;
+
     /// Returns "global" cpus information (aka the addition of all the CPUs).
     ///
     /// To have up-to-date information, you need to call [`SystemExt::refresh_cpu`] or
@@ -967,6 +2245,13 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
 
     /// Returns the list of the CPUs.
     ///
+    /// The list only contains the logical CPUs (use [`SystemExt::global_cpu_info`] for the
+    /// combined view) sorted in ascending order, so that index `N` maps to logical CPU `N`. The
+    /// order doesn't change between refreshes.
+    ///
+    /// ⚠️ On Linux, CPUs which were offline when the list was first retrieved are not part of
+    /// it, so the following indexes are shifted.
+    ///
     /// By default, the list of cpus is empty until you call [`SystemExt::refresh_cpu`] or
     /// [`SystemExt::refresh_specifics`] with `cpu` enabled.
     ///
@@ -982,6 +2267,23 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn cpus(&self) -> &[Cpu];
 
+    /// Returns the number of logical CPUs.
+    ///
+    /// Unlike [`SystemExt::cpus`], it doesn't require to refresh the CPUs first and doesn't
+    /// populate the CPUs list.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ Always `0` on unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// println!("{} CPUs", s.cpu_count());
+    /// ```
+    fn cpu_count(&self) -> usize;
+
     /// Returns the number of physical cores on the CPU or `None` if it couldn't get it.
     ///
     /// In case there are multiple CPUs, it will combine the physical core count of all the CPUs.
@@ -996,6 +2298,40 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn physical_core_count(&self) -> Option<usize>;
 
+    /// Returns the number of context switches which happened on the system between the last two
+    /// CPU refreshes.
+    ///
+    /// ⚠️ Always `None` on macOS and iOS since the system doesn't provide this information, and
+    /// on unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_cpu();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_cpu();
+    /// println!("{:?} context switches", s.context_switches());
+    /// ```
+    fn context_switches(&self) -> Option<u64>;
+
+    /// Returns the number of interrupts which happened on the system between the last two CPU
+    /// refreshes.
+    ///
+    /// ⚠️ Always `None` on macOS and iOS since the system doesn't provide this information, and
+    /// on unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_cpu();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_cpu();
+    /// println!("{:?} interrupts", s.interrupts());
+    /// ```
+    fn interrupts(&self) -> Option<u64>;
+
     /// Returns the RAM size in bytes.
     ///
     /// ```no_run
@@ -1020,63 +2356,209 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// let s = System::new_all();
     /// println!("{} bytes", s.free_memory());
     /// ```
-    fn free_memory(&self) -> u64;
+    fn free_memory(&self) -> u64;
+
+    /// Returns the amount of available RAM in bytes.
+    ///
+    /// Generally, "free" memory refers to unallocated memory whereas "available" memory refers to
+    /// memory that is available for (re)use.
+    ///
+    /// ⚠️ Windows and FreeBSD don't report "available" memory so [`SystemExt::free_memory`]
+    /// returns the same value as this method.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{} bytes", s.available_memory());
+    /// ```
+    fn available_memory(&self) -> u64;
+
+    /// Returns the amount of used RAM in bytes.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{} bytes", s.used_memory());
+    /// ```
+    fn used_memory(&self) -> u64;
+
+    /// Returns the percentage of RAM which isn't available, computed from
+    /// [`SystemExt::total_memory`] and [`SystemExt::available_memory`]. Returns `0` if the total
+    /// memory is `0`.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{}%", s.memory_usage_percent());
+    /// ```
+    fn memory_usage_percent(&self) -> f32 {
+        crate::utils::usage_percent(
+            self.total_memory().saturating_sub(self.available_memory()),
+            self.total_memory(),
+        )
+    }
+
+    /// Returns the SWAP size in bytes.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{} bytes", s.total_swap());
+    /// ```
+    fn total_swap(&self) -> u64;
+
+    /// Returns the amount of free SWAP in bytes.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{} bytes", s.free_swap());
+    /// ```
+    fn free_swap(&self) -> u64;
+
+    /// Returns the amount of used SWAP in bytes.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{} bytes", s.used_swap());
+    /// ```
+    fn used_swap(&self) -> u64;
+
+    /// Returns the percentage of SWAP which is used, computed from [`SystemExt::total_swap`] and
+    /// [`SystemExt::used_swap`]. Returns `0` if there is no SWAP.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{}%", s.swap_usage_percent());
+    /// ```
+    fn swap_usage_percent(&self) -> f32 {
+        crate::utils::usage_percent(self.used_swap(), self.total_swap())
+    }
+
+    /// Returns the list of the swap areas (partitions or files) of the system.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ Always empty on Windows, iOS and unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for device in s.swap_devices() {
+    ///     println!("{:?}: {:?}/{} bytes", device.path(), device.used(), device.total());
+    /// }
+    /// ```
+    fn swap_devices(&self) -> Vec<SwapDevice>;
+
+    /// Returns `true` if the swap is encrypted. If there are multiple swap areas, all of them
+    /// need to be encrypted. Returns `None` if it cannot be determined (or if there is no swap).
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ On Linux, only swap areas on dm-crypt devices (directly or through other device mapper
+    /// layers like LVM) are considered encrypted. On FreeBSD, only GELI and GBDE devices are. On
+    /// Windows, it only checks whether the paging file encryption is enabled.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// println!("swap encrypted: {:?}", s.is_swap_encrypted());
+    /// ```
+    fn is_swap_encrypted(&self) -> Option<bool>;
 
-    /// Returns the amount of available RAM in bytes.
+    /// Returns the size of a memory page, in bytes.
     ///
-    /// Generally, "free" memory refers to unallocated memory whereas "available" memory refers to
-    /// memory that is available for (re)use.
+    /// It is needed to interpret values expressed in pages, like the fields of
+    /// `/proc/[pid]/statm` on Linux.
     ///
-    /// ⚠️ Windows and FreeBSD don't report "available" memory so [`SystemExt::free_memory`]
-    /// returns the same value as this method.
+    /// ⚠️ Always `0` on unsupported systems.
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let s = System::new_all();
-    /// println!("{} bytes", s.available_memory());
+    /// let s = System::new();
+    /// println!("{} bytes", s.page_size());
     /// ```
-    fn available_memory(&self) -> u64;
+    fn page_size(&self) -> u64;
 
-    /// Returns the amount of used RAM in bytes.
+    /// Returns the number of pages swapped in per second between the last two calls to
+    /// [`SystemExt::refresh_memory`].
+    ///
+    /// ⚠️ At least two refreshes are needed to get a meaningful value. It always returns `0` on
+    /// Windows.
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let s = System::new_all();
-    /// println!("{} bytes", s.used_memory());
+    /// let mut s = System::new();
+    /// s.refresh_memory();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_memory();
+    /// println!("{} pages/s", s.swap_in_per_second());
     /// ```
-    fn used_memory(&self) -> u64;
+    fn swap_in_per_second(&self) -> f64;
 
-    /// Returns the SWAP size in bytes.
+    /// Returns the number of pages swapped out per second between the last two calls to
+    /// [`SystemExt::refresh_memory`].
+    ///
+    /// ⚠️ At least two refreshes are needed to get a meaningful value. It always returns `0` on
+    /// Windows.
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let s = System::new_all();
-    /// println!("{} bytes", s.total_swap());
+    /// let mut s = System::new();
+    /// s.refresh_memory();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_memory();
+    /// println!("{} pages/s", s.swap_out_per_second());
     /// ```
-    fn total_swap(&self) -> u64;
+    fn swap_out_per_second(&self) -> f64;
 
-    /// Returns the amount of free SWAP in bytes.
+    /// Returns the number of pages swapped in between the last two calls to
+    /// [`SystemExt::refresh_memory`].
+    ///
+    /// ⚠️ At least two refreshes are needed to get a meaningful value. It always returns `0` on
+    /// Windows.
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let s = System::new_all();
-    /// println!("{} bytes", s.free_swap());
+    /// let mut s = System::new();
+    /// s.refresh_memory();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_memory();
+    /// println!("{} pages", s.swap_in());
     /// ```
-    fn free_swap(&self) -> u64;
+    fn swap_in(&self) -> u64;
 
-    /// Returns the amount of used SWAP in bytes.
+    /// Returns the number of pages swapped out between the last two calls to
+    /// [`SystemExt::refresh_memory`].
+    ///
+    /// ⚠️ At least two refreshes are needed to get a meaningful value. It always returns `0` on
+    /// Windows.
     ///
     /// ```no_run
     /// use sysinfo::{System, SystemExt};
     ///
-    /// let s = System::new_all();
-    /// println!("{} bytes", s.used_swap());
+    /// let mut s = System::new();
+    /// s.refresh_memory();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_memory();
+    /// println!("{} pages", s.swap_out());
     /// ```
-    fn used_swap(&self) -> u64;
+    fn swap_out(&self) -> u64;
 
     /// Returns the components list.
     ///
@@ -1102,6 +2584,73 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn components_mut(&mut self) -> &mut [Component];
 
+    /// Returns `false` if `sysinfo` couldn't access the source of the components' information
+    /// (for example because the SMC connection couldn't be opened on macOS or because of a
+    /// sandbox), meaning that [`SystemExt::components`] will always be empty. If it returns
+    /// `true`, an empty components list means that there is no sensor on this system.
+    ///
+    /// ⚠️ It should be called after [`SystemExt::refresh_components_list`], otherwise it might
+    /// return `false` even if the sensors are available.
+    ///
+    /// ⚠️ On Windows and FreeBSD, it isn't possible to make the difference between the two cases,
+    /// so it returns `false` if no component was found.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_components_list();
+    /// if !s.sensors_available() {
+    ///     println!("cannot access the sensors");
+    /// }
+    /// ```
+    fn sensors_available(&self) -> bool;
+
+    /// Returns the CPU die temperature (in celsius degree), computed from the components list.
+    /// If the CPU has more than one die sensor (one per package or per core for example), the
+    /// average of their temperatures is returned.
+    ///
+    /// ⚠️ It should be called after [`SystemExt::refresh_components_list`] (and
+    /// [`SystemExt::refresh_components`] to get up-to-date values).
+    ///
+    /// ⚠️ Always `None` on Windows, iOS and unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_components_list();
+    /// if let Some(temperature) = s.cpu_temperature() {
+    ///     println!("CPU temperature: {temperature}°C");
+    /// }
+    /// ```
+    fn cpu_temperature(&self) -> Option<f32>;
+
+    /// Returns the hardware sensors of the system: the temperature ones from the components
+    /// list, along with the voltage, current and fan ones when they are available.
+    ///
+    /// **Important**: apart from the temperatures, this information is computed every time this
+    /// function is called. To get up-to-date temperatures, call
+    /// [`SystemExt::refresh_components_list`] (and [`SystemExt::refresh_components`]) first.
+    ///
+    /// ⚠️ Voltage, current and fan sensors are only available on Linux and on macOS with Intel
+    /// CPUs.
+    ///
+    /// ```no_run
+    /// use sysinfo::{SensorKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_components_list();
+    /// for sensor in s.sensors() {
+    ///     if sensor.kind() == SensorKind::Voltage {
+    ///         println!("{}: {}V", sensor.label(), sensor.value());
+    ///     }
+    /// }
+    /// ```
+    fn sensors(&self) -> Vec<Sensor> {
+        Sensor::temperatures(self.components())
+    }
+
     /// Returns the users list.
     ///
     /// ```no_run
@@ -1138,6 +2687,46 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn disks_mut(&mut self) -> &mut [Disk];
 
+    /// Returns all the mounted file systems, including the pseudo and network ones (like
+    /// `tmpfs`, `overlay` or NFS shares) which are excluded from [`SystemExt::disks`].
+    ///
+    /// Unlike [`SystemExt::refresh_disks_list_with_all_file_systems`], it doesn't change the
+    /// list returned by [`SystemExt::disks`].
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for fs in s.file_systems() {
+    ///     println!("{:?}: {} bytes available", fs.mount_point(), fs.available_space());
+    /// }
+    /// ```
+    fn file_systems(&self) -> Vec<Disk>;
+
+    /// Returns the disk containing the given `path`, meaning the one with the longest mount
+    /// point containing it.
+    ///
+    /// `path` is canonicalized first, so it can be relative or contain symlinks. Returns `None`
+    /// if it doesn't exist or if its disk isn't in the disks list.
+    ///
+    /// ```no_run
+    /// use sysinfo::{DiskExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(disk) = s.disk_for_path("./Cargo.toml".as_ref()) {
+    ///     println!("{} bytes available", disk.available_space());
+    /// }
+    /// ```
+    fn disk_for_path(&self, path: &Path) -> Option<&Disk> {
+        let path = crate::utils::strip_verbatim_prefix(path.canonicalize().ok()?);
+        self.disks()
+            .iter()
+            .filter(|disk| path.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    }
+
     /// Sort the disk list with the provided callback.
     ///
     /// Internally, it is using the [`slice::sort_unstable_by`] function, so please refer to it
@@ -1214,6 +2803,171 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn load_average(&self) -> LoadAvg;
 
+    /// Returns the system load average value divided by the number of logical CPUs (as
+    /// returned by [`SystemExt::cpu_count`]), so that a value around `1.0` means that the
+    /// system is fully loaded, whatever the number of CPUs.
+    ///
+    /// Returns `None` if the number of CPUs couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(load_avg) = s.load_average_per_core() {
+    ///     println!(
+    ///         "one minute: {}, five minutes: {}, fifteen minutes: {}",
+    ///         load_avg.one,
+    ///         load_avg.five,
+    ///         load_avg.fifteen,
+    ///     );
+    /// }
+    /// ```
+    fn load_average_per_core(&self) -> Option<LoadAvg> {
+        self.load_average().per_core(self.cpu_count())
+    }
+
+    /// Returns the CPU and memory usage of the given cgroup (v2) or `None` if it doesn't exist.
+    ///
+    /// `path` is relative to the root of the cgroup hierarchy, as displayed in
+    /// `/proc/[pid]/cgroup` (`/system.slice/sshd.service` for example). `None` is returned if it
+    /// contains `..` components.
+    ///
+    /// ⚠️ It is only supported on Linux and always returns `None` on other platforms.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(stats) = s.cgroup_stats("/system.slice".as_ref()) {
+    ///     println!("{:?}", stats);
+    /// }
+    /// ```
+    fn cgroup_stats(&self, path: &Path) -> Option<CgroupStats>;
+
+    /// Returns the memory and CPU limits of the cgroup (v1 or v2) the current process is running
+    /// in, or `None` if there is no memory limit (when not running in a container for example).
+    ///
+    /// When running in a container, [`SystemExt::total_memory`] and [`SystemExt::free_memory`]
+    /// return the host's values, so you should prefer the ones returned by this method.
+    ///
+    /// The cgroup is the one listed in `/proc/self/cgroup`. Limits greater than the total memory
+    /// of the system are ignored.
+    ///
+    /// ⚠️ It is only supported on Linux and always returns `None` on other platforms.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(limits) = s.cgroup_limits() {
+    ///     println!("{:?}", limits);
+    /// }
+    /// ```
+    fn cgroup_limits(&self) -> Option<CgroupLimits>;
+
+    /// Returns the pressure stall information (PSI) of the CPU, memory and I/O resources, or
+    /// `None` if the kernel doesn't provide it.
+    ///
+    /// It is a better indicator of saturation than [`SystemExt::load_average`]. It is updated
+    /// when the CPU information is refreshed (with [`SystemExt::refresh_cpu`] for example).
+    ///
+    /// ⚠️ It is only supported on Linux (4.20 and later) and always returns `None` on other
+    /// platforms.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_cpu();
+    /// if let Some(pressure) = s.pressure() {
+    ///     println!("{:?}", pressure.io);
+    /// }
+    /// ```
+    fn pressure(&self) -> Option<PressureStats>;
+
+    /// Returns the charge information of the battery of the system, or `None` if there is no
+    /// battery (on desktops for example).
+    ///
+    /// If the system has more than one battery, only the first one is returned.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ It always returns `None` on iOS.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(battery) = s.battery() {
+    ///     println!("{}%", battery.percentage());
+    /// }
+    /// ```
+    fn battery(&self) -> Option<Battery>;
+
+    /// Returns `true` if the system is powered by an external power source (an AC adapter),
+    /// `false` if it runs on battery and `None` if this information isn't available (on
+    /// desktops for example).
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ It always returns `None` on iOS and unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if s.on_ac_power() == Some(false) {
+    ///     println!("running on battery");
+    /// }
+    /// ```
+    fn on_ac_power(&self) -> Option<bool>;
+
+    /// Returns an owned copy of the current processes, CPUs, disks, networks and memory
+    /// information.
+    ///
+    /// It doesn't refresh anything: it contains the information retrieved by the last refreshes.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// let snapshot = s.snapshot();
+    /// println!("{} processes", snapshot.processes.len());
+    /// ```
+    fn snapshot(&self) -> SystemSnapshot {
+        SystemSnapshot::new(self)
+    }
+
+    /// Returns the processes which started and ended since `previous` was created (with
+    /// [`SystemExt::snapshot`]).
+    ///
+    /// Processes are compared with their PID and their start time, so a PID reused by a new
+    /// process is listed both as ended and as started.
+    ///
+    /// It doesn't refresh anything, so [`SystemExt::refresh_processes`] needs to be called
+    /// between the two.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// let snapshot = s.snapshot();
+    /// s.refresh_processes();
+    /// let diff = s.diff_processes(&snapshot);
+    /// println!("{} new processes", diff.started.len());
+    /// ```
+    fn diff_processes(&self, previous: &SystemSnapshot) -> ProcessDiff {
+        ProcessDiff::new(
+            previous
+                .processes
+                .iter()
+                .map(|(pid, process)| (*pid, process.start_time)),
+            self.processes()
+                .iter()
+                .map(|(pid, process)| (*pid, process.start_time())),
+        )
+    }
+
     /// Returns the system name.
     ///
     /// **Important**: this information is computed every time this function is called.
@@ -1291,6 +3045,22 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     /// ```
     fn host_name(&self) -> Option<String>;
 
+    /// Returns the hypervisor the system is running on, or `None` if it is running on a physical
+    /// machine.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ⚠️ On macOS, only Intel machines can identify the hypervisor, Apple Silicon ones return
+    /// [`Virtualization::Other`]. It always returns `None` on iOS and unsupported systems.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// println!("Virtualization: {:?}", s.virtualization());
+    /// ```
+    fn virtualization(&self) -> Option<Virtualization>;
+
     /// Returns the [`User`] matching the given `user_id`.
     ///
     /// **Important**: The user list must be filled before using this method, otherwise it will
@@ -1319,10 +3089,72 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
     fn get_user_by_id(&self, user_id: &Uid) -> Option<&User> {
         self.users().iter().find(|user| user.id() == user_id)
     }
+
+    /// Returns the name of the user with the given ID or `None` if it couldn't be found.
+    ///
+    /// It first looks into the users list (which is only updated when calling
+    /// [`SystemExt::refresh_users_list`]) and then falls back to asking the system directly
+    /// (with `getpwuid_r` on Unix systems), which allows to find users that are not listed
+    /// there. The answers from the system are cached until the next call to
+    /// [`SystemExt::refresh_users_list`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    ///
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some(user_id) = process.user_id() {
+    ///         eprintln!("User for process 1337: {:?}", s.get_user_name_by_id(user_id));
+    ///     }
+    /// }
+    /// ```
+    fn get_user_name_by_id(&self, user_id: &Uid) -> Option<String> {
+        self.get_user_by_id(user_id)
+            .map(|user| user.name().to_owned())
+    }
+
+    /// Returns the names of the users currently logged in (read from `utmpx`). Users logged in
+    /// more than once are only listed once.
+    ///
+    /// ⚠️ It always returns an empty list on Windows, Android and on Linux targets not using
+    /// glibc.
+    ///
+    /// **Important**: this information is computed every time this function is called.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for user in s.logged_in_users() {
+    ///     println!("{}", user);
+    /// }
+    /// ```
+    fn logged_in_users(&self) -> Vec<String> {
+        crate::utils::get_logged_in_users()
+    }
 }
 
 /// Getting volume of received and transmitted data.
 pub trait NetworkExt: Debug {
+    /// Returns `false` if the interface wasn't found during the last call to
+    /// [`NetworksExt::refresh_networks_list`].
+    ///
+    /// An interface which disappeared isn't removed right away: it is kept for a few calls to
+    /// [`NetworksExt::refresh_networks_list`], with the total values it had when it was last
+    /// seen and without any new traffic.
+    ///
+    /// ```no_run
+    /// use sysinfo::{NetworkExt, NetworksExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// let networks = s.networks();
+    /// for (interface_name, network) in networks {
+    ///     println!("{}: active: {}", interface_name, network.is_active());
+    /// }
+    /// ```
+    fn is_active(&self) -> bool;
+
     /// Returns the number of received bytes since the last refresh.
     ///
     /// ```no_run
@@ -1375,6 +3207,40 @@ pub trait NetworkExt: Debug {
     /// ```
     fn total_transmitted(&self) -> u64;
 
+    /// Returns the number of received bytes per second between the last two refreshes.
+    ///
+    /// Returns `0` until the network interface was refreshed at least once after being added to
+    /// the list.
+    ///
+    /// ```no_run
+    /// use sysinfo::{NetworkExt, NetworksExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_networks();
+    /// for (interface_name, network) in s.networks() {
+    ///     println!("in: {} B/s", network.received_per_second());
+    /// }
+    /// ```
+    fn received_per_second(&self) -> f64;
+
+    /// Returns the number of transmitted bytes per second between the last two refreshes.
+    ///
+    /// Returns `0` until the network interface was refreshed at least once after being added to
+    /// the list.
+    ///
+    /// ```no_run
+    /// use sysinfo::{NetworkExt, NetworksExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// std::thread::sleep(std::time::Duration::from_secs(1));
+    /// s.refresh_networks();
+    /// for (interface_name, network) in s.networks() {
+    ///     println!("out: {} B/s", network.transmitted_per_second());
+    /// }
+    /// ```
+    fn transmitted_per_second(&self) -> f64;
+
     /// Returns the number of incoming packets since the last refresh.
     ///
     /// ```no_run
@@ -1493,10 +3359,13 @@ pub trait NetworksExt: Debug {
     ///     println!("in: {} B", network.received());
     /// }
     /// ```
-    fn iter(&self) -> NetworksIter;
+    fn iter(&self) -> NetworksIter<'_>;
 
     /// Refreshes the network interfaces list.
     ///
+    /// The interfaces which disappeared are kept (and marked as inactive) for a few calls before
+    /// being removed. Take a look at [`NetworkExt::is_active`] for more information.
+    ///
     /// ```no_run
     /// use sysinfo::{NetworksExt, System, SystemExt};
     ///
@@ -9,6 +9,11 @@ impl Cpu {
     pub(crate) fn new() -> Cpu {
         Cpu {}
     }
+
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(_snapshot: &crate::CpuSnapshot) -> Cpu {
+        Cpu::new()
+    }
 }
 
 impl CpuExt for Cpu {
@@ -16,6 +21,26 @@ impl CpuExt for Cpu {
         0.0
     }
 
+    fn user_usage(&self) -> f32 {
+        0.0
+    }
+
+    fn system_usage(&self) -> f32 {
+        0.0
+    }
+
+    fn nice_usage(&self) -> f32 {
+        0.0
+    }
+
+    fn idle_usage(&self) -> f32 {
+        0.0
+    }
+
+    fn usage_history(&self) -> &[f32] {
+        &[]
+    }
+
     fn name(&self) -> &str {
         ""
     }
@@ -32,10 +32,38 @@ impl DiskExt for Disk {
         0
     }
 
+    fn free_space(&self) -> u64 {
+        0
+    }
+
+    fn total_inodes(&self) -> u64 {
+        0
+    }
+
+    fn available_inodes(&self) -> u64 {
+        0
+    }
+
     fn is_removable(&self) -> bool {
         false
     }
 
+    fn mount_options(&self) -> &[String] {
+        &[]
+    }
+
+    fn model(&self) -> &str {
+        ""
+    }
+
+    fn serial(&self) -> &str {
+        ""
+    }
+
+    fn last_refresh_failed(&self) -> bool {
+        false
+    }
+
     fn refresh(&mut self) -> bool {
         true
     }
@@ -18,7 +18,7 @@ impl Networks {
 }
 
 impl NetworksExt for Networks {
-    fn iter(&self) -> NetworksIter {
+    fn iter(&self) -> NetworksIter<'_> {
         NetworksIter::new(self.interfaces.iter())
     }
 
@@ -31,6 +31,10 @@ impl NetworksExt for Networks {
 pub struct NetworkData;
 
 impl NetworkExt for NetworkData {
+    fn is_active(&self) -> bool {
+        false
+    }
+
     fn received(&self) -> u64 {
         0
     }
@@ -47,6 +51,14 @@ impl NetworkExt for NetworkData {
         0
     }
 
+    fn received_per_second(&self) -> f64 {
+        0.
+    }
+
+    fn transmitted_per_second(&self) -> f64 {
+        0.
+    }
+
     fn packets_received(&self) -> u64 {
         0
     }
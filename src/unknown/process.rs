@@ -1,6 +1,6 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use crate::{DiskUsage, Gid, Pid, ProcessExt, ProcessStatus, Signal, Uid};
+use crate::{DiskUsage, Gid, Pid, ProcessExt, ProcessStatus, Signal, ThreadInfo, Uid};
 
 use std::fmt;
 use std::path::Path;
@@ -17,6 +17,16 @@ pub struct Process {
     parent: Option<Pid>,
 }
 
+impl Process {
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::ProcessSnapshot) -> Process {
+        Process {
+            pid: snapshot.pid,
+            parent: snapshot.parent,
+        }
+    }
+}
+
 impl ProcessExt for Process {
     fn kill_with(&self, _signal: Signal) -> Option<bool> {
         None
@@ -30,6 +40,10 @@ impl ProcessExt for Process {
         &[]
     }
 
+    fn is_cmd_truncated(&self) -> bool {
+        false
+    }
+
     fn exe(&self) -> &Path {
         Path::new("")
     }
@@ -66,6 +80,10 @@ impl ProcessExt for Process {
         ProcessStatus::Unknown(0)
     }
 
+    fn wait_channel(&self) -> Option<&str> {
+        None
+    }
+
     fn start_time(&self) -> u64 {
         0
     }
@@ -78,6 +96,18 @@ impl ProcessExt for Process {
         0.0
     }
 
+    fn cpu_time(&self) -> u64 {
+        0
+    }
+
+    fn iowait_time(&self) -> Option<u64> {
+        None
+    }
+
+    fn gpu_usage(&self) -> Option<f32> {
+        None
+    }
+
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage::default()
     }
@@ -86,9 +116,79 @@ impl ProcessExt for Process {
         None
     }
 
+    fn real_user_id(&self) -> Option<&Uid> {
+        None
+    }
+
     fn group_id(&self) -> Option<Gid> {
         None
     }
 
-    fn wait(&self) {}
+    fn real_group_id(&self) -> Option<Gid> {
+        None
+    }
+
+    fn is_being_traced(&self) -> Option<bool> {
+        None
+    }
+
+    fn tracer_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    fn open_files(&self) -> Option<usize> {
+        None
+    }
+
+    fn network_usage(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    fn is_64bit(&self) -> Option<bool> {
+        None
+    }
+
+    fn minor_faults(&self) -> u64 {
+        0
+    }
+
+    fn major_faults(&self) -> u64 {
+        0
+    }
+
+    fn thread_count(&self) -> usize {
+        0
+    }
+
+    fn threads(&self) -> Vec<ThreadInfo> {
+        Vec::new()
+    }
+
+    fn nice(&self) -> i32 {
+        0
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn set_nice(&self, _value: i32) -> bool {
+        false
+    }
+
+    fn tty(&self) -> Option<&str> {
+        None
+    }
+
+    fn process_group_id(&self) -> Option<Pid> {
+        None
+    }
+
+    fn session_id(&self) -> Option<Pid> {
+        None
+    }
+
+    fn wait_status(&self) -> Option<i32> {
+        None
+    }
 }
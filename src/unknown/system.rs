@@ -2,10 +2,14 @@
 
 use crate::{
     sys::{component::Component, Cpu, Disk, Networks, Process},
-    CpuRefreshKind, LoadAvg, Pid, ProcessRefreshKind, RefreshKind, SystemExt, User,
+    Battery, CgroupLimits, CgroupStats, CpuRefreshKind, Error, LoadAvg, Pid, PressureStats,
+    ProcessRefreshKind, Protocol, RefreshKind, RefreshTimes, SwapDevice, SystemExt, User,
+    Virtualization,
 };
 
 use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 
 declare_signals! {
     (),
@@ -15,6 +19,7 @@ declare_signals! {
 #[doc = include_str!("../../md_doc/system.md")]
 pub struct System {
     processes_list: HashMap<Pid, Process>,
+    process_errors: HashMap<Pid, Error>,
     networks: Networks,
     global_cpu: Cpu,
 }
@@ -26,6 +31,7 @@ impl SystemExt for System {
     fn new_with_specifics(_: RefreshKind) -> System {
         System {
             processes_list: Default::default(),
+            process_errors: Default::default(),
             networks: Networks::new(),
             global_cpu: Cpu::new(),
         }
@@ -35,16 +41,38 @@ impl SystemExt for System {
 
     fn refresh_cpu_specifics(&mut self, _refresh_kind: CpuRefreshKind) {}
 
+    fn set_cpu_usage_history_capacity(&mut self, _capacity: usize) {}
+
     fn refresh_components_list(&mut self) {}
 
     fn refresh_processes_specifics(&mut self, _refresh_kind: ProcessRefreshKind) {}
 
-    fn refresh_process_specifics(&mut self, _pid: Pid, _refresh_kind: ProcessRefreshKind) -> bool {
+    fn set_process_refresh_interval(&mut self, _interval: Duration) {}
+
+    fn processes_refresh_skipped(&self) -> bool {
+        false
+    }
+
+    fn refresh_times(&self) -> RefreshTimes {
+        RefreshTimes::default()
+    }
+
+    fn refresh_process_specifics(&mut self, pid: Pid, _refresh_kind: ProcessRefreshKind) -> bool {
+        self.process_errors.clear();
+        self.process_errors.insert(pid, Error::Unsupported);
         false
     }
 
+    fn refresh_pids_specifics(&mut self, pids: &[Pid], _refresh_kind: ProcessRefreshKind) {
+        self.process_errors = pids.iter().map(|pid| (*pid, Error::Unsupported)).collect();
+    }
+
     fn refresh_disks_list(&mut self) {}
 
+    fn refresh_disks_list_local_only(&mut self) {}
+
+    fn refresh_disks_list_with_all_file_systems(&mut self) {}
+
     fn refresh_users_list(&mut self) {}
 
     // COMMON PART
@@ -59,6 +87,14 @@ impl SystemExt for System {
         None
     }
 
+    fn last_errors(&self) -> &HashMap<Pid, Error> {
+        &self.process_errors
+    }
+
+    fn process_by_port(&self, _port: u16, _protocol: Protocol) -> Option<&Process> {
+        None
+    }
+
     fn networks(&self) -> &Networks {
         &self.networks
     }
@@ -75,10 +111,34 @@ impl SystemExt for System {
         &[]
     }
 
+    fn cpu_count(&self) -> usize {
+        0
+    }
+
     fn physical_core_count(&self) -> Option<usize> {
         None
     }
 
+    fn max_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    fn read_sysctl(&self, _name: &str) -> Option<i64> {
+        None
+    }
+
+    fn read_proc_value(&self, _path: &str) -> Option<String> {
+        None
+    }
+
+    fn context_switches(&self) -> Option<u64> {
+        None
+    }
+
+    fn interrupts(&self) -> Option<u64> {
+        None
+    }
+
     fn total_memory(&self) -> u64 {
         0
     }
@@ -107,6 +167,34 @@ impl SystemExt for System {
         0
     }
 
+    fn swap_devices(&self) -> Vec<SwapDevice> {
+        Vec::new()
+    }
+
+    fn is_swap_encrypted(&self) -> Option<bool> {
+        None
+    }
+
+    fn page_size(&self) -> u64 {
+        0
+    }
+
+    fn swap_in_per_second(&self) -> f64 {
+        0.
+    }
+
+    fn swap_out_per_second(&self) -> f64 {
+        0.
+    }
+
+    fn swap_in(&self) -> u64 {
+        0
+    }
+
+    fn swap_out(&self) -> u64 {
+        0
+    }
+
     fn components(&self) -> &[Component] {
         &[]
     }
@@ -115,6 +203,14 @@ impl SystemExt for System {
         &mut []
     }
 
+    fn sensors_available(&self) -> bool {
+        false
+    }
+
+    fn cpu_temperature(&self) -> Option<f32> {
+        None
+    }
+
     fn disks(&self) -> &[Disk] {
         &[]
     }
@@ -123,6 +219,10 @@ impl SystemExt for System {
         &mut []
     }
 
+    fn file_systems(&self) -> Vec<Disk> {
+        Vec::new()
+    }
+
     fn sort_disks_by<F>(&mut self, _compare: F)
     where
         F: FnMut(&Disk, &Disk) -> std::cmp::Ordering,
@@ -146,6 +246,26 @@ impl SystemExt for System {
         }
     }
 
+    fn cgroup_stats(&self, _path: &Path) -> Option<CgroupStats> {
+        None
+    }
+
+    fn cgroup_limits(&self) -> Option<CgroupLimits> {
+        None
+    }
+
+    fn pressure(&self) -> Option<PressureStats> {
+        None
+    }
+
+    fn battery(&self) -> Option<Battery> {
+        None
+    }
+
+    fn on_ac_power(&self) -> Option<bool> {
+        None
+    }
+
     fn users(&self) -> &[User] {
         &[]
     }
@@ -173,6 +293,10 @@ impl SystemExt for System {
     fn host_name(&self) -> Option<String> {
         None
     }
+
+    fn virtualization(&self) -> Option<Virtualization> {
+        None
+    }
 }
 
 impl Default for System {
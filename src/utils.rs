@@ -45,3 +45,829 @@ where
 {
     val.into_iter()
 }
+
+/// Joins the command line arguments with spaces. Arguments which are empty or contain
+/// whitespace or double quotes are surrounded with double quotes (and their quotes and
+/// backslashes are escaped) so the result can be read back unambiguously.
+pub(crate) fn join_cmd(cmd: &[String]) -> String {
+    let mut out = String::new();
+    for (pos, arg) in cmd.iter().enumerate() {
+        if pos != 0 {
+            out.push(' ');
+        }
+        if !arg.is_empty() && !arg.chars().any(|c| c.is_whitespace() || c == '"') {
+            out.push_str(arg);
+            continue;
+        }
+        out.push('"');
+        for c in arg.chars() {
+            if c == '"' || c == '\\' {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out.push('"');
+    }
+    out
+}
+
+/// Removes the verbatim prefix (`\\?\`) added by [`Path::canonicalize`] on Windows so the path
+/// can be compared with paths which don't have it, like the mount points of the disks.
+///
+/// [`Path::canonicalize`]: std::path::Path::canonicalize
+pub(crate) fn strip_verbatim_prefix(path: std::path::PathBuf) -> std::path::PathBuf {
+    let s = match path.to_str() {
+        Some(s) => s,
+        None => return path,
+    };
+    if let Some(unc) = s.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", unc).into()
+    } else if let Some(rest) = s
+        .strip_prefix(r"\\?\")
+        .filter(|rest| rest.as_bytes().get(1) == Some(&b':'))
+    {
+        rest.into()
+    } else {
+        path
+    }
+}
+
+/// Returns `used` as a percentage of `total`, or `0` if `total` is `0`.
+pub(crate) fn usage_percent(used: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.
+    } else {
+        // Computed with `f64` to not lose precision with big amounts of memory.
+        (used.min(total) as f64 * 100. / total as f64) as f32
+    }
+}
+
+/// Names of the users looked up by ID which aren't in the users list. Each `System` has its own,
+/// emptied on [`SystemExt::refresh_users_list`][crate::SystemExt::refresh_users_list].
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+#[derive(Default)]
+pub(crate) struct UserNameCache(
+    std::sync::Mutex<std::collections::HashMap<crate::Uid, Option<String>>>,
+);
+
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+impl UserNameCache {
+    pub(crate) fn get(&self, user_id: &crate::Uid) -> Option<String> {
+        let mut names = match self.0.lock() {
+            Ok(names) => names,
+            Err(_) => return lookup_user_name_by_id(user_id),
+        };
+        names
+            .entry(user_id.clone())
+            .or_insert_with(|| lookup_user_name_by_id(user_id))
+            .clone()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        if let Ok(names) = self.0.get_mut() {
+            names.clear();
+        }
+    }
+}
+
+/// Returns the name of the user with the given ID using `getpwuid_r`.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+fn lookup_user_name_by_id(user_id: &crate::Uid) -> Option<String> {
+    let mut buffer: Vec<std::os::raw::c_char> = vec![0; 1024];
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result = std::ptr::null_mut();
+
+    loop {
+        let ret = unsafe {
+            libc::getpwuid_r(
+                **user_id,
+                &mut pwd,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+        if ret == libc::ERANGE && buffer.len() < 1024 * 1024 {
+            // The buffer is too small, let's try again with a bigger one.
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if ret != 0 || result.is_null() || pwd.pw_name.is_null() {
+            return None;
+        }
+        return unsafe { std::ffi::CStr::from_ptr(pwd.pw_name) }
+            .to_str()
+            .ok()
+            .map(|name| name.to_owned());
+    }
+}
+
+/// Returns the (deduplicated) names of the users currently logged in, read from `utmpx`.
+#[cfg(all(
+    any(
+        all(target_os = "linux", target_env = "gnu"),
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+pub(crate) fn get_logged_in_users() -> Vec<String> {
+    // The `*utxent` functions are not thread-safe.
+    static UTMPX_LOCK: once_cell::sync::Lazy<std::sync::Mutex<()>> =
+        once_cell::sync::Lazy::new(|| std::sync::Mutex::new(()));
+
+    let _guard = UTMPX_LOCK.lock();
+    let mut users = Vec::new();
+    unsafe {
+        libc::setutxent();
+        loop {
+            let entry = libc::getutxent();
+            if entry.is_null() {
+                break;
+            }
+            let entry = &*entry;
+            if entry.ut_type != libc::USER_PROCESS {
+                continue;
+            }
+            let name = &entry.ut_user;
+            let len = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+            let name = std::slice::from_raw_parts(name.as_ptr() as *const u8, len);
+            if let Ok(name) = std::str::from_utf8(name) {
+                if !name.is_empty() && !users.iter().any(|user| user == name) {
+                    users.push(name.to_owned());
+                }
+            }
+        }
+        libc::endutxent();
+    }
+    users
+}
+
+#[cfg(not(all(
+    any(
+        all(target_os = "linux", target_env = "gnu"),
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+)))]
+pub(crate) fn get_logged_in_users() -> Vec<String> {
+    Vec::new()
+}
+
+/// The file systems listed when refreshing the disks list.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DiskListFilter {
+    /// What [`SystemExt::refresh_disks_list`][crate::SystemExt::refresh_disks_list] lists.
+    Default,
+    /// Also excludes the pseudo (`autofs`, `devfs`) and network file systems.
+    LocalOnly,
+    /// Doesn't exclude anything.
+    All,
+}
+
+/// Keeps the last `capacity` CPU usage values, the oldest first.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+#[derive(Default)]
+pub(crate) struct CpuUsageHistory {
+    capacity: usize,
+    values: Vec<f32>,
+}
+
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+impl CpuUsageHistory {
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        if self.values.len() > capacity {
+            self.values.drain(..self.values.len() - capacity);
+        }
+        self.values.shrink_to(capacity);
+        self.capacity = capacity;
+    }
+
+    pub(crate) fn push(&mut self, value: f32) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.values.len() >= self.capacity {
+            // The capacity is small so it's fine to move the values.
+            self.values.remove(0);
+        }
+        self.values.push(value);
+    }
+
+    pub(crate) fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+/// Used to skip refreshes happening sooner than a given interval after the previous one.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "windows",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+#[derive(Default)]
+pub(crate) struct RefreshInterval {
+    interval: std::time::Duration,
+    last_refresh: Option<std::time::Instant>,
+    skipped: bool,
+}
+
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "windows",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+impl RefreshInterval {
+    pub(crate) fn set_interval(&mut self, interval: std::time::Duration) {
+        self.interval = interval;
+    }
+
+    /// Returns `true` if the interval elapsed since the last refresh, in which case `now` becomes
+    /// the last refresh time.
+    pub(crate) fn should_refresh(&mut self, now: std::time::Instant) -> bool {
+        self.skipped = matches!(
+            self.last_refresh,
+            Some(last_refresh) if now.saturating_duration_since(last_refresh) < self.interval
+        );
+        if !self.skipped {
+            self.last_refresh = Some(now);
+        }
+        !self.skipped
+    }
+
+    /// Returns `true` if the last call to [`RefreshInterval::should_refresh`] returned `false`.
+    pub(crate) fn skipped(&self) -> bool {
+        self.skipped
+    }
+}
+
+/// Keeps track of the swap activity to compute the number of pages swapped in and out per
+/// second between two refreshes.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+#[derive(Default)]
+pub(crate) struct SwapActivity {
+    pages_in: u64,
+    pages_out: u64,
+    last_update: Option<std::time::Instant>,
+    pub(crate) swapped_in: u64,
+    pub(crate) swapped_out: u64,
+    pub(crate) in_per_second: f64,
+    pub(crate) out_per_second: f64,
+}
+
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+impl SwapActivity {
+    /// `pages_in` and `pages_out` are the total number of pages swapped in and out since boot.
+    pub(crate) fn update(&mut self, pages_in: u64, pages_out: u64, now: std::time::Instant) {
+        if let Some(last_update) = self.last_update {
+            let elapsed = now.saturating_duration_since(last_update).as_secs_f64();
+            if elapsed <= 0. {
+                // Not enough time elapsed since the last update, we keep the previous values.
+                return;
+            }
+            self.swapped_in = pages_in.saturating_sub(self.pages_in);
+            self.swapped_out = pages_out.saturating_sub(self.pages_out);
+            self.in_per_second = self.swapped_in as f64 / elapsed;
+            self.out_per_second = self.swapped_out as f64 / elapsed;
+        }
+        self.pages_in = pages_in;
+        self.pages_out = pages_out;
+        self.last_update = Some(now);
+    }
+}
+
+/// Called for each network interface at the end of `refresh_networks_list`. An interface which
+/// disappeared (not `updated`) is kept for a few refreshes, without any traffic: its `(old, current)`
+/// counters are made equal on the first one. Returns `false` once it should be removed.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+pub(crate) fn keep_network_after_list_refresh(
+    updated: bool,
+    inactive_refreshes: &mut u8,
+    counters: &mut [(&mut u64, u64)],
+) -> bool {
+    // Number of calls to `refresh_networks_list` during which an interface which disappeared is
+    // kept (and reported as inactive) before being removed.
+    const MAX_INACTIVE_REFRESHES: u8 = 3;
+
+    if updated {
+        *inactive_refreshes = 0;
+        return true;
+    }
+    if *inactive_refreshes == 0 {
+        for (old, current) in counters.iter_mut() {
+            **old = *current;
+        }
+    }
+    *inactive_refreshes = inactive_refreshes.saturating_add(1);
+    *inactive_refreshes <= MAX_INACTIVE_REFRESHES
+}
+
+/// Keeps track of the time elapsed between the last two updates to compute rates.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+#[derive(PartialEq, Eq)]
+pub(crate) struct ElapsedTime {
+    last_update: std::time::Instant,
+    elapsed: std::time::Duration,
+}
+
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+impl ElapsedTime {
+    pub(crate) fn new(now: std::time::Instant) -> Self {
+        Self {
+            last_update: now,
+            elapsed: std::time::Duration::from_secs(0),
+        }
+    }
+
+    pub(crate) fn update(&mut self, now: std::time::Instant) {
+        self.elapsed = now.saturating_duration_since(self.last_update);
+        self.last_update = now;
+    }
+
+    /// Returns `value` divided by the number of seconds elapsed between the last two updates, or
+    /// `0` if there was no previous update.
+    pub(crate) fn per_second(&self, value: u64) -> f64 {
+        let elapsed = self.elapsed.as_secs_f64();
+        if elapsed <= 0. {
+            0.
+        } else {
+            value as f64 / elapsed
+        }
+    }
+}
+
+/// Keeps track of a system-wide counter (like the number of context switches) to compute how
+/// much it increased between two refreshes.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+#[derive(Default)]
+pub(crate) struct CounterDelta {
+    total: Option<u64>,
+    pub(crate) delta: u64,
+}
+
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        windows,
+    ),
+    not(feature = "unknown-ci")
+))]
+impl CounterDelta {
+    /// `total` is the value of the counter since boot.
+    pub(crate) fn update(&mut self, total: u64) {
+        if let Some(previous) = self.total {
+            self.delta = total.saturating_sub(previous);
+        }
+        self.total = Some(total);
+    }
+}
+
+/// Returns the hypervisor advertised through the CPUID instruction, or `None` if the
+/// "hypervisor present" bit isn't set.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "windows",
+    ),
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(feature = "unknown-ci")
+))]
+pub(crate) fn cpuid_hypervisor() -> Option<crate::Virtualization> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    // `__cpuid` is only safe in recent Rust versions.
+    #[allow(unused_unsafe)]
+    unsafe {
+        // The bit 31 of ECX is set when running under a hypervisor.
+        if __cpuid(1).ecx & (1 << 31) == 0 {
+            return None;
+        }
+        let leaf = __cpuid(0x4000_0000);
+        let mut vendor = [0; 12];
+        vendor[..4].copy_from_slice(&leaf.ebx.to_le_bytes());
+        vendor[4..8].copy_from_slice(&leaf.ecx.to_le_bytes());
+        vendor[8..].copy_from_slice(&leaf.edx.to_le_bytes());
+        let hypervisor = hypervisor_from_cpuid_vendor(&vendor);
+        // With Hyper-V (also used by VBS, Credential Guard or WSL2), the host OS runs in the root
+        // partition, which is the only one allowed to create partitions (bit 0 of EBX of the
+        // `0x4000_0003` leaf): it runs on the physical machine.
+        if hypervisor == crate::Virtualization::HyperV
+            && leaf.eax >= 0x4000_0003
+            && __cpuid(0x4000_0003).ebx & 1 != 0
+        {
+            return None;
+        }
+        Some(hypervisor)
+    }
+}
+
+/// Returns the hypervisor matching the vendor signature of the `0x4000_0000` CPUID leaf.
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "windows",
+    ),
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(feature = "unknown-ci")
+))]
+fn hypervisor_from_cpuid_vendor(vendor: &[u8]) -> crate::Virtualization {
+    use crate::Virtualization;
+
+    match vendor {
+        b"KVMKVMKVM\0\0\0" => Virtualization::Kvm,
+        b"VMwareVMware" => Virtualization::VMware,
+        b"VBoxVBoxVBox" => Virtualization::VirtualBox,
+        b"XenVMMXenVMM" => Virtualization::Xen,
+        b"Microsoft Hv" => Virtualization::HyperV,
+        _ => Virtualization::Other,
+    }
+}
+
+/// Reads the integer sysctl `name`. Returns `None` if it doesn't exist, if `name` contains a
+/// nul byte or if its value isn't an integer.
+#[cfg(all(
+    any(target_os = "macos", target_os = "ios", target_os = "freebsd"),
+    not(feature = "unknown-ci")
+))]
+pub(crate) fn read_sysctl_integer(name: &str) -> Option<i64> {
+    let name = std::ffi::CString::new(name).ok()?;
+    let mut buf = [0u8; 8];
+    let mut len = buf.len();
+
+    unsafe {
+        if libc::sysctlbyname(
+            name.as_ptr(),
+            buf.as_mut_ptr() as *mut _,
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        ) != 0
+        {
+            // Also fails with `ENOMEM` if the value doesn't fit in 8 bytes, meaning that it
+            // isn't an integer.
+            return None;
+        }
+    }
+    match len {
+        1 => Some(buf[0] as i8 as i64),
+        2 => Some(i16::from_ne_bytes([buf[0], buf[1]]) as i64),
+        4 => Some(i32::from_ne_bytes([buf[0], buf[1], buf[2], buf[3]]) as i64),
+        8 => Some(i64::from_ne_bytes(buf)),
+        _ => None,
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+    ),
+    not(feature = "unknown-ci")
+))]
+mod test {
+    use super::{
+        join_cmd, keep_network_after_list_refresh, strip_verbatim_prefix, CpuUsageHistory,
+        ElapsedTime, RefreshInterval, SwapActivity,
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn check_keep_network_after_list_refresh() {
+        let mut inactive_refreshes = 0;
+        let mut old = 10;
+        let current = 15;
+
+        assert!(keep_network_after_list_refresh(
+            true,
+            &mut inactive_refreshes,
+            &mut [(&mut old, current)]
+        ));
+        assert_eq!((inactive_refreshes, old), (0, 10));
+
+        // The interface disappeared: it is kept a few times without any traffic.
+        for count in 1..=3 {
+            assert!(keep_network_after_list_refresh(
+                false,
+                &mut inactive_refreshes,
+                &mut [(&mut old, current)]
+            ));
+            assert_eq!((inactive_refreshes, old), (count, current));
+        }
+        assert!(!keep_network_after_list_refresh(
+            false,
+            &mut inactive_refreshes,
+            &mut [(&mut old, current)]
+        ));
+
+        // It came back.
+        assert!(keep_network_after_list_refresh(
+            true,
+            &mut inactive_refreshes,
+            &mut [(&mut old, current)]
+        ));
+        assert_eq!(inactive_refreshes, 0);
+    }
+
+    #[test]
+    fn check_strip_verbatim_prefix() {
+        let strip = |path: &str| strip_verbatim_prefix(path.into());
+        assert_eq!(strip(r"\\?\C:\Users"), std::path::Path::new(r"C:\Users"));
+        assert_eq!(
+            strip(r"\\?\UNC\server\share\dir"),
+            std::path::Path::new(r"\\server\share\dir")
+        );
+        // Only the disk and UNC prefixes have a non-verbatim equivalent.
+        assert_eq!(
+            strip(r"\\?\Volume{42}\dir"),
+            std::path::Path::new(r"\\?\Volume{42}\dir")
+        );
+        assert_eq!(strip("/home/user"), std::path::Path::new("/home/user"));
+    }
+
+    #[test]
+    fn check_cpu_usage_history() {
+        let mut history = CpuUsageHistory::default();
+        // Disabled by default.
+        history.push(1.);
+        assert!(history.values().is_empty());
+
+        history.set_capacity(3);
+        for value in [1., 2., 3., 4.].iter() {
+            history.push(*value);
+        }
+        assert_eq!(history.values(), &[2., 3., 4.]);
+
+        history.set_capacity(2);
+        assert_eq!(history.values(), &[3., 4.]);
+        history.push(5.);
+        assert_eq!(history.values(), &[4., 5.]);
+
+        history.set_capacity(0);
+        assert!(history.values().is_empty());
+    }
+
+    #[test]
+    fn check_refresh_interval() {
+        let mut interval = RefreshInterval::default();
+        let now = Instant::now();
+
+        // By default, refreshes are never skipped.
+        assert!(interval.should_refresh(now));
+        assert!(interval.should_refresh(now));
+        assert!(!interval.skipped());
+
+        interval.set_interval(Duration::from_secs(2));
+        assert!(!interval.should_refresh(now + Duration::from_secs(1)));
+        assert!(interval.skipped());
+        assert!(interval.should_refresh(now + Duration::from_secs(2)));
+        assert!(!interval.skipped());
+        // The last refresh time was updated.
+        assert!(!interval.should_refresh(now + Duration::from_secs(3)));
+        assert!(interval.should_refresh(now + Duration::from_secs(4)));
+    }
+
+    #[test]
+    fn check_swap_activity() {
+        let mut activity = SwapActivity::default();
+        let now = Instant::now();
+
+        activity.update(100, 50, now);
+        // Two updates are needed to get a rate.
+        assert_eq!(activity.in_per_second, 0.);
+        assert_eq!(activity.out_per_second, 0.);
+
+        activity.update(300, 60, now + Duration::from_secs(2));
+        assert_eq!(activity.swapped_in, 200);
+        assert_eq!(activity.swapped_out, 10);
+        assert_eq!(activity.in_per_second, 100.);
+        assert_eq!(activity.out_per_second, 5.);
+
+        // No time elapsed: values are kept as is.
+        activity.update(1_000, 1_000, now + Duration::from_secs(2));
+        assert_eq!(activity.in_per_second, 100.);
+        assert_eq!(activity.out_per_second, 5.);
+
+        // Counters going backward (after a reset for example) don't underflow.
+        activity.update(0, 0, now + Duration::from_secs(4));
+        assert_eq!(activity.swapped_in, 0);
+        assert_eq!(activity.swapped_out, 0);
+        assert_eq!(activity.in_per_second, 0.);
+        assert_eq!(activity.out_per_second, 0.);
+    }
+
+    #[test]
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    fn check_counter_delta() {
+        let mut counter = super::CounterDelta::default();
+
+        counter.update(100);
+        // Two updates are needed to get a delta.
+        assert_eq!(counter.delta, 0);
+
+        counter.update(150);
+        assert_eq!(counter.delta, 50);
+
+        // Counters going backward (after a wrap for example) don't underflow.
+        counter.update(10);
+        assert_eq!(counter.delta, 0);
+        counter.update(15);
+        assert_eq!(counter.delta, 5);
+    }
+
+    #[test]
+    fn check_elapsed_time() {
+        let now = Instant::now();
+        let mut elapsed = ElapsedTime::new(now);
+        // No previous update.
+        assert_eq!(elapsed.per_second(100), 0.);
+
+        elapsed.update(now + Duration::from_secs(2));
+        assert_eq!(elapsed.per_second(100), 50.);
+
+        // No time elapsed.
+        elapsed.update(now + Duration::from_secs(2));
+        assert_eq!(elapsed.per_second(100), 0.);
+    }
+
+    #[test]
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android", target_os = "macos"),
+        any(target_arch = "x86", target_arch = "x86_64"),
+    ))]
+    fn check_hypervisor_from_cpuid_vendor() {
+        use super::hypervisor_from_cpuid_vendor;
+        use crate::Virtualization;
+
+        assert_eq!(
+            hypervisor_from_cpuid_vendor(b"KVMKVMKVM\0\0\0"),
+            Virtualization::Kvm
+        );
+        assert_eq!(
+            hypervisor_from_cpuid_vendor(b"Microsoft Hv"),
+            Virtualization::HyperV
+        );
+        assert_eq!(
+            hypervisor_from_cpuid_vendor(b"TCGTCGTCGTCG"),
+            Virtualization::Other
+        );
+    }
+
+    #[test]
+    fn check_join_cmd() {
+        let to_vec = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(join_cmd(&[]), "");
+        assert_eq!(join_cmd(&to_vec(&["ls", "-l"])), "ls -l");
+        assert_eq!(join_cmd(&to_vec(&["echo", "a b", ""])), r#"echo "a b" """#);
+        assert_eq!(
+            join_cmd(&to_vec(&["echo", r#"say "hi""#, r"C:\dir"])),
+            r#"echo "say \"hi\"" C:\dir"#
+        );
+        assert_eq!(
+            join_cmd(&to_vec(&[r"C:\my dir\a.exe"])),
+            r#""C:\\my dir\\a.exe""#
+        );
+    }
+}
@@ -1,6 +1,7 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
 use crate::sys::tools::KeyHandler;
+use crate::utils::CpuUsageHistory;
 use crate::{CpuExt, CpuRefreshKind, LoadAvg};
 
 use std::collections::HashMap;
@@ -244,6 +245,7 @@ pub(crate) struct CpusWrapper {
     global: Cpu,
     cpus: Vec<Cpu>,
     got_cpu_frequency: bool,
+    usage_history_capacity: usize,
 }
 
 impl CpusWrapper {
@@ -252,6 +254,7 @@ impl CpusWrapper {
             global: Cpu::new_with_values("Total CPU".to_owned(), String::new(), String::new(), 0),
             cpus: Vec::new(),
             got_cpu_frequency: false,
+            usage_history_capacity: 0,
         }
     }
 
@@ -274,6 +277,17 @@ impl CpusWrapper {
             self.global.vendor_id = vendor_id;
             self.global.brand = brand;
             self.got_cpu_frequency = refresh_kind.frequency();
+            for cpu in self.cpus.iter_mut() {
+                cpu.usage_history.set_capacity(self.usage_history_capacity);
+            }
+        }
+    }
+
+    pub fn set_usage_history_capacity(&mut self, capacity: usize) {
+        self.usage_history_capacity = capacity;
+        self.global.usage_history.set_capacity(capacity);
+        for cpu in self.cpus.iter_mut() {
+            cpu.usage_history.set_capacity(capacity);
         }
     }
 
@@ -304,10 +318,14 @@ impl CpusWrapper {
 pub struct Cpu {
     name: String,
     cpu_usage: f32,
+    user_usage: f32,
+    system_usage: f32,
+    idle_usage: f32,
     key_used: Option<KeyHandler>,
     vendor_id: String,
     brand: String,
     frequency: u64,
+    usage_history: CpuUsageHistory,
 }
 
 impl CpuExt for Cpu {
@@ -315,6 +333,26 @@ impl CpuExt for Cpu {
         self.cpu_usage
     }
 
+    fn user_usage(&self) -> f32 {
+        self.user_usage
+    }
+
+    fn system_usage(&self) -> f32 {
+        self.system_usage
+    }
+
+    fn nice_usage(&self) -> f32 {
+        0.
+    }
+
+    fn idle_usage(&self) -> f32 {
+        self.idle_usage
+    }
+
+    fn usage_history(&self) -> &[f32] {
+        self.usage_history.values()
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -342,15 +380,38 @@ impl Cpu {
         Cpu {
             name,
             cpu_usage: 0f32,
+            user_usage: 0f32,
+            system_usage: 0f32,
+            idle_usage: 0f32,
             key_used: None,
             vendor_id,
             brand,
             frequency,
+            usage_history: CpuUsageHistory::default(),
         }
     }
 
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::CpuSnapshot) -> Cpu {
+        let mut cpu = Cpu::new_with_values(
+            snapshot.name.clone(),
+            snapshot.vendor_id.clone(),
+            snapshot.brand.clone(),
+            snapshot.frequency,
+        );
+        cpu.cpu_usage = snapshot.cpu_usage;
+        cpu
+    }
+
     pub(crate) fn set_cpu_usage(&mut self, value: f32) {
         self.cpu_usage = value;
+        self.usage_history.push(value);
+    }
+
+    pub(crate) fn set_states_usage(&mut self, user: f32, system: f32, idle: f32) {
+        self.user_usage = user;
+        self.system_usage = system;
+        self.idle_usage = idle;
     }
 
     pub(crate) fn set_frequency(&mut self, value: u64) {
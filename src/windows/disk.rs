@@ -1,5 +1,6 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+use crate::utils::DiskListFilter;
 use crate::{DiskExt, DiskType};
 
 use std::ffi::{OsStr, OsString};
@@ -7,17 +8,17 @@ use std::mem::size_of;
 use std::path::Path;
 
 use winapi::ctypes::c_void;
-use winapi::shared::minwindef::{DWORD, MAX_PATH};
+use winapi::shared::minwindef::{BYTE, DWORD, MAX_PATH};
 use winapi::um::fileapi::{
     CreateFileW, GetDiskFreeSpaceExW, GetDriveTypeW, GetLogicalDrives, GetVolumeInformationW,
     OPEN_EXISTING,
 };
 use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
 use winapi::um::ioapiset::DeviceIoControl;
-use winapi::um::winbase::{DRIVE_FIXED, DRIVE_REMOVABLE};
+use winapi::um::winbase::{DRIVE_CDROM, DRIVE_FIXED, DRIVE_RAMDISK, DRIVE_REMOTE, DRIVE_REMOVABLE};
 use winapi::um::winioctl::{
-    PropertyStandardQuery, StorageDeviceSeekPenaltyProperty, IOCTL_STORAGE_QUERY_PROPERTY,
-    STORAGE_PROPERTY_QUERY,
+    PropertyStandardQuery, StorageDeviceProperty, StorageDeviceSeekPenaltyProperty,
+    IOCTL_STORAGE_QUERY_PROPERTY, STORAGE_PROPERTY_QUERY,
 };
 use winapi::um::winnt::{BOOLEAN, FILE_SHARE_READ, FILE_SHARE_WRITE, HANDLE, ULARGE_INTEGER};
 
@@ -30,7 +31,11 @@ pub struct Disk {
     s_mount_point: String,
     total_space: u64,
     available_space: u64,
+    free_space: u64,
     is_removable: bool,
+    model: String,
+    serial: String,
+    last_refresh_failed: bool,
 }
 
 impl DiskExt for Disk {
@@ -58,26 +63,50 @@ impl DiskExt for Disk {
         self.available_space
     }
 
+    fn free_space(&self) -> u64 {
+        self.free_space
+    }
+
+    fn total_inodes(&self) -> u64 {
+        0
+    }
+
+    fn available_inodes(&self) -> u64 {
+        0
+    }
+
     fn is_removable(&self) -> bool {
         self.is_removable
     }
 
+    fn mount_options(&self) -> &[String] {
+        &[]
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    fn last_refresh_failed(&self) -> bool {
+        self.last_refresh_failed
+    }
+
     fn refresh(&mut self) -> bool {
         if self.total_space != 0 {
             unsafe {
-                let mut tmp: ULARGE_INTEGER = std::mem::zeroed();
-                if GetDiskFreeSpaceExW(
-                    self.mount_point.as_ptr(),
-                    std::ptr::null_mut(),
-                    std::ptr::null_mut(),
-                    &mut tmp,
-                ) != 0
-                {
-                    self.available_space = *tmp.QuadPart();
+                if let Some((_, available_space, free_space)) = get_drive_size(&self.mount_point) {
+                    self.available_space = available_space;
+                    self.free_space = free_space;
+                    self.last_refresh_failed = false;
                     return true;
                 }
             }
         }
+        self.last_refresh_failed = true;
         false
     }
 }
@@ -112,19 +141,23 @@ impl Drop for HandleWrapper {
     }
 }
 
-unsafe fn get_drive_size(mount_point: &[u16]) -> Option<(u64, u64)> {
+/// Returns the total, available (taking into account the user quotas) and free sizes of the
+/// drive.
+unsafe fn get_drive_size(mount_point: &[u16]) -> Option<(u64, u64, u64)> {
     let mut total_size: ULARGE_INTEGER = std::mem::zeroed();
     let mut available_space: ULARGE_INTEGER = std::mem::zeroed();
+    let mut free_space: ULARGE_INTEGER = std::mem::zeroed();
     if GetDiskFreeSpaceExW(
         mount_point.as_ptr(),
-        std::ptr::null_mut(),
-        &mut total_size,
         &mut available_space,
+        &mut total_size,
+        &mut free_space,
     ) != 0
     {
         Some((
             *total_size.QuadPart() as _,
             *available_space.QuadPart() as _,
+            *free_space.QuadPart() as _,
         ))
     } else {
         None
@@ -140,7 +173,98 @@ struct DEVICE_SEEK_PENALTY_DESCRIPTOR {
     IncursSeekPenalty: BOOLEAN,
 }
 
-pub(crate) unsafe fn get_disks() -> Vec<Disk> {
+// Not provided by winapi 0.3.
+#[allow(non_snake_case)]
+#[repr(C)]
+struct STORAGE_DEVICE_DESCRIPTOR {
+    Version: DWORD,
+    Size: DWORD,
+    DeviceType: BYTE,
+    DeviceTypeModifier: BYTE,
+    RemovableMedia: BOOLEAN,
+    CommandQueueing: BOOLEAN,
+    VendorIdOffset: DWORD,
+    ProductIdOffset: DWORD,
+    ProductRevisionOffset: DWORD,
+    SerialNumberOffset: DWORD,
+    BusType: DWORD,
+    RawPropertiesLength: DWORD,
+    RawDeviceProperties: [BYTE; 1],
+}
+
+unsafe fn get_disk_type(handle: &HandleWrapper) -> DiskType {
+    let mut spq_trim = STORAGE_PROPERTY_QUERY {
+        PropertyId: StorageDeviceSeekPenaltyProperty,
+        QueryType: PropertyStandardQuery,
+        AdditionalParameters: [0],
+    };
+    let mut result: DEVICE_SEEK_PENALTY_DESCRIPTOR = std::mem::zeroed();
+
+    let mut dw_size = 0;
+    if DeviceIoControl(
+        handle.0,
+        IOCTL_STORAGE_QUERY_PROPERTY,
+        &mut spq_trim as *mut STORAGE_PROPERTY_QUERY as *mut c_void,
+        size_of::<STORAGE_PROPERTY_QUERY>() as DWORD,
+        &mut result as *mut DEVICE_SEEK_PENALTY_DESCRIPTOR as *mut c_void,
+        size_of::<DEVICE_SEEK_PENALTY_DESCRIPTOR>() as DWORD,
+        &mut dw_size,
+        std::ptr::null_mut(),
+    ) == 0
+        || dw_size != size_of::<DEVICE_SEEK_PENALTY_DESCRIPTOR>() as DWORD
+    {
+        DiskType::Unknown(-1)
+    } else {
+        let is_ssd = result.IncursSeekPenalty == 0;
+        if is_ssd {
+            DiskType::SSD
+        } else {
+            DiskType::HDD
+        }
+    }
+}
+
+unsafe fn get_disk_model_and_serial(handle: &HandleWrapper) -> (String, String) {
+    let mut spq = STORAGE_PROPERTY_QUERY {
+        PropertyId: StorageDeviceProperty,
+        QueryType: PropertyStandardQuery,
+        AdditionalParameters: [0],
+    };
+    let mut buffer = [0u8; 1024];
+
+    let mut dw_size = 0;
+    if DeviceIoControl(
+        handle.0,
+        IOCTL_STORAGE_QUERY_PROPERTY,
+        &mut spq as *mut STORAGE_PROPERTY_QUERY as *mut c_void,
+        size_of::<STORAGE_PROPERTY_QUERY>() as DWORD,
+        buffer.as_mut_ptr() as *mut c_void,
+        buffer.len() as DWORD,
+        &mut dw_size,
+        std::ptr::null_mut(),
+    ) == 0
+        || (dw_size as usize) < size_of::<STORAGE_DEVICE_DESCRIPTOR>()
+    {
+        return (String::new(), String::new());
+    }
+    let buffer = &buffer[..dw_size as usize];
+    let descriptor: STORAGE_DEVICE_DESCRIPTOR =
+        std::ptr::read_unaligned(buffer.as_ptr() as *const STORAGE_DEVICE_DESCRIPTOR);
+    // The offsets point to NUL-terminated strings inside the buffer (0 meaning not available).
+    let read_str = |offset: DWORD| match buffer.get(offset as usize..) {
+        Some(s) if offset != 0 => {
+            let end = s.iter().position(|c| *c == 0).unwrap_or(s.len());
+            String::from_utf8_lossy(&s[..end]).trim().to_owned()
+        }
+        _ => String::new(),
+    };
+    (
+        read_str(descriptor.ProductIdOffset),
+        read_str(descriptor.SerialNumberOffset),
+    )
+}
+
+pub(crate) unsafe fn get_disks(filter: DiskListFilter) -> Vec<Disk> {
     let drives = GetLogicalDrives();
     if drives == 0 {
         return Vec::new();
@@ -160,8 +284,10 @@ pub(crate) unsafe fn get_disks() -> Vec<Disk> {
 
             let is_removable = drive_type == DRIVE_REMOVABLE;
 
-            if drive_type != DRIVE_FIXED && drive_type != DRIVE_REMOVABLE {
-                return None;
+            match drive_type {
+                DRIVE_FIXED | DRIVE_REMOVABLE => {}
+                DRIVE_REMOTE | DRIVE_CDROM | DRIVE_RAMDISK if filter == DiskListFilter::All => {}
+                _ => return None,
             }
             let mut name = [0u16; MAX_PATH + 1];
             let mut file_system = [0u16; 32];
@@ -206,39 +332,17 @@ pub(crate) unsafe fn get_disks() -> Vec<Disk> {
                 b':' as u16,
                 0,
             ];
-            let handle = HandleWrapper::new(&drive_name, 0)?;
-            let (total_space, available_space) = get_drive_size(&mount_point)?;
+            let (total_space, available_space, free_space) = get_drive_size(&mount_point)?;
             if total_space == 0 {
                 return None;
             }
-            let mut spq_trim = STORAGE_PROPERTY_QUERY {
-                PropertyId: StorageDeviceSeekPenaltyProperty,
-                QueryType: PropertyStandardQuery,
-                AdditionalParameters: [0],
-            };
-            let mut result: DEVICE_SEEK_PENALTY_DESCRIPTOR = std::mem::zeroed();
-
-            let mut dw_size = 0;
-            let type_ = if DeviceIoControl(
-                handle.0,
-                IOCTL_STORAGE_QUERY_PROPERTY,
-                &mut spq_trim as *mut STORAGE_PROPERTY_QUERY as *mut c_void,
-                size_of::<STORAGE_PROPERTY_QUERY>() as DWORD,
-                &mut result as *mut DEVICE_SEEK_PENALTY_DESCRIPTOR as *mut c_void,
-                size_of::<DEVICE_SEEK_PENALTY_DESCRIPTOR>() as DWORD,
-                &mut dw_size,
-                std::ptr::null_mut(),
-            ) == 0
-                || dw_size != size_of::<DEVICE_SEEK_PENALTY_DESCRIPTOR>() as DWORD
-            {
-                DiskType::Unknown(-1)
-            } else {
-                let is_ssd = result.IncursSeekPenalty == 0;
-                if is_ssd {
-                    DiskType::SSD
-                } else {
-                    DiskType::HDD
+            let (type_, (model, serial)) = match HandleWrapper::new(&drive_name, 0) {
+                Some(handle) => (get_disk_type(&handle), get_disk_model_and_serial(&handle)),
+                // Network shares cannot be opened like local drives.
+                None if filter == DiskListFilter::All => {
+                    (DiskType::Unknown(-1), (String::new(), String::new()))
                 }
+                None => return None,
             };
             Some(Disk {
                 type_,
@@ -248,7 +352,11 @@ pub(crate) unsafe fn get_disks() -> Vec<Disk> {
                 s_mount_point: String::from_utf16_lossy(&mount_point[..mount_point.len() - 1]),
                 total_space,
                 available_space,
+                free_space,
                 is_removable,
+                model,
+                serial,
+                last_refresh_failed: false,
             })
         })
         .collect::<Vec<_>>()
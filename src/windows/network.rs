@@ -1,8 +1,10 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+use crate::utils::{keep_network_after_list_refresh, ElapsedTime};
 use crate::{NetworkExt, NetworksExt, NetworksIter};
 
 use std::collections::{hash_map, HashMap};
+use std::time::Instant;
 
 use winapi::shared::ifdef::{MediaConnectStateDisconnected, NET_LUID};
 use winapi::shared::netioapi::{
@@ -47,6 +49,7 @@ impl NetworksExt for Networks {
             for (_, data) in self.interfaces.iter_mut() {
                 data.updated = false;
             }
+            let now = Instant::now();
 
             // In here, this is tricky: we have to filter out the software interfaces to only keep
             // the hardware ones. To do so, we first check the connection potential speed (if 0, not
@@ -117,6 +120,7 @@ impl NetworksExt for Networks {
                         );
                         old_and_new!(interface, errors_in, old_errors_in, ptr.InErrors);
                         old_and_new!(interface, errors_out, old_errors_out, ptr.OutErrors);
+                        interface.elapsed.update(now);
                         interface.updated = true;
                     }
                     hash_map::Entry::Vacant(e) => {
@@ -137,7 +141,9 @@ impl NetworksExt for Networks {
                             old_errors_in: ptr.InErrors,
                             errors_out: ptr.OutErrors,
                             old_errors_out: ptr.OutErrors,
+                            elapsed: ElapsedTime::new(now),
                             updated: true,
+                            inactive_refreshes: 0,
                         });
                     }
                 }
@@ -145,7 +151,7 @@ impl NetworksExt for Networks {
             FreeMibTable(table as _);
         }
         // Remove interfaces which are gone.
-        self.interfaces.retain(|_, d| d.updated);
+        self.interfaces.retain(|_, d| d.keep_after_list_refresh());
     }
 
     fn refresh(&mut self) {
@@ -153,6 +159,7 @@ impl NetworksExt for Networks {
 
         unsafe {
             let mut entry = entry.assume_init();
+            let now = Instant::now();
             for (_, interface) in self.interfaces.iter_mut() {
                 entry.InterfaceLuid = interface.id;
                 entry.InterfaceIndex = 0; // to prevent the function to pick this one as index
@@ -175,6 +182,7 @@ impl NetworksExt for Networks {
                 );
                 old_and_new!(interface, errors_in, old_errors_in, entry.InErrors);
                 old_and_new!(interface, errors_out, old_errors_out, entry.OutErrors);
+                interface.elapsed.update(now);
             }
         }
     }
@@ -195,10 +203,36 @@ pub struct NetworkData {
     old_errors_in: u64,
     errors_out: u64,
     old_errors_out: u64,
+    elapsed: ElapsedTime,
     updated: bool,
+    // Number of calls to `refresh_networks_list` since the interface disappeared.
+    inactive_refreshes: u8,
+}
+
+impl NetworkData {
+    // Called at the end of `refresh_networks_list`. Returns `false` once the interface should be
+    // removed.
+    fn keep_after_list_refresh(&mut self) -> bool {
+        keep_network_after_list_refresh(
+            self.updated,
+            &mut self.inactive_refreshes,
+            &mut [
+                (&mut self.old_in, self.current_in),
+                (&mut self.old_out, self.current_out),
+                (&mut self.old_packets_in, self.packets_in),
+                (&mut self.old_packets_out, self.packets_out),
+                (&mut self.old_errors_in, self.errors_in),
+                (&mut self.old_errors_out, self.errors_out),
+            ],
+        )
+    }
 }
 
 impl NetworkExt for NetworkData {
+    fn is_active(&self) -> bool {
+        self.inactive_refreshes == 0
+    }
+
     fn received(&self) -> u64 {
         self.current_in.saturating_sub(self.old_in)
     }
@@ -215,6 +249,14 @@ impl NetworkExt for NetworkData {
         self.current_out
     }
 
+    fn received_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.received())
+    }
+
+    fn transmitted_per_second(&self) -> f64 {
+        self.elapsed.per_second(self.transmitted())
+    }
+
     fn packets_received(&self) -> u64 {
         self.packets_in.saturating_sub(self.old_packets_in)
     }
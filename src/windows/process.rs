@@ -2,7 +2,9 @@
 
 use crate::sys::system::is_proc_running;
 use crate::sys::utils::to_str;
-use crate::{DiskUsage, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Signal, Uid};
+use crate::{
+    DiskUsage, Gid, Pid, ProcessExt, ProcessRefreshKind, ProcessStatus, Signal, ThreadInfo, Uid,
+};
 
 use std::ffi::OsString;
 use std::fmt;
@@ -35,17 +37,21 @@ use winapi::shared::ntstatus::{
 };
 use winapi::shared::winerror::ERROR_INSUFFICIENT_BUFFER;
 use winapi::um::errhandlingapi::GetLastError;
-use winapi::um::handleapi::CloseHandle;
+use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
 use winapi::um::heapapi::{GetProcessHeap, HeapAlloc, HeapFree};
 use winapi::um::memoryapi::{ReadProcessMemory, VirtualQueryEx};
 use winapi::um::processthreadsapi::{
-    GetProcessTimes, GetSystemTimes, OpenProcess, OpenProcessToken,
+    GetExitCodeProcess, GetProcessHandleCount, GetProcessTimes, GetSystemTimes, OpenProcess,
+    OpenProcessToken,
 };
 use winapi::um::psapi::{
     EnumProcessModulesEx, GetModuleBaseNameW, GetModuleFileNameExW, GetProcessMemoryInfo,
     LIST_MODULES_ALL, PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX,
 };
 use winapi::um::securitybaseapi::GetTokenInformation;
+use winapi::um::tlhelp32::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
 use winapi::um::winbase::{GetProcessIoCounters, CREATE_NO_WINDOW};
 use winapi::um::winnt::{
     TokenUser, HANDLE, HEAP_ZERO_MEMORY, IO_COUNTERS, MEMORY_BASIC_INFORMATION,
@@ -222,6 +228,11 @@ pub struct Process {
     old_written_bytes: u64,
     read_bytes: u64,
     written_bytes: u64,
+    open_files: Option<usize>,
+    is_64bit: Option<bool>,
+    minor_faults: u64,
+    pub(crate) thread_count: usize,
+    pub(crate) priority: i32,
 }
 
 struct CPUsageCalculationValues {
@@ -256,6 +267,27 @@ static WINDOWS_8_1_OR_NEWER: Lazy<bool> = Lazy::new(|| unsafe {
         || version_info.dwMajorVersion == 6 && version_info.dwMinorVersion >= 3
 });
 
+unsafe fn get_thread_count(pid: Pid) -> Option<usize> {
+    let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+    if snapshot == INVALID_HANDLE_VALUE {
+        return None;
+    }
+    let snapshot = HandleWrapper(snapshot);
+    let mut entry: PROCESSENTRY32W = zeroed();
+    entry.dwSize = size_of::<PROCESSENTRY32W>() as DWORD;
+    if Process32FirstW(*snapshot, &mut entry) == FALSE {
+        return None;
+    }
+    loop {
+        if entry.th32ProcessID as usize == pid.0 {
+            return Some(entry.cntThreads as usize);
+        }
+        if Process32NextW(*snapshot, &mut entry) == FALSE {
+            return None;
+        }
+    }
+}
+
 unsafe fn get_process_name(process_handler: &HandleWrapper, h_mod: *mut c_void) -> String {
     let mut process_name = [0u16; MAX_PATH + 1];
 
@@ -322,13 +354,7 @@ impl Process {
             let exe = get_exe(&process_handler);
             let mut root = exe.clone();
             root.pop();
-            let (cmd, environ, cwd) = match get_process_params(&process_handler) {
-                Ok(args) => args,
-                Err(_e) => {
-                    sysinfo_debug!("Failed to get process parameters: {}", _e);
-                    (Vec::new(), Vec::new(), PathBuf::new())
-                }
-            };
+            let (cmd, environ, cwd) = get_cmd_environ_cwd(&process_handler, refresh_kind);
             let (start_time, run_time) = get_start_and_run_time(*process_handler, now);
             let parent = if info.InheritedFromUniqueProcessId as usize != 0 {
                 Some(Pid(info.InheritedFromUniqueProcessId as _))
@@ -336,6 +362,7 @@ impl Process {
                 None
             };
             let user_id = get_process_user_id(&process_handler, refresh_kind);
+            let is_64bit = get_is_64bit(&process_handler);
             Some(Process {
                 handle: Some(Arc::new(process_handler)),
                 name,
@@ -359,10 +386,55 @@ impl Process {
                 old_written_bytes: 0,
                 read_bytes: 0,
                 written_bytes: 0,
+                open_files: None,
+                is_64bit,
+                minor_faults: 0,
+                thread_count: 0,
+                priority: 0,
             })
         }
     }
 
+    #[cfg(feature = "testing")]
+    pub(crate) fn from_snapshot(snapshot: &crate::ProcessSnapshot) -> Process {
+        let mut root = snapshot.exe.clone();
+        root.pop();
+        let disk_usage = &snapshot.disk_usage;
+        Process {
+            handle: None,
+            name: snapshot.name.clone(),
+            pid: snapshot.pid,
+            user_id: snapshot.user_id.clone(),
+            parent: snapshot.parent,
+            cmd: snapshot.cmd.clone(),
+            environ: Vec::new(),
+            exe: snapshot.exe.clone(),
+            cwd: PathBuf::new(),
+            root,
+            status: snapshot.status,
+            memory: snapshot.memory,
+            virtual_memory: snapshot.virtual_memory,
+            cpu_usage: snapshot.cpu_usage,
+            cpu_calc_values: CPUsageCalculationValues::new(),
+            start_time: snapshot.start_time,
+            run_time: snapshot.run_time,
+            updated: true,
+            old_read_bytes: disk_usage
+                .total_read_bytes
+                .saturating_sub(disk_usage.read_bytes),
+            old_written_bytes: disk_usage
+                .total_written_bytes
+                .saturating_sub(disk_usage.written_bytes),
+            read_bytes: disk_usage.total_read_bytes,
+            written_bytes: disk_usage.total_written_bytes,
+            open_files: None,
+            is_64bit: None,
+            minor_faults: 0,
+            thread_count: 0,
+            priority: 0,
+        }
+    }
+
     pub(crate) fn new_full(
         pid: Pid,
         parent: Option<Pid>,
@@ -377,15 +449,10 @@ impl Process {
                 let exe = get_exe(&handle);
                 let mut root = exe.clone();
                 root.pop();
-                let (cmd, environ, cwd) = match get_process_params(&handle) {
-                    Ok(args) => args,
-                    Err(_e) => {
-                        sysinfo_debug!("Failed to get process parameters: {}", _e);
-                        (Vec::new(), Vec::new(), PathBuf::new())
-                    }
-                };
+                let (cmd, environ, cwd) = get_cmd_environ_cwd(&handle, refresh_kind);
                 let (start_time, run_time) = get_start_and_run_time(*handle, now);
                 let user_id = get_process_user_id(&handle, refresh_kind);
+                let is_64bit = get_is_64bit(&handle);
                 Process {
                     handle: Some(Arc::new(handle)),
                     name,
@@ -409,6 +476,11 @@ impl Process {
                     old_written_bytes: 0,
                     read_bytes: 0,
                     written_bytes: 0,
+                    open_files: None,
+                    is_64bit,
+                    minor_faults: 0,
+                    thread_count: 0,
+                    priority: 0,
                 }
             }
         } else {
@@ -435,6 +507,11 @@ impl Process {
                 old_written_bytes: 0,
                 read_bytes: 0,
                 written_bytes: 0,
+                open_files: None,
+                is_64bit: None,
+                minor_faults: 0,
+                thread_count: 0,
+                priority: 0,
             }
         }
     }
@@ -451,10 +528,21 @@ impl Process {
         if refresh_kind.disk_usage() {
             update_disk_usage(self);
         }
+        if refresh_kind.open_files() {
+            self.open_files = self.get_handle().and_then(get_handle_count);
+        }
         self.run_time = now.saturating_sub(self.start_time());
         self.updated = true;
     }
 
+    // The number of threads is only provided when all processes are listed at once, so when only
+    // one process is refreshed, it is retrieved from a snapshot of the running processes.
+    pub(crate) fn update_thread_count(&mut self) {
+        if let Some(thread_count) = unsafe { get_thread_count(self.pid) } {
+            self.thread_count = thread_count;
+        }
+    }
+
     pub(crate) fn get_handle(&self) -> Option<HANDLE> {
         self.handle.as_ref().map(|h| ***h)
     }
@@ -484,6 +572,10 @@ impl ProcessExt for Process {
         &self.cmd
     }
 
+    fn is_cmd_truncated(&self) -> bool {
+        false
+    }
+
     fn exe(&self) -> &Path {
         self.exe.as_path()
     }
@@ -520,6 +612,10 @@ impl ProcessExt for Process {
         self.status
     }
 
+    fn wait_channel(&self) -> Option<&str> {
+        None
+    }
+
     fn start_time(&self) -> u64 {
         self.start_time
     }
@@ -532,6 +628,22 @@ impl ProcessExt for Process {
         self.cpu_usage
     }
 
+    fn cpu_time(&self) -> u64 {
+        // The process times are expressed in 100-nanosecond units.
+        self.cpu_calc_values
+            .old_process_user_cpu
+            .saturating_add(self.cpu_calc_values.old_process_sys_cpu)
+            / 10_000
+    }
+
+    fn iowait_time(&self) -> Option<u64> {
+        None
+    }
+
+    fn gpu_usage(&self) -> Option<f32> {
+        None
+    }
+
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage {
             written_bytes: self.written_bytes - self.old_written_bytes,
@@ -545,22 +657,98 @@ impl ProcessExt for Process {
         self.user_id.as_ref()
     }
 
+    fn real_user_id(&self) -> Option<&Uid> {
+        None
+    }
+
     fn group_id(&self) -> Option<Gid> {
         None
     }
 
-    fn wait(&self) {
+    fn real_group_id(&self) -> Option<Gid> {
+        None
+    }
+
+    fn is_being_traced(&self) -> Option<bool> {
+        None
+    }
+
+    fn tracer_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    fn open_files(&self) -> Option<usize> {
+        self.open_files
+    }
+
+    fn network_usage(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    fn is_64bit(&self) -> Option<bool> {
+        self.is_64bit
+    }
+
+    fn minor_faults(&self) -> u64 {
+        self.minor_faults
+    }
+
+    fn major_faults(&self) -> u64 {
+        0
+    }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn threads(&self) -> Vec<ThreadInfo> {
+        Vec::new()
+    }
+
+    fn nice(&self) -> i32 {
+        0
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn set_nice(&self, _value: i32) -> bool {
+        false
+    }
+
+    fn tty(&self) -> Option<&str> {
+        None
+    }
+
+    fn process_group_id(&self) -> Option<Pid> {
+        None
+    }
+
+    fn session_id(&self) -> Option<Pid> {
+        None
+    }
+
+    fn wait_status(&self) -> Option<i32> {
         if let Some(handle) = self.get_handle() {
             while is_proc_running(handle) {
                 if get_start_time(handle) != self.start_time() {
                     // PID owner changed so the previous process was finished!
-                    return;
+                    return None;
                 }
                 std::thread::sleep(std::time::Duration::from_millis(10));
             }
+            let mut exit_code = 0;
+            unsafe {
+                if GetExitCodeProcess(handle, &mut exit_code) != FALSE {
+                    return Some(exit_code as i32);
+                }
+            }
+            None
         } else {
             // In this case, we can't do anything so we just return.
             sysinfo_debug!("can't wait on this process so returning");
+            None
         }
     }
 }
@@ -736,6 +924,22 @@ macro_rules! impl_RtlUserProcessParameters {
 impl_RtlUserProcessParameters!(RTL_USER_PROCESS_PARAMETERS32);
 impl_RtlUserProcessParameters!(RTL_USER_PROCESS_PARAMETERS);
 
+unsafe fn get_cmd_environ_cwd(
+    handle: &HandleWrapper,
+    refresh_kind: ProcessRefreshKind,
+) -> (Vec<String>, Vec<String>, PathBuf) {
+    if !refresh_kind.cmd() {
+        return (Vec::new(), Vec::new(), PathBuf::new());
+    }
+    match get_process_params(handle) {
+        Ok(args) => args,
+        Err(_e) => {
+            sysinfo_debug!("Failed to get process parameters: {}", _e);
+            (Vec::new(), Vec::new(), PathBuf::new())
+        }
+    }
+}
+
 unsafe fn get_process_params(
     handle: &HandleWrapper,
 ) -> Result<(Vec<String>, Vec<String>, PathBuf), &'static str> {
@@ -1046,6 +1250,34 @@ pub(crate) fn update_disk_usage(p: &mut Process) {
     }
 }
 
+// A process running in the WOW64 emulator is a 32-bit one.
+unsafe fn get_is_64bit(handle: &HandleWrapper) -> Option<bool> {
+    if !cfg!(target_pointer_width = "64") {
+        return None;
+    }
+    let mut pwow32info = MaybeUninit::<LPVOID>::uninit();
+    let result = NtQueryInformationProcess(
+        **handle,
+        ProcessWow64Information,
+        pwow32info.as_mut_ptr() as *mut _,
+        size_of::<LPVOID>() as u32,
+        null_mut(),
+    );
+    if !NT_SUCCESS(result) {
+        return None;
+    }
+    Some(pwow32info.assume_init().is_null())
+}
+
+fn get_handle_count(handle: HANDLE) -> Option<usize> {
+    let mut count: DWORD = 0;
+    if unsafe { GetProcessHandleCount(handle, &mut count) } != 0 {
+        Some(count as _)
+    } else {
+        None
+    }
+}
+
 pub(crate) fn update_memory(p: &mut Process) {
     if let Some(handle) = p.get_handle() {
         unsafe {
@@ -1059,6 +1291,8 @@ pub(crate) fn update_memory(p: &mut Process) {
             {
                 p.memory = pmc.WorkingSetSize as _;
                 p.virtual_memory = pmc.PrivateUsage as _;
+                // Soft and hard faults aren't distinguished.
+                p.minor_faults = pmc.PageFaultCount as _;
             }
         }
     }
@@ -1,8 +1,9 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
 use crate::{
-    CpuRefreshKind, LoadAvg, Networks, Pid, ProcessExt, ProcessRefreshKind, RefreshKind, SystemExt,
-    User,
+    Battery, CgroupLimits, CgroupStats, CpuRefreshKind, Error, LoadAvg, Networks, Pid,
+    PressureStats, ProcessExt, ProcessRefreshKind, Protocol, RefreshKind, RefreshTimes, SwapDevice,
+    SystemExt, User, Virtualization,
 };
 use winapi::um::winreg::HKEY_LOCAL_MACHINE;
 
@@ -14,31 +15,36 @@ use crate::sys::tools::*;
 use crate::sys::users::get_users;
 use crate::sys::utils::get_now;
 
-use crate::utils::into_iter;
+use crate::utils::{into_iter, CounterDelta, DiskListFilter, RefreshInterval};
 
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::mem::{size_of, zeroed};
 use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
 use std::slice::from_raw_parts;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use ntapi::ntexapi::{
-    NtQuerySystemInformation, SystemProcessInformation, SYSTEM_PROCESS_INFORMATION,
+    NtQuerySystemInformation, SystemPerformanceInformation, SystemProcessInformation,
+    SystemProcessorPerformanceInformation, SYSTEM_PERFORMANCE_INFORMATION,
+    SYSTEM_PROCESSOR_PERFORMANCE_INFORMATION, SYSTEM_PROCESS_INFORMATION,
 };
 use winapi::ctypes::wchar_t;
 use winapi::shared::minwindef::{DWORD, FALSE, HKEY, LPBYTE, TRUE};
 use winapi::shared::ntdef::{PVOID, ULONG};
 use winapi::shared::ntstatus::STATUS_INFO_LENGTH_MISMATCH;
 use winapi::shared::winerror;
+use winapi::um::errhandlingapi::GetLastError;
 use winapi::um::minwinbase::STILL_ACTIVE;
 use winapi::um::processthreadsapi::GetExitCodeProcess;
 use winapi::um::psapi::{GetPerformanceInfo, PERFORMANCE_INFORMATION};
 use winapi::um::sysinfoapi::{
-    ComputerNamePhysicalDnsHostname, GetComputerNameExW, GetTickCount64, GlobalMemoryStatusEx,
-    MEMORYSTATUSEX,
+    ComputerNamePhysicalDnsHostname, GetComputerNameExW, GetSystemInfo, GetTickCount64,
+    GlobalMemoryStatusEx, MEMORYSTATUSEX, SYSTEM_INFO,
 };
+use winapi::um::winbase::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
 use winapi::um::winnt::{HANDLE, KEY_READ};
 use winapi::um::winreg::{RegOpenKeyExW, RegQueryValueExW};
 
@@ -51,6 +57,9 @@ declare_signals! {
 #[doc = include_str!("../../md_doc/system.md")]
 pub struct System {
     process_list: HashMap<Pid, Process>,
+    process_errors: HashMap<Pid, Error>,
+    process_refresh_interval: RefreshInterval,
+    refresh_times: RefreshTimes,
     mem_total: u64,
     mem_available: u64,
     swap_total: u64,
@@ -62,6 +71,8 @@ pub struct System {
     networks: Networks,
     boot_time: u64,
     users: Vec<User>,
+    context_switches: CounterDelta,
+    interrupts: CounterDelta,
 }
 
 static WINDOWS_ELEVEN_BUILD_NUMBER: u32 = 22000;
@@ -75,6 +86,43 @@ impl System {
                 .parse()
                 .unwrap_or(0)
     }
+
+    fn refresh_process_inner(&mut self, pid: Pid, refresh_kind: ProcessRefreshKind) -> bool {
+        let now = get_now();
+        let nb_cpus = self.cpus.len() as u64;
+
+        if let Some(proc_) = self.process_list.get_mut(&pid) {
+            if let Some(ret) = refresh_existing_process(proc_, nb_cpus, now, refresh_kind) {
+                if ret {
+                    proc_.update_thread_count();
+                } else {
+                    self.process_errors.insert(pid, Error::NotFound);
+                }
+                return ret;
+            }
+            // We need to re-make the process because the PID owner changed.
+        }
+        if let Some(mut p) = Process::new_from_pid(pid, now, refresh_kind) {
+            p.update(refresh_kind, nb_cpus, now);
+            p.update_thread_count();
+            p.updated = false;
+            self.process_list.insert(pid, p);
+            true
+        } else {
+            self.process_errors.insert(pid, last_process_error());
+            false
+        }
+    }
+}
+
+// Converts the error set by a failed `OpenProcess` call.
+fn last_process_error() -> Error {
+    match unsafe { GetLastError() } {
+        // `OpenProcess` returns this error when there is no process with this PID.
+        winerror::ERROR_INVALID_PARAMETER => Error::NotFound,
+        winerror::ERROR_ACCESS_DENIED => Error::PermissionDenied,
+        code => Error::Io(std::io::Error::from_raw_os_error(code as _).kind()),
+    }
 }
 
 // Useful for parallel iterations.
@@ -84,6 +132,49 @@ struct Wrap<T>(T);
 unsafe impl<T> Send for Wrap<T> {}
 unsafe impl<T> Sync for Wrap<T> {}
 
+// Returns the total number of context switches and interrupts since boot.
+unsafe fn get_context_switches_and_interrupts(nb_cpus: usize) -> Option<(u64, u64)> {
+    let mut performance_info: SYSTEM_PERFORMANCE_INFORMATION = zeroed();
+    let mut returned_size: ULONG = 0;
+    let ntstatus = NtQuerySystemInformation(
+        SystemPerformanceInformation,
+        &mut performance_info as *mut _ as PVOID,
+        size_of::<SYSTEM_PERFORMANCE_INFORMATION>() as ULONG,
+        &mut returned_size,
+    );
+    if ntstatus < 0 {
+        sysinfo_debug!(
+            "Couldn't get performance infos: NtQuerySystemInformation returned {}",
+            ntstatus
+        );
+        return None;
+    }
+
+    let mut cpus_info: Vec<SYSTEM_PROCESSOR_PERFORMANCE_INFORMATION> = Vec::with_capacity(nb_cpus);
+    let ntstatus = NtQuerySystemInformation(
+        SystemProcessorPerformanceInformation,
+        cpus_info.as_mut_ptr() as PVOID,
+        (size_of::<SYSTEM_PROCESSOR_PERFORMANCE_INFORMATION>() * nb_cpus) as ULONG,
+        &mut returned_size,
+    );
+    if ntstatus < 0 {
+        sysinfo_debug!(
+            "Couldn't get processors infos: NtQuerySystemInformation returned {}",
+            ntstatus
+        );
+        return None;
+    }
+    cpus_info.set_len(
+        (returned_size as usize / size_of::<SYSTEM_PROCESSOR_PERFORMANCE_INFORMATION>())
+            .min(nb_cpus),
+    );
+    let interrupts = cpus_info
+        .iter()
+        .map(|info| info.InterruptCount as u64)
+        .sum();
+    Some((performance_info.ContextSwitches as u64, interrupts))
+}
+
 unsafe fn boot_time() -> u64 {
     match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
         Ok(n) => n.as_secs().saturating_sub(GetTickCount64() / 1_000),
@@ -102,6 +193,9 @@ impl SystemExt for System {
     fn new_with_specifics(refreshes: RefreshKind) -> System {
         let mut s = System {
             process_list: HashMap::with_capacity(500),
+            process_errors: HashMap::new(),
+            process_refresh_interval: RefreshInterval::default(),
+            refresh_times: RefreshTimes::default(),
             mem_total: 0,
             mem_available: 0,
             swap_total: 0,
@@ -113,12 +207,15 @@ impl SystemExt for System {
             networks: Networks::new(),
             boot_time: unsafe { boot_time() },
             users: Vec::new(),
+            context_switches: CounterDelta::default(),
+            interrupts: CounterDelta::default(),
         };
         s.refresh_specifics(refreshes);
         s
     }
 
     fn refresh_cpu_specifics(&mut self, refresh_kind: CpuRefreshKind) {
+        self.refresh_times.cpu = Some(std::time::Instant::now());
         if self.query.is_none() {
             self.query = Query::new();
             if let Some(ref mut query) = self.query {
@@ -128,6 +225,7 @@ impl SystemExt for System {
                     get_key_used(self.cpus.global_cpu_mut()),
                     "tot_0".to_owned(),
                 );
+                add_cpu_states_counters(query, "_Total", "tot");
                 for (pos, proc_) in self.cpus.iter_mut(refresh_kind).enumerate() {
                     add_english_counter(
                         format!(r"\Processor({pos})\% Processor Time"),
@@ -135,6 +233,7 @@ impl SystemExt for System {
                         get_key_used(proc_),
                         format!("{pos}_0"),
                     );
+                    add_cpu_states_counters(query, &pos.to_string(), &pos.to_string());
                 }
             }
         }
@@ -151,7 +250,8 @@ impl SystemExt for System {
             if let Some(used_time) = used_time {
                 self.cpus.global_cpu_mut().set_cpu_usage(used_time);
             }
-            for p in self.cpus.iter_mut(refresh_kind) {
+            refresh_cpu_states_usage(query, self.cpus.global_cpu_mut(), "tot");
+            for (pos, p) in self.cpus.iter_mut(refresh_kind).enumerate() {
                 let mut used_time = None;
                 if let Some(ref key_used) = *get_key_used(p) {
                     used_time = Some(
@@ -163,14 +263,26 @@ impl SystemExt for System {
                 if let Some(used_time) = used_time {
                     p.set_cpu_usage(used_time);
                 }
+                refresh_cpu_states_usage(query, p, &pos.to_string());
             }
             if refresh_kind.frequency() {
                 self.cpus.get_frequencies();
             }
         }
+        if let Some((context_switches, interrupts)) =
+            unsafe { get_context_switches_and_interrupts(self.cpus.len()) }
+        {
+            self.context_switches.update(context_switches);
+            self.interrupts.update(interrupts);
+        }
+    }
+
+    fn set_cpu_usage_history_capacity(&mut self, capacity: usize) {
+        self.cpus.set_usage_history_capacity(capacity);
     }
 
     fn refresh_memory(&mut self) {
+        self.refresh_times.memory = Some(std::time::Instant::now());
         unsafe {
             let mut mem_info: MEMORYSTATUSEX = zeroed();
             mem_info.dwLength = size_of::<MEMORYSTATUSEX>() as u32;
@@ -203,27 +315,29 @@ impl SystemExt for System {
 
     #[allow(clippy::map_entry)]
     fn refresh_process_specifics(&mut self, pid: Pid, refresh_kind: ProcessRefreshKind) -> bool {
-        let now = get_now();
-        let nb_cpus = self.cpus.len() as u64;
+        self.process_errors.clear();
+        self.refresh_process_inner(pid, refresh_kind)
+    }
 
-        if let Some(proc_) = self.process_list.get_mut(&pid) {
-            if let Some(ret) = refresh_existing_process(proc_, nb_cpus, now, refresh_kind) {
-                return ret;
-            }
-            // We need to re-make the process because the PID owner changed.
-        }
-        if let Some(mut p) = Process::new_from_pid(pid, now, refresh_kind) {
-            p.update(refresh_kind, nb_cpus, now);
-            p.updated = false;
-            self.process_list.insert(pid, p);
-            true
-        } else {
-            false
+    fn refresh_pids_specifics(&mut self, pids: &[Pid], refresh_kind: ProcessRefreshKind) {
+        self.process_errors.clear();
+        // The CPU usage of a process is computed from its own previous values, so there is no
+        // need to share anything between the calls.
+        for pid in pids {
+            self.refresh_process_inner(*pid, refresh_kind);
         }
     }
 
     #[allow(clippy::cast_ptr_alignment)]
     fn refresh_processes_specifics(&mut self, refresh_kind: ProcessRefreshKind) {
+        if !self
+            .process_refresh_interval
+            .should_refresh(std::time::Instant::now())
+        {
+            return;
+        }
+        self.refresh_times.processes = Some(std::time::Instant::now());
+        self.process_errors.clear();
         // Windows 10 notebook requires at least 512KiB of memory to make it in one go
         let mut buffer_size: usize = 512 * 1024;
         let now = get_now();
@@ -291,6 +405,8 @@ impl SystemExt for System {
                                 {
                                     proc_.memory = pi.WorkingSetSize as _;
                                     proc_.virtual_memory = pi.VirtualSize as _;
+                                    proc_.thread_count = pi.NumberOfThreads as _;
+                                    proc_.priority = pi.BasePriority as _;
                                     proc_.update(refresh_kind, nb_cpus, now);
                                     return None;
                                 }
@@ -311,6 +427,8 @@ impl SystemExt for System {
                                 now,
                                 refresh_kind,
                             );
+                            p.thread_count = pi.NumberOfThreads as _;
+                            p.priority = pi.BasePriority as _;
                             p.update(refresh_kind, nb_cpus, now);
                             Some(p)
                         })
@@ -339,8 +457,28 @@ impl SystemExt for System {
         }
     }
 
+    fn set_process_refresh_interval(&mut self, interval: Duration) {
+        self.process_refresh_interval.set_interval(interval);
+    }
+
+    fn processes_refresh_skipped(&self) -> bool {
+        self.process_refresh_interval.skipped()
+    }
+
+    fn refresh_times(&self) -> RefreshTimes {
+        self.refresh_times
+    }
+
     fn refresh_disks_list(&mut self) {
-        self.disks = unsafe { get_disks() };
+        self.disks = unsafe { get_disks(DiskListFilter::Default) };
+    }
+
+    fn refresh_disks_list_local_only(&mut self) {
+        self.disks = unsafe { get_disks(DiskListFilter::LocalOnly) };
+    }
+
+    fn refresh_disks_list_with_all_file_systems(&mut self) {
+        self.disks = unsafe { get_disks(DiskListFilter::All) };
     }
 
     fn refresh_users_list(&mut self) {
@@ -355,6 +493,14 @@ impl SystemExt for System {
         self.process_list.get(&pid)
     }
 
+    fn last_errors(&self) -> &HashMap<Pid, Error> {
+        &self.process_errors
+    }
+
+    fn process_by_port(&self, _port: u16, _protocol: Protocol) -> Option<&Process> {
+        None
+    }
+
     fn global_cpu_info(&self) -> &Cpu {
         self.cpus.global_cpu()
     }
@@ -363,10 +509,38 @@ impl SystemExt for System {
         self.cpus.cpus()
     }
 
+    fn cpu_count(&self) -> usize {
+        unsafe {
+            let mut sys_info: SYSTEM_INFO = zeroed();
+            GetSystemInfo(&mut sys_info);
+            sys_info.dwNumberOfProcessors as usize
+        }
+    }
+
     fn physical_core_count(&self) -> Option<usize> {
         get_physical_core_count()
     }
 
+    fn max_pid(&self) -> Option<Pid> {
+        None
+    }
+
+    fn read_sysctl(&self, _name: &str) -> Option<i64> {
+        None
+    }
+
+    fn read_proc_value(&self, _path: &str) -> Option<String> {
+        None
+    }
+
+    fn context_switches(&self) -> Option<u64> {
+        Some(self.context_switches.delta)
+    }
+
+    fn interrupts(&self) -> Option<u64> {
+        Some(self.interrupts.delta)
+    }
+
     fn total_memory(&self) -> u64 {
         self.mem_total
     }
@@ -381,7 +555,7 @@ impl SystemExt for System {
     }
 
     fn used_memory(&self) -> u64 {
-        self.mem_total - self.mem_available
+        self.mem_total.saturating_sub(self.mem_available)
     }
 
     fn total_swap(&self) -> u64 {
@@ -389,13 +563,50 @@ impl SystemExt for System {
     }
 
     fn free_swap(&self) -> u64 {
-        self.swap_total - self.swap_used
+        self.swap_total.saturating_sub(self.swap_used)
     }
 
     fn used_swap(&self) -> u64 {
         self.swap_used
     }
 
+    fn swap_devices(&self) -> Vec<SwapDevice> {
+        Vec::new()
+    }
+
+    fn is_swap_encrypted(&self) -> Option<bool> {
+        get_reg_value_u32(
+            HKEY_LOCAL_MACHINE,
+            "SYSTEM\\CurrentControlSet\\Control\\FileSystem",
+            "NtfsEncryptPagingFile",
+        )
+        .map(|value| u32::from_le_bytes(value) != 0)
+    }
+
+    fn page_size(&self) -> u64 {
+        unsafe {
+            let mut sys_info: SYSTEM_INFO = zeroed();
+            GetSystemInfo(&mut sys_info);
+            sys_info.dwPageSize as _
+        }
+    }
+
+    fn swap_in_per_second(&self) -> f64 {
+        0.
+    }
+
+    fn swap_out_per_second(&self) -> f64 {
+        0.
+    }
+
+    fn swap_in(&self) -> u64 {
+        0
+    }
+
+    fn swap_out(&self) -> u64 {
+        0
+    }
+
     fn components(&self) -> &[Component] {
         &self.components
     }
@@ -404,6 +615,15 @@ impl SystemExt for System {
         &mut self.components
     }
 
+    fn sensors_available(&self) -> bool {
+        !self.components.is_empty()
+    }
+
+    fn cpu_temperature(&self) -> Option<f32> {
+        // The ACPI thermal zone isn't the CPU die temperature.
+        None
+    }
+
     fn disks(&self) -> &[Disk] {
         &self.disks
     }
@@ -412,6 +632,10 @@ impl SystemExt for System {
         &mut self.disks
     }
 
+    fn file_systems(&self) -> Vec<Disk> {
+        unsafe { get_disks(DiskListFilter::All) }
+    }
+
     fn sort_disks_by<F>(&mut self, compare: F)
     where
         F: FnMut(&Disk, &Disk) -> std::cmp::Ordering,
@@ -443,6 +667,56 @@ impl SystemExt for System {
         get_load_average()
     }
 
+    fn cgroup_stats(&self, _path: &Path) -> Option<CgroupStats> {
+        None
+    }
+
+    fn cgroup_limits(&self) -> Option<CgroupLimits> {
+        None
+    }
+
+    fn pressure(&self) -> Option<PressureStats> {
+        None
+    }
+
+    fn battery(&self) -> Option<Battery> {
+        unsafe {
+            let mut status: SYSTEM_POWER_STATUS = zeroed();
+            if GetSystemPowerStatus(&mut status) == 0 {
+                return None;
+            }
+            // 128 means that there is no battery and 255 that its status is unknown.
+            if status.BatteryFlag & 128 != 0 || status.BatteryLifePercent > 100 {
+                return None;
+            }
+            Some(Battery {
+                percentage: status.BatteryLifePercent as f32,
+                is_charging: status.BatteryFlag & 8 != 0,
+                // It is `-1` if the remaining time is unknown or if the battery is charging.
+                time_remaining: if status.BatteryLifeTime == DWORD::MAX {
+                    None
+                } else {
+                    Some(Duration::from_secs(status.BatteryLifeTime as _))
+                },
+            })
+        }
+    }
+
+    fn on_ac_power(&self) -> Option<bool> {
+        unsafe {
+            let mut status: SYSTEM_POWER_STATUS = zeroed();
+            if GetSystemPowerStatus(&mut status) == 0 {
+                return None;
+            }
+            // 255 means that the status is unknown.
+            match status.ACLineStatus {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            }
+        }
+    }
+
     fn name(&self) -> Option<String> {
         Some("Windows".to_owned())
     }
@@ -467,6 +741,16 @@ impl SystemExt for System {
         get_dns_hostname()
     }
 
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn virtualization(&self) -> Option<Virtualization> {
+        crate::utils::cpuid_hypervisor()
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    fn virtualization(&self) -> Option<Virtualization> {
+        None
+    }
+
     fn kernel_version(&self) -> Option<String> {
         get_reg_string_value(
             HKEY_LOCAL_MACHINE,
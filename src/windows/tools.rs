@@ -53,3 +53,29 @@ pub(crate) fn add_english_counter(
         *keys = Some(KeyHandler::new(counter_name));
     }
 }
+
+// `(state, counter)` pairs used to get the CPU usage split by state.
+const CPU_STATES_COUNTERS: &[(&str, &str)] = &[
+    ("user", "% User Time"),
+    ("system", "% Privileged Time"),
+    ("idle", "% Idle Time"),
+];
+
+pub(crate) fn add_cpu_states_counters(query: &mut Query, instance: &str, counter_prefix: &str) {
+    for (state, counter) in CPU_STATES_COUNTERS {
+        let mut full = format!(r"\Processor({instance})\{counter}")
+            .encode_utf16()
+            .collect::<Vec<_>>();
+        full.push(0);
+        query.add_english_counter(&format!("{counter_prefix}_{state}"), full);
+    }
+}
+
+pub(crate) fn refresh_cpu_states_usage(query: &Query, cpu: &mut Cpu, counter_prefix: &str) {
+    let get_usage = |state: &str| {
+        query
+            .get(&format!("{counter_prefix}_{state}"))
+            .unwrap_or(0.)
+    };
+    cpu.set_states_usage(get_usage("user"), get_usage("system"), get_usage("idle"));
+}
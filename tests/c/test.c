@@ -0,0 +1,133 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+// Checks the C interface through the cdylib. Run it with `make test-c`.
+
+#undef NDEBUG
+
+#include <assert.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/types.h>
+#include <unistd.h>
+#include "sysinfo.h"
+
+static bool count_processes(pid_t pid, CProcess process, void *data) {
+    size_t *count = data;
+
+    assert(process != NULL);
+    assert(sysinfo_process_pid(process) == pid);
+    *count += 1;
+    return true;
+}
+
+static bool stop_at_first(pid_t pid, CProcess process, void *data) {
+    (void)pid;
+    (void)process;
+    *(size_t *)data += 1;
+    return false;
+}
+
+static void check_memory(CSystem system) {
+    assert(sysinfo_total_memory(system) > 0);
+    assert(sysinfo_used_memory(system) <= sysinfo_total_memory(system));
+    assert(sysinfo_free_memory(system) <= sysinfo_total_memory(system));
+    assert(sysinfo_used_swap(system) <= sysinfo_total_swap(system));
+    assert(sysinfo_free_swap(system) <= sysinfo_total_swap(system));
+}
+
+static void check_cpus(CSystem system) {
+    unsigned int length = 0;
+    float *cpus = NULL;
+
+    sysinfo_cpus_usage(system, &length, &cpus);
+    assert(cpus != NULL);
+    for (unsigned int i = 0; i < length; ++i) {
+        assert(cpus[i] >= 0.);
+    }
+    free(cpus);
+}
+
+static void check_processes(CSystem system) {
+    size_t count = 0;
+
+    assert(sysinfo_processes(system, count_processes, &count) == count);
+    assert(count > 0);
+    count = 0;
+    assert(sysinfo_processes(system, stop_at_first, &count) > 0);
+    assert(count == 1);
+    assert(sysinfo_processes(system, NULL, NULL) == 0);
+}
+
+static void check_current_process(CSystem system) {
+    CProcess process = sysinfo_process_by_pid(system, getpid());
+
+    assert(process != NULL);
+    assert(sysinfo_process_pid(process) == getpid());
+    assert(sysinfo_process_parent_pid(process) == getppid());
+    assert(sysinfo_process_memory(process) > 0);
+
+    RString exe = sysinfo_process_executable_path(process);
+    assert(exe != NULL);
+    assert(strstr(exe, "c_test") != NULL);
+    sysinfo_rstring_free(exe);
+
+    char cwd[4096];
+    assert(getcwd(cwd, sizeof(cwd)) != NULL);
+    RString current_dir = sysinfo_process_current_directory(process);
+    assert(current_dir != NULL);
+    assert(strcmp(current_dir, cwd) == 0);
+    sysinfo_rstring_free(current_dir);
+
+    RString root = sysinfo_process_root_directory(process);
+    assert(root != NULL);
+    sysinfo_rstring_free(root);
+
+    assert(sysinfo_process_by_pid(system, -1) == NULL);
+}
+
+#ifdef __linux__
+static void *wait_for_pipe(void *data) {
+    char c;
+
+    // Blocks until the main thread closes the pipe.
+    (void)read(*(int *)data, &c, 1);
+    return NULL;
+}
+
+static void check_tasks(CSystem system) {
+    int fds[2];
+    pthread_t thread;
+    size_t count = 0;
+
+    assert(pipe(fds) == 0);
+    assert(pthread_create(&thread, NULL, wait_for_pipe, &fds[0]) == 0);
+    sysinfo_refresh_process(system, getpid());
+    CProcess process = sysinfo_process_by_pid(system, getpid());
+    assert(process != NULL);
+    assert(sysinfo_process_tasks(process, count_processes, &count) == count);
+    // The main thread isn't part of the tasks.
+    assert(count >= 1);
+    close(fds[1]);
+    assert(pthread_join(thread, NULL) == 0);
+    close(fds[0]);
+}
+#endif
+
+int main() {
+    CSystem system = sysinfo_init();
+
+    assert(system != NULL);
+    sysinfo_refresh_all(system);
+    check_memory(system);
+    check_cpus(system);
+    check_processes(system);
+    check_current_process(system);
+#ifdef __linux__
+    check_tasks(system);
+#endif
+    sysinfo_destroy(system);
+    printf("C interface: OK\n");
+    return 0;
+}
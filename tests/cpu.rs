@@ -30,3 +30,141 @@ fn test_physical_core_numbers() {
         assert!(count.unwrap() > 0);
     }
 }
+
+#[test]
+fn test_cpu_usage_history() {
+    use sysinfo::{CpuExt, SystemExt};
+
+    if !sysinfo::System::IS_SUPPORTED {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_cpu();
+    // Disabled by default.
+    assert!(s.global_cpu_info().usage_history().is_empty());
+
+    s.set_cpu_usage_history_capacity(3);
+    let mut prev = 0;
+    for _ in 0..5 {
+        s.refresh_cpu();
+        let len = s.global_cpu_info().usage_history().len();
+        assert!(len >= prev && len <= 3);
+        prev = len;
+        for cpu in s.cpus() {
+            assert!(cpu.usage_history().len() <= 3);
+        }
+    }
+    assert_eq!(prev, 3);
+
+    s.set_cpu_usage_history_capacity(1);
+    assert_eq!(s.global_cpu_info().usage_history().len(), 1);
+}
+
+#[test]
+fn test_cpu_states_usage() {
+    use sysinfo::{CpuExt, SystemExt};
+
+    if !sysinfo::System::IS_SUPPORTED {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_cpu();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    s.refresh_cpu();
+
+    for cpu in std::iter::once(s.global_cpu_info()).chain(s.cpus()) {
+        let states = [
+            cpu.user_usage(),
+            cpu.system_usage(),
+            cpu.nice_usage(),
+            cpu.idle_usage(),
+        ];
+        assert!(states.iter().all(|usage| (0. ..=100.).contains(usage)));
+        assert!(states.iter().sum::<f32>() <= 101.);
+    }
+    let global = s.global_cpu_info();
+    assert!(global.user_usage() + global.system_usage() + global.idle_usage() > 0.);
+}
+
+#[test]
+fn test_context_switches_and_interrupts() {
+    use sysinfo::SystemExt;
+
+    let mut s = sysinfo::System::new();
+    if !sysinfo::System::IS_SUPPORTED || cfg!(any(target_os = "macos", target_os = "ios")) {
+        assert_eq!(s.context_switches(), None);
+        assert_eq!(s.interrupts(), None);
+        return;
+    }
+    s.refresh_cpu();
+    // Two refreshes are needed to get a delta.
+    assert_eq!(s.context_switches(), Some(0));
+    assert_eq!(s.interrupts(), Some(0));
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    s.refresh_cpu();
+    assert!(s.context_switches() > Some(0));
+}
+
+#[test]
+fn test_cpu_count() {
+    use sysinfo::SystemExt;
+
+    let mut s = sysinfo::System::new();
+    if !sysinfo::System::IS_SUPPORTED {
+        assert_eq!(s.cpu_count(), 0);
+        return;
+    }
+    let count = s.cpu_count();
+    assert!(count > 0);
+    // The CPUs list isn't populated.
+    assert!(s.cpus().is_empty());
+
+    s.refresh_cpu();
+    assert_eq!(s.cpus().len(), count);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_cpus_order() {
+    use sysinfo::{CpuExt, SystemExt};
+
+    if !sysinfo::System::IS_SUPPORTED {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_cpu();
+    let indexes = s
+        .cpus()
+        .iter()
+        .map(|cpu| {
+            cpu.name()
+                .strip_prefix("cpu")
+                .and_then(|index| index.parse::<usize>().ok())
+                .expect("invalid CPU name")
+        })
+        .collect::<Vec<_>>();
+    assert!(!indexes.is_empty());
+    assert!(indexes.windows(2).all(|w| w[0] < w[1]));
+
+    s.refresh_cpu();
+    assert_eq!(s.cpus().len(), indexes.len());
+}
+
+#[test]
+fn test_refresh_cpu_over() {
+    use std::time::{Duration, Instant};
+    use sysinfo::{CpuExt, SystemExt};
+
+    let mut s = sysinfo::System::new();
+    let window = Duration::from_millis(200);
+    let start = Instant::now();
+    s.refresh_cpu_over(window);
+    assert!(start.elapsed() >= window);
+    if sysinfo::System::IS_SUPPORTED {
+        assert!(!s.cpus().is_empty());
+        for cpu in s.cpus() {
+            assert!((0. ..=100.).contains(&cpu.cpu_usage()));
+        }
+    }
+}
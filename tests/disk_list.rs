@@ -12,3 +12,150 @@ fn test_disks() {
         }
     }
 }
+
+#[test]
+fn test_refresh_disk() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    let mut s = sysinfo::System::new();
+    assert!(!s.refresh_disk("/".as_ref()));
+
+    if sysinfo::System::IS_SUPPORTED {
+        s.refresh_disks_list();
+        let mount_points = s
+            .disks()
+            .iter()
+            .map(|disk| disk.mount_point().to_path_buf())
+            .collect::<Vec<_>>();
+        for mount_point in mount_points {
+            assert!(s.refresh_disk(&mount_point));
+        }
+        assert!(s.disks().iter().all(|disk| !disk.last_refresh_failed()));
+    }
+}
+
+#[test]
+fn test_disk_mount_options() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    if !sysinfo::System::IS_SUPPORTED || cfg!(target_os = "windows") {
+        return;
+    }
+    let s = sysinfo::System::new_all();
+    for disk in s.disks() {
+        let options = disk.mount_options();
+        assert!(
+            options.iter().any(|o| o == "rw" || o == "ro"),
+            "{:?}: {:?}",
+            disk.mount_point(),
+            options
+        );
+    }
+}
+
+// `refresh_disks_list_local_only` excludes the pseudo file systems and the default list
+// (`refresh_disks_list`) is between it and the list with all file systems.
+#[test]
+fn test_disks_list_local_only_excludes_pseudo_file_systems() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    let mut s = sysinfo::System::new();
+    s.refresh_disks_list_local_only();
+    for disk in s.disks() {
+        assert!(
+            !matches!(disk.file_system(), b"devfs" | b"autofs"),
+            "{:?}",
+            disk.mount_point()
+        );
+    }
+
+    let local_count = s.disks().len();
+    s.refresh_disks_list();
+    let count = s.disks().len();
+    assert!(count >= local_count);
+    s.refresh_disks_list_with_all_file_systems();
+    assert!(s.disks().len() >= count);
+}
+
+#[test]
+fn test_file_systems() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    let mut s = sysinfo::System::new();
+    s.refresh_disks_list();
+    let file_systems = s.file_systems();
+    // The disks list isn't modified.
+    assert!(file_systems.len() >= s.disks().len());
+    for disk in s.disks() {
+        assert!(file_systems
+            .iter()
+            .any(|fs| fs.mount_point() == disk.mount_point()));
+    }
+}
+
+#[test]
+fn test_disk_model_and_serial() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    let s = sysinfo::System::new_all();
+    for disk in s.disks() {
+        // Not all devices expose this information but it should never contain extra whitespace.
+        assert_eq!(disk.model(), disk.model().trim());
+        assert_eq!(disk.serial(), disk.serial().trim());
+    }
+}
+
+#[test]
+fn test_disk_free_space() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    // On Apple targets, the available space also contains the purgeable space, so it can be
+    // greater than the free space.
+    if !sysinfo::System::IS_SUPPORTED || cfg!(target_vendor = "apple") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_disks_list();
+    for disk in s.disks() {
+        assert!(disk.free_space() >= disk.available_space());
+        assert!(disk.free_space() <= disk.total_space());
+    }
+}
+
+#[test]
+fn test_disk_inodes() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    let mut s = sysinfo::System::new();
+    s.refresh_disks_list();
+    for disk in s.disks() {
+        assert!(disk.available_inodes() <= disk.total_inodes());
+    }
+}
+
+#[test]
+fn test_disk_for_path() {
+    use sysinfo::{DiskExt, SystemExt};
+
+    let mut s = sysinfo::System::new();
+    let current_dir = std::env::current_dir().expect("failed to get current directory");
+    // The disks list wasn't refreshed yet.
+    assert!(s.disk_for_path(&current_dir).is_none());
+
+    if !sysinfo::System::IS_SUPPORTED {
+        return;
+    }
+    s.refresh_disks_list();
+    assert!(s.disk_for_path("does/not/exist".as_ref()).is_none());
+    let disk = s
+        .disk_for_path(&current_dir)
+        .expect("no disk found for the current directory");
+    assert!(current_dir.starts_with(disk.mount_point()));
+    // No other disk has a longer mount point containing the path.
+    assert!(s.disks().iter().all(|other| {
+        !current_dir.starts_with(other.mount_point())
+            || other.mount_point().as_os_str().len() <= disk.mount_point().as_os_str().len()
+    }));
+    // Relative paths work too.
+    assert!(s.disk_for_path(".".as_ref()).is_some());
+}
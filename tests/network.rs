@@ -13,3 +13,19 @@ fn test_networks() {
         assert!(s.networks().iter().count() > 0);
     }
 }
+
+#[test]
+fn test_networks_refresh_kind() {
+    use sysinfo::{NetworkExt, NetworksExt, RefreshKind, SystemExt};
+
+    let refresh_kind = RefreshKind::everything()
+        .without_networks()
+        .without_networks_list();
+    let mut s = sysinfo::System::new_with_specifics(refresh_kind);
+    s.refresh_specifics(refresh_kind);
+    // Without networks, nothing should have been retrieved.
+    assert!(s.networks().iter().all(|(_, data)| data.received() == 0));
+    if sysinfo::System::IS_SUPPORTED {
+        assert_eq!(s.networks().iter().count(), 0);
+    }
+}
@@ -1,6 +1,6 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use sysinfo::{Pid, PidExt, ProcessExt, SystemExt};
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, SystemExt};
 
 #[test]
 fn test_process() {
@@ -381,6 +381,24 @@ fn test_refresh_process() {
     assert!(s.process(pid).is_some());
 }
 
+#[test]
+fn test_last_errors() {
+    use sysinfo::Error;
+
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    // There is very little chance that such a process exists.
+    let pid = Pid::from_u32(0x7fff_fff1);
+    let mut s = sysinfo::System::new();
+    assert!(!s.refresh_process(pid));
+    assert_eq!(s.last_errors().get(&pid), Some(&Error::NotFound));
+
+    // Errors are reset on each refresh.
+    s.refresh_process(sysinfo::get_current_pid().expect("failed to get current pid"));
+    assert!(s.last_errors().is_empty());
+}
+
 #[test]
 fn test_wait_child() {
     if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
@@ -455,3 +473,457 @@ fn test_wait_non_child() {
     assert!(before.elapsed() > std::time::Duration::from_millis(2000));
     assert!(before.elapsed() < std::time::Duration::from_millis(3000));
 }
+
+#[test]
+fn test_process_tracer() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let p = s.process(pid).expect("Process not found!");
+
+    if cfg!(target_os = "linux") {
+        assert!(p.is_being_traced().is_some());
+    }
+    // If we have a tracer, we are traced.
+    if p.tracer_pid().is_some() {
+        assert_eq!(p.is_being_traced(), Some(true));
+    }
+}
+
+#[test]
+fn test_processes_iter() {
+    let mut s = sysinfo::System::new();
+    assert_eq!(s.processes_iter().count(), 0);
+    s.refresh_processes();
+    assert_eq!(s.processes_iter().count(), s.processes().len());
+    for (pid, process) in s.processes_iter() {
+        assert_eq!(pid, process.pid());
+    }
+}
+
+#[test]
+fn test_process_open_files() {
+    if !sysinfo::System::IS_SUPPORTED
+        || cfg!(feature = "apple-sandbox")
+        || cfg!(target_os = "freebsd")
+    {
+        return;
+    }
+    let _file = std::fs::File::open("Cargo.toml").unwrap();
+    let mut s = sysinfo::System::new();
+    s.refresh_processes_specifics(ProcessRefreshKind::everything().with_open_files());
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    assert!(p.open_files().unwrap_or(0) > 0);
+
+    // It's only retrieved when asked.
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    assert_eq!(p.open_files(), None);
+}
+
+// The current process shares its own network namespace so it shouldn't report any usage.
+#[test]
+fn test_process_network_usage() {
+    if !sysinfo::System::IS_SUPPORTED {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes_specifics(ProcessRefreshKind::everything().with_network_usage());
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    assert_eq!(p.network_usage(), None);
+}
+
+#[test]
+fn test_process_without_cmd() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes_specifics(sysinfo::ProcessRefreshKind::new().without_cmd());
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    assert!(p.cmd().is_empty());
+    assert!(p.environ().is_empty());
+    assert!(!p.name().is_empty());
+}
+
+#[test]
+fn test_process_average_cpu_usage() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    for p in s.processes().values() {
+        let average = p.average_cpu_usage();
+        if p.run_time() == 0 {
+            assert_eq!(average, 0.);
+        } else {
+            assert!(average >= 0.);
+            assert!((average - p.cpu_time() as f32 / p.run_time() as f32 / 10.).abs() < 0.01);
+        }
+    }
+}
+
+#[test]
+fn test_process_gpu_usage() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    // At least two refreshes are needed.
+    assert!(s.processes().values().all(|p| p.gpu_usage().is_none()));
+    s.refresh_processes();
+    for p in s.processes().values() {
+        if cfg!(target_os = "macos") {
+            assert!(p.gpu_usage().map_or(true, |usage| usage >= 0.));
+        } else {
+            assert_eq!(p.gpu_usage(), None);
+        }
+    }
+}
+
+#[test]
+fn test_process_page_faults() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    // Any running process has triggered some page faults.
+    assert!(p.minor_faults() > 0);
+}
+
+#[test]
+fn test_process_is_64bit() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    assert_eq!(p.is_64bit(), Some(cfg!(target_pointer_width = "64")));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_process_real_ids() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    unsafe {
+        if cfg!(target_os = "freebsd") {
+            assert_eq!(p.user_id().map(|u| **u), Some(libc::getuid()));
+            assert_eq!(p.group_id().map(|g| *g), Some(libc::getgid()));
+        } else {
+            assert_eq!(p.user_id().map(|u| **u), Some(libc::geteuid()));
+            assert_eq!(p.group_id().map(|g| *g), Some(libc::getegid()));
+        }
+        assert_eq!(p.real_user_id().map(|u| **u), Some(libc::getuid()));
+        assert_eq!(p.real_group_id().map(|g| *g), Some(libc::getgid()));
+    }
+}
+
+#[test]
+fn test_process_thread_count() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let p = s
+        .process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    assert!(p.thread_count() >= 1);
+}
+
+// The kernel truncates the process names to 16 characters on macOS.
+#[test]
+fn test_process_long_name() {
+    if !cfg!(target_os = "macos") || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    // `tempfile` is only a dev-dependency on Linux.
+    let exe = std::env::temp_dir().join("sysinfo_process_with_a_long_name");
+    std::fs::copy("/bin/sleep", &exe).expect("failed to copy sleep");
+    let mut child = std::process::Command::new(&exe)
+        .arg("3")
+        .spawn()
+        .expect("failed to spawn process");
+
+    let pid = Pid::from_u32(child.id() as _);
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    child.kill().expect("Unable to kill process.");
+    let _ = child.wait();
+    let _ = std::fs::remove_file(&exe);
+
+    let p = s.process(pid).expect("didn't find process");
+    assert_eq!(p.name(), "sysinfo_process_with_a_long_name");
+}
+
+#[test]
+fn test_process_threads() {
+    if !sysinfo::System::IS_SUPPORTED
+        || cfg!(feature = "apple-sandbox")
+        || !cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos"
+        ))
+    {
+        return;
+    }
+    let current_pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process_specifics(current_pid, ProcessRefreshKind::new().with_threads());
+    let p = s.process(current_pid).expect("didn't find process");
+    let threads = p.threads();
+    assert!(!threads.is_empty());
+    if cfg!(any(target_os = "linux", target_os = "android")) {
+        // The identifier of the main thread is the PID.
+        assert!(threads
+            .iter()
+            .any(|t| t.tid() == current_pid.as_u32() as u64));
+    }
+
+    s.refresh_process_specifics(current_pid, ProcessRefreshKind::new().without_threads());
+    let p = s.process(current_pid).expect("didn't find process");
+    if cfg!(target_os = "macos") {
+        assert!(p.threads().is_empty());
+    }
+}
+
+#[test]
+fn test_refresh_pids() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let current_pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_pids(&[current_pid, Pid::from_u32(u32::MAX)]);
+    // Only the existing process should have been added.
+    assert!(s.process(current_pid).is_some());
+    assert_eq!(s.processes().len(), 1);
+
+    // Processes which weren't asked for should be left untouched.
+    s.refresh_processes();
+    let count = s.processes().len();
+    s.refresh_pids_specifics(&[current_pid], sysinfo::ProcessRefreshKind::new());
+    assert_eq!(s.processes().len(), count);
+    assert!(s.process(current_pid).is_some());
+}
+
+#[test]
+fn test_process_cpu_time() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    // Burn some CPU time so the value can't be `0`.
+    let start = std::time::Instant::now();
+    let mut x = 0u64;
+    while start.elapsed() < std::time::Duration::from_millis(200) {
+        x = x.wrapping_add(1);
+    }
+    assert!(x > 0);
+
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let cpu_time = s.process(pid).expect("didn't find process").cpu_time();
+    assert!(cpu_time > 0);
+
+    // It's a cumulative value so it can't go backward.
+    s.refresh_processes();
+    assert!(s.process(pid).expect("didn't find process").cpu_time() >= cpu_time);
+}
+
+#[test]
+fn test_process_refresh_interval() {
+    let mut s = sysinfo::System::new();
+    // By default, the processes are always refreshed.
+    s.refresh_processes();
+    assert!(!s.processes_refresh_skipped());
+    s.refresh_processes();
+    assert!(!s.processes_refresh_skipped());
+
+    if !sysinfo::System::IS_SUPPORTED {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.set_process_refresh_interval(std::time::Duration::from_secs(3600));
+    // The first refresh always happens.
+    s.refresh_processes();
+    assert!(!s.processes_refresh_skipped());
+    // Called again too soon, so it's skipped.
+    s.refresh_processes();
+    assert!(s.processes_refresh_skipped());
+
+    s.set_process_refresh_interval(std::time::Duration::from_secs(0));
+    s.refresh_processes();
+    assert!(!s.processes_refresh_skipped());
+}
+
+#[test]
+fn test_refresh_and_get_process() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let current_pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    assert_eq!(
+        s.refresh_and_get_process(current_pid).map(|p| p.pid()),
+        Some(current_pid)
+    );
+    assert!(s.refresh_and_get_process(Pid::from_u32(u32::MAX)).is_none());
+}
+
+#[test]
+fn test_process_nice() {
+    if !sysinfo::System::IS_SUPPORTED
+        || cfg!(feature = "apple-sandbox")
+        || cfg!(target_os = "windows")
+    {
+        return;
+    }
+    let mut p = std::process::Command::new("nice")
+        .arg("-n")
+        .arg("5")
+        .arg("sleep")
+        .arg("300")
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid = Pid::from_u32(p.id() as _);
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let nice = s.process(pid).map(|p| p.nice());
+    p.kill().expect("Unable to kill process.");
+    let _ = p.wait();
+    // The test process could itself have a nice value greater than 0.
+    assert!(nice.unwrap_or(0) >= 5);
+}
+
+#[test]
+fn test_process_set_nice() {
+    if !sysinfo::System::IS_SUPPORTED
+        || cfg!(feature = "apple-sandbox")
+        || cfg!(target_os = "windows")
+    {
+        return;
+    }
+    let mut p = std::process::Command::new("sleep")
+        .arg("300")
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid = Pid::from_u32(p.id() as _);
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    // Increasing the nice value doesn't require any specific rights. The value is clamped.
+    let updated = s.process(pid).map(|p| p.set_nice(100));
+    s.refresh_process(pid);
+    let nice = s.process(pid).map(|p| p.nice());
+    p.kill().expect("Unable to kill process.");
+    let _ = p.wait();
+    assert_eq!(updated, Some(true));
+    assert_eq!(nice, Some(19));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_process_group_and_session_ids() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    let p = s
+        .refresh_and_get_process(sysinfo::get_current_pid().expect("failed to get current pid"))
+        .expect("didn't find process");
+    unsafe {
+        assert_eq!(
+            p.process_group_id().map(|pid| pid.as_u32()),
+            Some(libc::getpgrp() as u32)
+        );
+        assert_eq!(
+            p.session_id().map(|pid| pid.as_u32()),
+            Some(libc::getsid(0) as u32)
+        );
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn test_wait_exit_code() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut p = std::process::Command::new("sh")
+        .arg("-c")
+        .arg("sleep 1; exit 3")
+        .spawn()
+        .unwrap();
+    let pid = Pid::from_u32(p.id() as _);
+
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let process = s.process(pid).expect("process not found");
+    assert_eq!(process.wait_status(), Some(3));
+    // The process was already reaped by `wait_status`.
+    let _ = p.wait();
+}
+
+#[test]
+fn test_process_by_port() {
+    use sysinfo::Protocol;
+
+    if !sysinfo::System::IS_SUPPORTED
+        || cfg!(feature = "apple-sandbox")
+        || !cfg!(any(target_os = "linux", target_os = "macos"))
+    {
+        return;
+    }
+    let tcp = std::net::TcpListener::bind("127.0.0.1:0").expect("failed to bind TCP socket");
+    let tcp_port = tcp.local_addr().expect("failed to get TCP address").port();
+    let udp = std::net::UdpSocket::bind("127.0.0.1:0").expect("failed to bind UDP socket");
+    let udp_port = udp.local_addr().expect("failed to get UDP address").port();
+
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    assert_eq!(
+        s.process_by_port(tcp_port, Protocol::Tcp).map(|p| p.pid()),
+        Some(pid)
+    );
+    assert_eq!(
+        s.process_by_port(udp_port, Protocol::Udp).map(|p| p.pid()),
+        Some(pid)
+    );
+}